@@ -175,6 +175,16 @@ RUST_LIB_FLAGS_ST3 += -C prefer-dynamic
 # by not emitting them.
 RUSTFLAGS_STAGE0 += -Z no-landing-pads
 
+# The snapshot compiler doesn't know -C split-debuginfo, so only the outputs of
+# stage1 and later compilers are split. `make install` installs the .debug files
+# next to them.
+ifdef CFG_ENABLE_SPLIT_DEBUGINFO
+  $(info cfg: splitting debuginfo (CFG_ENABLE_SPLIT_DEBUGINFO))
+  RUSTFLAGS_STAGE1 += -C split-debuginfo
+  RUSTFLAGS_STAGE2 += -C split-debuginfo
+  RUSTFLAGS_STAGE3 += -C split-debuginfo
+endif
+
 # platform-specific auto-configuration
 include $(CFG_SRC_DIR)mk/platform.mk
 
@@ -273,10 +283,10 @@ endif
 
 # FIXME: x86-ism
 LLVM_COMPONENTS=x86 arm aarch64 mips powerpc ipo bitreader bitwriter linker asmparser mcjit \
-                interpreter instrumentation
+                interpreter instrumentation profiledata
 
 # Only build these LLVM tools
-LLVM_TOOLS=bugpoint llc llvm-ar llvm-as llvm-dis llvm-mc opt llvm-extract
+LLVM_TOOLS=bugpoint llc llvm-ar llvm-as llvm-dis llvm-mc opt llvm-extract llvm-profdata
 
 define DEF_LLVM_VARS
 # The configure script defines these variables with the target triples
@@ -396,7 +406,9 @@ endif
 TSREQ$(1)_T_$(2)_H_$(3) = \
 	$$(HSREQ$(1)_H_$(3)) \
 	$$(TLIB$(1)_T_$(2)_H_$(3))/libmorestack.a \
-	$$(TLIB$(1)_T_$(2)_H_$(3))/libcompiler-rt.a
+	$$(TLIB$(1)_T_$(2)_H_$(3))/libcompiler-rt.a \
+	$$(foreach lib,$$(COMPRT_CLANG_RT_LIBS_$(2)),$$(TLIB$(1)_T_$(2)_H_$(3))/$$(lib)) \
+	$$(foreach obj,$$(INSTALLED_OBJECTS_$(2)),$$(TLIB$(1)_T_$(2)_H_$(3))/$$(obj))
 
 # Prerequisites for a working stageN compiler and libraries, for a specific
 # target
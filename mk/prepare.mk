@@ -34,14 +34,19 @@ define PREPARE_DIR
 	$(Q)$(PREPARE_DIR_CMD) $(1)
 endef
 
-# Copy an executable
+# Copy an executable, along with the .debug file -C split-debuginfo left next
+# to it, if any
 # $(1) is the filename/libname-glob
 define PREPARE_BIN
 	@$(call E, prepare: $(PREPARE_DEST_BIN_DIR)/$(1))
 	$(Q)$(PREPARE_BIN_CMD) $(PREPARE_SOURCE_BIN_DIR)/$(1) $(PREPARE_DEST_BIN_DIR)/$(1)
+	$(Q)if [ -f $(PREPARE_SOURCE_BIN_DIR)/$(1).debug ]; then \
+	  $(PREPARE_LIB_CMD) $(PREPARE_SOURCE_BIN_DIR)/$(1).debug $(PREPARE_DEST_BIN_DIR)/$(1).debug; \
+	fi
 endef
 
-# Copy a dylib or rlib
+# Copy a dylib or rlib, along with the .debug file -C split-debuginfo left next
+# to it, if any
 # $(1) is the filename/libname-glob
 #
 # XXX: Don't remove the $(nop) command below!
@@ -61,6 +66,10 @@ define PREPARE_LIB
 	  echo $$MATCHES ; \
 	fi
 	$(Q)$(PREPARE_LIB_CMD) `ls -drt1 $(PREPARE_WORKING_SOURCE_LIB_DIR)/$(1) | tail -1` $(PREPARE_WORKING_DEST_LIB_DIR)/
+	$(Q)LIB=`ls -drt1 $(PREPARE_WORKING_SOURCE_LIB_DIR)/$(1) | tail -1`; \
+	if [ -f "$$LIB.debug" ]; then \
+	  $(PREPARE_LIB_CMD) "$$LIB.debug" $(PREPARE_WORKING_DEST_LIB_DIR)/; \
+	fi
 endef
 
 # Copy a man page
@@ -141,7 +150,9 @@ prepare-target-$(2)-host-$(3)-$(1)-$(4): prepare-maybe-clean-$(4) \
             $$(foreach crate,$$(HOST_CRATES), \
               $$(call PREPARE_LIB,$$(call CFG_LIB_GLOB_$(2),$$(crate)))),) \
           $$(call PREPARE_LIB,libmorestack.a) \
-          $$(call PREPARE_LIB,libcompiler-rt.a),),),)
+          $$(call PREPARE_LIB,libcompiler-rt.a) \
+          $$(foreach lib,$$(COMPRT_CLANG_RT_LIBS_$(2)),$$(call PREPARE_LIB,$$(lib))) \
+          $$(foreach obj,$$(INSTALLED_OBJECTS_$(2)),$$(call PREPARE_LIB,$$(obj))),),),)
 endef
 
 define INSTALL_GDB_DEBUGGER_SCRIPTS_COMMANDS
@@ -58,6 +58,9 @@ NATIVE_DEPS_rustrt_native_$(1) := \
 			arch/$$(HOST_$(1))/record_sp.S
 NATIVE_DEPS_rust_test_helpers_$(1) := rust_test_helpers.c
 NATIVE_DEPS_morestack_$(1) := arch/$$(HOST_$(1))/morestack.S
+ifeq ($$(HOST_$(1)),x86_64)
+NATIVE_DEPS_morestack_$(1) += arch/x86_64/probestack.S
+endif
 
 
 ################################################################################
@@ -238,6 +241,31 @@ $$(COMPRT_LIB_$(1)): $$(COMPRT_DEPS) $$(MKFILE_DEPS)
 		triple-builtins
 	$$(Q)cp $$(COMPRT_BUILD_DIR_$(1))/triple/builtins/libcompiler_rt.a $$(COMPRT_LIB_$(1))
 
+# The sanitizer runtimes linked in by `-Z sanitizer`, and the profiling
+# runtime linked in by `-C coverage`. compiler-rt's makefile build only
+# provides them for Linux, and the sanitizers only for x86_64, which are
+# also the only targets rustc accepts the options for.
+ifeq ($(1),x86_64-unknown-linux-gnu)
+COMPRT_CLANG_RT_LIBS_$(1) := \
+	$$(foreach san,asan tsan msan lsan,libclang_rt.$$(san)-x86_64.a) \
+	libclang_rt.profile-x86_64.a
+else ifeq ($(1),i686-unknown-linux-gnu)
+COMPRT_CLANG_RT_LIBS_$(1) := libclang_rt.profile-i386.a
+else
+COMPRT_CLANG_RT_LIBS_$(1) :=
+endif
+
+$$(RT_OUTPUT_DIR_$(1))/libclang_rt.%.a: $$(COMPRT_DEPS) $$(MKFILE_DEPS)
+	@$$(call E, make: compiler-rt $$*)
+	$$(Q)$$(MAKE) -C "$(S)src/compiler-rt" \
+		ProjSrcRoot="$(S)src/compiler-rt" \
+		ProjObjRoot="$$(abspath $$(COMPRT_BUILD_DIR_$(1)))" \
+		CC="$$(CC_$(1))" \
+		AR="$$(AR_$(1))" \
+		RANLIB="$$(AR_$(1)) s" \
+		clang_linux
+	$$(Q)cp $$(COMPRT_BUILD_DIR_$(1))/clang_linux/$$*/libcompiler_rt.a $$@
+
 ################################################################################
 # libbacktrace
 #
@@ -312,6 +340,31 @@ endif # endif for windowsy
 endif # endif for ios
 endif # endif for darwin
 
+################################################################################
+# musl
+#
+# Targets linking against musl produce static executables, so the C library,
+# an unwinder and the C runtime startup objects are all taken from the musl
+# installation (see --musl-root) and shipped along with the Rust libraries.
+# musl itself comes without an unwinder, so unless a libunwind.a was installed
+# next to it, the libgcc_eh.a of the C compiler, which provides the same
+# _Unwind_* functions, is shipped as libunwind.a.
+################################################################################
+
+ifeq ($$(findstring musl,$(1)),musl)
+$$(foreach file,libc.a $$(INSTALLED_OBJECTS_$(1)), \
+  $$(RT_OUTPUT_DIR_$(1))/$$(file)): \
+    $$(RT_OUTPUT_DIR_$(1))/%: $$(CFG_MUSL_ROOT)/lib/%
+	@$$(call E, cp: $$@)
+	$$(Q)cp $$< $$@
+
+MUSL_UNWIND_$(1) := $$(firstword $$(wildcard $$(CFG_MUSL_ROOT)/lib/libunwind.a) \
+                      $$(shell $$(CC_$(1)) -print-file-name=libgcc_eh.a))
+$$(RT_OUTPUT_DIR_$(1))/libunwind.a: $$(MUSL_UNWIND_$(1))
+	@$$(call E, cp: $$@)
+	$$(Q)cp $$< $$@
+endif
+
 endef
 
 # Instantiate template for all stages/targets
@@ -166,6 +166,12 @@ pub fn is_test_ignored(config: &Config, testfile: &Path) -> bool {
     fn ignore_architecture(config: &Config) -> String {
         format!("ignore-{}", util::get_arch(&config.target))
     }
+    fn ignore_env(config: &Config, line: &str) -> bool {
+        match util::get_env(&config.target) {
+            Some(env) => parse_name_directive(line, &format!("ignore-{}", env)),
+            None => false,
+        }
+    }
     fn ignore_stage(config: &Config) -> String {
         format!("ignore-{}",
                 config.stage_id.split('-').next().unwrap())
@@ -230,6 +236,7 @@ pub fn is_test_ignored(config: &Config, testfile: &Path) -> bool {
         !parse_name_directive(ln, "ignore-test") &&
         !parse_name_directive(ln, &ignore_target(config)) &&
         !parse_name_directive(ln, &ignore_architecture(config)) &&
+        !ignore_env(config, ln) &&
         !parse_name_directive(ln, &ignore_stage(config)) &&
         !(config.mode == common::Pretty && parse_name_directive(ln, "ignore-pretty")) &&
         !(config.target != config.host && parse_name_directive(ln, "ignore-cross-compile")) &&
@@ -17,6 +17,7 @@ use errors;
 use header::TestProps;
 use header;
 use procsrv;
+use util;
 use util::logv;
 
 use std::env;
@@ -1217,8 +1218,17 @@ fn compose_and_run_compiler(config: &Config, props: &TestProps,
     for rel_ab in &props.aux_builds {
         let abs_ab = config.aux_base.join(rel_ab);
         let aux_props = header::load_props(&abs_ab);
+        let aux_target = if aux_props.force_host {
+            &*config.host
+        } else {
+            &*config.target
+        };
         let mut crate_type = if aux_props.no_prefer_dynamic {
             Vec::new()
+        } else if !util::supports_dylibs(aux_target) {
+            // Targets without dynamic linking get the auxiliary crates as
+            // rlibs, which is what `prefer-dynamic` falls back to anyway.
+            vec!("--crate-type=lib".to_string())
         } else {
             vec!("--crate-type=dylib".to_string())
         };
@@ -1304,7 +1314,7 @@ fn make_compile_args<F>(config: &Config,
                         config.build_base.to_str().unwrap().to_string(),
                         format!("--target={}", target));
     args.push_all(&extras);
-    if !props.no_prefer_dynamic {
+    if !props.no_prefer_dynamic && util::supports_dylibs(target) {
         args.push("-C".to_string());
         args.push("prefer-dynamic".to_string());
     }
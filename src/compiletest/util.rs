@@ -60,6 +60,17 @@ pub fn get_arch(triple: &str) -> &'static str {
     panic!("Cannot determine Architecture from triple");
 }
 
+/// Returns the environment (e.g. `gnu` or `musl`) component of a target
+/// triple, if it has one.
+pub fn get_env(triple: &str) -> Option<&str> {
+    triple.split('-').nth(3)
+}
+
+/// Whether dynamic libraries can be produced for the given target.
+pub fn supports_dylibs(triple: &str) -> bool {
+    get_env(triple) != Some("musl")
+}
+
 pub fn make_new_path(path: &str) -> String {
     assert!(cfg!(windows));
     // Windows just uses PATH as the library search path, so we have to
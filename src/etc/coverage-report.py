@@ -0,0 +1,160 @@
+#!/usr/bin/env python
+#
+# Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+"""
+Turns the counts written by a program built with `-C coverage` into a
+per-line report of the Rust sources it was built from.
+
+    coverage-report.py [--src DIR] [--gcov PROG] [--annotate] PATH...
+
+Each PATH is a `.gcda` file, or a directory searched for them. The counts
+of all of them are added up, so a library and the test binaries that
+exercise it can be reported on together. Only sources under DIR (the
+current directory by default) are reported on.
+
+For each source file, the report gives the number of lines that ran at
+least once out of the lines that have code. With `--annotate`, every line
+of the file follows, prefixed with how many times it ran, `#####` if it
+never did, or `-` if it has no code.
+"""
+
+import argparse
+import os
+import shlex
+import shutil
+import subprocess
+import sys
+import tempfile
+
+
+def find_gcda_files(paths):
+    for path in paths:
+        if os.path.isdir(path):
+            for root, _, files in os.walk(path):
+                for f in sorted(files):
+                    if f.endswith('.gcda'):
+                        yield os.path.join(root, f)
+        else:
+            yield path
+
+
+def run_gcov(gcov, gcda, outdir):
+    """Runs gcov on one data file, leaving its `.gcov` files in outdir."""
+    gcda = os.path.abspath(gcda)
+    with open(os.devnull, 'w') as devnull:
+        status = subprocess.call(shlex.split(gcov) +
+                                 ['--preserve-paths',
+                                  '--object-directory', os.path.dirname(gcda),
+                                  gcda],
+                                 cwd=outdir, stdout=devnull)
+    if status != 0:
+        sys.exit('error: `%s` failed on %s' % (gcov, gcda))
+
+
+def parse_gcov(path, counts, texts):
+    """Adds the counts of one `.gcov` file to `counts`, which maps each
+    source file to a map of line numbers to counts (None for lines without
+    code), and records the text of the lines in `texts`."""
+    source = None
+    with open(path) as f:
+        for line in f:
+            parts = line.rstrip('\n').split(':', 2)
+            if len(parts) < 3:
+                continue
+            count, lineno, text = parts[0].strip(), int(parts[1]), parts[2]
+            if lineno == 0:
+                if text.startswith('Source:'):
+                    source = os.path.normpath(text[len('Source:'):])
+                    counts.setdefault(source, {})
+                    texts.setdefault(source, {})
+                continue
+            if source is None:
+                continue
+            lines = counts[source]
+            texts[source][lineno] = text
+            if count == '-':
+                lines.setdefault(lineno, None)
+            else:
+                # `#####` marks lines that never ran; a `*` suffix marks
+                # lines with blocks that never ran.
+                n = 0 if count.startswith('#') else int(count.rstrip('*'))
+                lines[lineno] = (lines.get(lineno) or 0) + n
+
+
+def report(counts, texts, src, annotate):
+    total_covered = total_lines = 0
+    for source in sorted(counts):
+        if not os.path.abspath(source).startswith(src):
+            continue
+        lines = counts[source]
+        code = [n for n in lines.values() if n is not None]
+        covered = len([n for n in code if n > 0])
+        total_covered += covered
+        total_lines += len(code)
+        print('%s: %d/%d lines (%s)' % (os.path.relpath(source, src), covered,
+                                        len(code), percent(covered, len(code))))
+        if annotate:
+            for lineno in sorted(texts[source]):
+                n = lines.get(lineno)
+                if n is None:
+                    mark = '-'
+                elif n == 0:
+                    mark = '#####'
+                else:
+                    mark = str(n)
+                print('%9s:%5d:%s' % (mark, lineno, texts[source][lineno]))
+    print('total: %d/%d lines (%s)' % (total_covered, total_lines,
+                                       percent(total_covered, total_lines)))
+
+
+def percent(covered, total):
+    if total == 0:
+        return 'no code'
+    return '%.1f%%' % (100.0 * covered / total)
+
+
+def main():
+    parser = argparse.ArgumentParser(
+        description='Report the line coverage of a program built with -C coverage.')
+    parser.add_argument('paths', nargs='+', metavar='PATH',
+                        help='a .gcda file, or a directory containing some')
+    parser.add_argument('--src', default='.',
+                        help='only report on sources under this directory')
+    parser.add_argument('--gcov', default='gcov',
+                        help='the gcov program to use, e.g. `llvm-cov gcov`')
+    parser.add_argument('--annotate', action='store_true',
+                        help='print each source line with its count')
+    args = parser.parse_args()
+
+    gcda_files = list(find_gcda_files(args.paths))
+    if not gcda_files:
+        sys.exit('error: no .gcda files found; was the program run?')
+
+    counts = {}
+    texts = {}
+    outdir = tempfile.mkdtemp()
+    try:
+        for gcda in gcda_files:
+            run_gcov(args.gcov, gcda, outdir)
+            for f in sorted(os.listdir(outdir)):
+                path = os.path.join(outdir, f)
+                if f.endswith('.gcov'):
+                    parse_gcov(path, counts, texts)
+                os.remove(path)
+    finally:
+        shutil.rmtree(outdir)
+
+    report(counts, texts, os.path.abspath(args.src) + os.sep, args.annotate)
+
+
+if __name__ == '__main__':
+    main()
@@ -71,15 +71,23 @@ def rust_pretty_printer_lookup_function(val):
                 # This is a singleton enum
                 return rust_pretty_printer_lookup_function(val[enum_members[0]])
             else:
-                assert first_variant_name.startswith("RUST$ENCODED$ENUM$")
                 # This is a space-optimized enum.
                 # This means this enum has only two states, and Rust uses one
                 # of the fields somewhere in the struct to determine which of
                 # the two states it's in. The location of the field is encoded
                 # in the name as something like
                 # RUST$ENCODED$ENUM$(num$)*name_of_zero_state
+                # or, if the other state is not represented by zero, as
+                # RUST$ENCODED$NICHE$value$(num$)*name_of_other_state
                 last_separator_index = first_variant_name.rfind("$")
-                start_index = len("RUST$ENCODED$ENUM$")
+                if first_variant_name.startswith("RUST$ENCODED$NICHE$"):
+                    value_index = len("RUST$ENCODED$NICHE$")
+                    start_index = first_variant_name.index("$", value_index) + 1
+                    niche_value = int(first_variant_name[value_index:start_index - 1])
+                else:
+                    assert first_variant_name.startswith("RUST$ENCODED$ENUM$")
+                    start_index = len("RUST$ENCODED$ENUM$")
+                    niche_value = 0
                 disr_field_indices = first_variant_name[start_index:last_separator_index].split("$")
                 disr_field_indices = [int(index) for index in disr_field_indices]
 
@@ -94,7 +102,7 @@ def rust_pretty_printer_lookup_function(val):
                 if discriminant.type.code == gdb.TYPE_CODE_STRUCT:
                     discriminant = discriminant[get_field_at_index(discriminant, 0)]
 
-                if discriminant == 0:
+                if int(discriminant) == niche_value:
                     null_variant_name = first_variant_name[last_separator_index + 1:]
                     return IdentityPrinter(null_variant_name)
 
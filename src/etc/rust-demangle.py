@@ -0,0 +1,101 @@
+#!/usr/bin/env python
+#
+# Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+# file at the top-level directory of this distribution and at
+# http://rust-lang.org/COPYRIGHT.
+#
+# Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+# http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+# <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+# option. This file may not be copied, modified, or distributed
+# except according to those terms.
+
+"""
+Demangles Rust symbols, in the style of c++filt.
+
+Symbols given as arguments are demangled and printed one per line. Without
+arguments, stdin is copied to stdout with every Rust symbol in it demangled,
+which is useful for the output of profilers, nm, objdump and the like:
+
+    perf report --stdio | rust-demangle.py
+
+The demangling mirrors `demangle` in src/libstd/sys/common/backtrace.rs; see
+`sanitize` and `mangle` in src/librustc_trans/back/link.rs for the mangling.
+"""
+
+import re
+import sys
+
+SYMBOL = re.compile(r'_?ZN[0-9A-Za-z_.$]+?E(?![0-9A-Za-z_$])')
+HASH = re.compile(r'^h[0-9a-f]{16}[0-9A-Za-z]{0,3}$')
+ESCAPES = {
+    'SP': '@',
+    'BP': '*',
+    'RF': '&',
+    'LT': '<',
+    'GT': '>',
+    'LP': '(',
+    'RP': ')',
+    'C': ',',
+}
+
+
+def elements(inner):
+    """Splits the length-prefixed elements of a symbol, or returns None."""
+    result = []
+    while inner:
+        m = re.match(r'[0-9]+', inner)
+        if m is None:
+            return None
+        digits = len(m.group(0))
+        n = int(m.group(0))
+        if n == 0 or n > len(inner) - digits:
+            return None
+        result.append(inner[digits:digits + n])
+        inner = inner[digits + n:]
+    return result
+
+
+def unescape(m):
+    escape = m.group(1)
+    if escape in ESCAPES:
+        return ESCAPES[escape]
+    if escape.startswith('u'):
+        try:
+            c = int(escape[1:], 16)
+            if sys.version_info[0] < 3:
+                return unichr(c).encode('utf-8')
+            return chr(c)
+        except ValueError:
+            pass
+    return m.group(0)
+
+
+def demangle(symbol):
+    if symbol.startswith('_ZN') and symbol.endswith('E'):
+        inner = symbol[3:-1]
+    elif symbol.startswith('ZN') and symbol.endswith('E'):
+        inner = symbol[2:-1]
+    else:
+        return symbol
+
+    parts = elements(inner)
+    if not parts:
+        return symbol
+    if len(parts) > 1 and HASH.match(parts[-1]):
+        parts.pop()
+
+    # `sanitize` puts a "_" in front of elements that start with an escape.
+    parts = [p[1:] if p.startswith('_$') else p for p in parts]
+    parts = [re.sub(r'\$([A-Za-z0-9]+)\$', unescape, p.replace('..', '::'))
+             for p in parts]
+    return '::'.join(parts)
+
+
+if __name__ == '__main__':
+    if len(sys.argv) > 1:
+        for symbol in sys.argv[1:]:
+            print(demangle(symbol))
+    else:
+        for line in sys.stdin:
+            sys.stdout.write(SYMBOL.sub(lambda m: demangle(m.group(0)), line))
@@ -140,11 +140,17 @@ pub use funcs::bsd43::*;
 
 // On NaCl, these libraries are static. Thus it would be a Bad Idea to link them
 // in when creating a test crate.
-#[cfg(not(any(windows, all(target_os = "nacl", test))))]
+#[cfg(not(any(windows, target_env = "musl", all(target_os = "nacl", test))))]
 #[link(name = "c")]
 #[link(name = "m")]
 extern {}
 
+// musl is linked statically into every program, so its libc.a (which also
+// provides libm, libpthread and libdl) is carried along in this crate.
+#[cfg(all(target_env = "musl", not(test)))]
+#[link(name = "c", kind = "static")]
+extern {}
+
 // libnacl provides functions that require a trip through the IRT to work.
 // ie: _exit, mmap, nanosleep, etc. Anything that would otherwise require a trip
 // to the kernel.
@@ -756,6 +762,7 @@ pub mod types {
             }
             pub mod extra {
                 use types::os::arch::c95::{c_ushort, c_int, c_uchar};
+                #[repr(C)]
                 #[derive(Copy, Clone)] pub struct sockaddr_ll {
                     pub sll_family: c_ushort,
                     pub sll_protocol: c_ushort,
@@ -3355,18 +3362,23 @@ pub mod consts {
             pub const PTHREAD_STACK_MIN: size_t = 8192;
 
             #[cfg(all(target_os = "linux",
+                      not(target_env = "musl"),
                       any(target_arch = "arm",
                           target_arch = "x86",
                           target_arch = "x86_64")))]
             pub const PTHREAD_STACK_MIN: size_t = 16384;
 
             #[cfg(all(target_os = "linux",
+                      not(target_env = "musl"),
                       any(target_arch = "mips",
                           target_arch = "mipsel",
                           target_arch = "aarch64",
                           target_arch = "powerpc")))]
             pub const PTHREAD_STACK_MIN: size_t = 131072;
 
+            #[cfg(all(target_os = "linux", target_env = "musl"))]
+            pub const PTHREAD_STACK_MIN: size_t = 2048;
+
             pub const CLOCK_REALTIME: c_int = 0;
             pub const CLOCK_MONOTONIC: c_int = 1;
 
@@ -372,7 +372,8 @@ register_diagnostics! {
     E0314, // closure outlives stack frame
     E0315, // cannot invoke closure outside of its lifetime
     E0316, // nested quantification of lifetimes
-    E0370  // discriminant overflow
+    E0370, // discriminant overflow
+    E0374  // infinitely recursive generic instantiation
 }
 
 __build_diagnostic_array! { DIAGNOSTICS }
@@ -90,6 +90,7 @@ pub mod middle {
     pub mod astencode;
     pub mod cfg;
     pub mod check_const;
+    pub mod check_generic_recursion;
     pub mod check_static_recursion;
     pub mod check_loop;
     pub mod check_match;
@@ -123,6 +124,7 @@ pub mod middle {
     pub mod ty_match;
     pub mod ty_relate;
     pub mod ty_walk;
+    pub mod type_properties;
     pub mod weak_lang_items;
 }
 
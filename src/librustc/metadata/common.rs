@@ -195,6 +195,10 @@ pub const tag_native_libraries: usize = 0x10a; // top-level only
 pub const tag_native_libraries_lib: usize = 0x82;
 pub const tag_native_libraries_name: usize = 0x83;
 pub const tag_native_libraries_kind: usize = 0x84;
+pub const tag_native_libraries_cfg: usize = 0xa7;
+pub const tag_native_libraries_whole_archive: usize = 0xa8;
+pub const tag_native_libraries_linker_arg: usize = 0xa9;
+pub const tag_native_libraries_linker_arg_value: usize = 0xaa;
 
 pub const tag_plugin_registrar_fn: usize = 0x10b; // top-level only
 
@@ -208,6 +212,26 @@ pub const tag_items_data_item_stability: usize = 0x88;
 
 pub const tag_items_data_item_repr: usize = 0x89;
 
+// The metadata of every library starts with a header saying which compiler
+// wrote it. The layout of the header never changes, so that any rustc can tell
+// why it can't read a library. All of its integers are big-endian:
+//
+// - the bytes of `metadata_magic`
+// - the version of the encoding of the metadata, a u32
+// - the length of the version string of the rustc that wrote the metadata, a
+//   u32, followed by the string
+// - the SipHash of the metadata, a u64
+// - the length of the metadata, a u32, followed by the metadata
+pub const metadata_magic: &'static [u8] = b"rustmeta";
+
+// NB: Increment this as you change the encoding of the metadata.
+pub const metadata_schema_version: u32 = 1;
+
+/// The version of the compiler, as recorded in the header of the metadata.
+pub fn rustc_version() -> String {
+    format!("rustc {}", option_env!("CFG_VERSION").unwrap_or("unknown version"))
+}
+
 #[derive(Clone, Debug)]
 pub struct LinkMeta {
     pub crate_name: String,
@@ -256,3 +280,10 @@ pub const tag_codemap_filemap: usize = 0xa2;
 pub const tag_item_super_predicates: usize = 0xa3;
 
 pub const tag_defaulted_trait: usize = 0xa4;
+
+pub const tag_items_data_item_deprecation: usize = 0xa5;
+
+pub const tag_object_safe_traits: usize = 0x10f; // top-level only
+pub const tag_object_safe_trait: usize = 0xa6;
+
+pub const tag_panic_strategy: usize = 0x110; // top-level only
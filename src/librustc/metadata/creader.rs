@@ -21,6 +21,7 @@ use metadata::decoder;
 use metadata::loader;
 use metadata::loader::CratePaths;
 
+use std::cell::RefCell;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use syntax::ast;
@@ -33,6 +34,7 @@ use syntax::parse::token::InternedString;
 use syntax::parse::token;
 use syntax::visit;
 use util::fs;
+use util::nodemap::FnvHashMap;
 use log;
 
 pub struct CrateReader<'a> {
@@ -97,9 +99,8 @@ pub fn validate_crate_name(sess: Option<&Session>, s: &str, sp: Option<Span>) {
 
 fn register_native_lib(sess: &Session,
                        span: Option<Span>,
-                       name: String,
-                       kind: cstore::NativeLibraryKind) {
-    if name.is_empty() {
+                       lib: cstore::NativeLibrary) {
+    if lib.name.is_empty() {
         match span {
             Some(span) => {
                 sess.span_err(span, "#[link(name = \"\")] given with \
@@ -112,14 +113,14 @@ fn register_native_lib(sess: &Session,
         return
     }
     let is_osx = sess.target.target.options.is_like_osx;
-    if kind == cstore::NativeFramework && !is_osx {
+    if lib.kind == cstore::NativeFramework && !is_osx {
         let msg = "native frameworks are only available on OSX targets";
         match span {
             Some(span) => sess.span_err(span, msg),
             None => sess.err(msg),
         }
     }
-    sess.cstore.add_used_library(name, kind);
+    sess.cstore.add_used_library(lib);
 }
 
 // Extra info about a crate loaded for plugins or exported macros.
@@ -163,8 +164,35 @@ impl<'a> CrateReader<'a> {
         }
 
         for &(ref name, kind) in &self.sess.opts.libs {
-            register_native_lib(self.sess, None, name.clone(), kind);
+            register_native_lib(self.sess, None, cstore::NativeLibrary {
+                kind: kind,
+                name: name.clone(),
+                cfg: None,
+                whole_archive: false,
+            });
         }
+
+        self.check_panic_strategy();
+    }
+
+    // Code compiled with `-C panic=abort` has no landing pads, so a crate
+    // that unwinds cannot depend on it: destructors in the aborting crate
+    // would be skipped while unwinding through it. The converse is fine,
+    // since a panic in the unwinding dependency aborts anyway.
+    fn check_panic_strategy(&self) {
+        let strategy = self.sess.opts.cg.panic;
+        if strategy == config::PanicStrategy::Abort {
+            return;
+        }
+        self.sess.cstore.iter_crate_data(|_, data| {
+            let dep_strategy = decoder::get_panic_strategy(data.data());
+            if dep_strategy != strategy {
+                let msg = format!("the crate `{}` is compiled with the panic strategy `{}`, \
+                                   which is incompatible with this crate's strategy of `{}`",
+                                  data.name, dep_strategy.desc(), strategy.desc());
+                self.sess.span_err(data.span, &msg);
+            }
+        });
     }
 
     fn process_crate(&self, c: &ast::Crate) {
@@ -250,48 +278,87 @@ impl<'a> CrateReader<'a> {
                     })
                     .collect::<Vec<&ast::Attribute>>();
                 for m in &link_args {
-                    match m.meta_item_list() {
-                        Some(items) => {
-                            let kind = items.iter().find(|k| {
-                                k.name() == "kind"
-                            }).and_then(|a| a.value_str());
-                            let kind = match kind {
-                                Some(k) => {
-                                    if k == "static" {
-                                        cstore::NativeStatic
-                                    } else if self.sess.target.target.options.is_like_osx
-                                              && k == "framework" {
-                                        cstore::NativeFramework
-                                    } else if k == "framework" {
-                                        cstore::NativeFramework
-                                    } else if k == "dylib" {
-                                        cstore::NativeUnknown
-                                    } else {
-                                        self.sess.span_err(m.span,
-                                            &format!("unknown kind: `{}`",
-                                                    k));
-                                        cstore::NativeUnknown
-                                    }
-                                }
-                                None => cstore::NativeUnknown
-                            };
-                            let n = items.iter().find(|n| {
-                                n.name() == "name"
-                            }).and_then(|a| a.value_str());
-                            let n = match n {
-                                Some(n) => n,
-                                None => {
+                    let items = match m.meta_item_list() {
+                        Some(items) => items,
+                        None => continue,
+                    };
+                    let cfg = items.iter().find(|k| k.name() == "cfg").and_then(|k| {
+                        match k.meta_item_list() {
+                            Some(preds) if preds.len() == 1 => Some(preds[0].clone()),
+                            _ => {
+                                self.sess.span_err(k.span,
+                                                   "`cfg()` must have exactly one argument");
+                                None
+                            }
+                        }
+                    });
+
+                    // `#[link(arg = "...")]` passes a single argument to the
+                    // linker, rather than naming a library.
+                    if let Some(arg) = items.iter().find(|k| k.name() == "arg") {
+                        match arg.value_str() {
+                            Some(a) => {
+                                if items.iter().any(|k| k.name() == "name") {
                                     self.sess.span_err(m.span,
-                                        "#[link(...)] specified without \
-                                         `name = \"foo\"`");
-                                    InternedString::new("foo")
+                                        "#[link(...)] can't give both `arg` and `name`");
                                 }
-                            };
-                            register_native_lib(self.sess, Some(m.span),
-                                                n.to_string(), kind);
+                                self.sess.cstore.add_used_linker_arg(a.to_string(), cfg);
+                            }
+                            None => {
+                                self.sess.span_err(arg.span,
+                                    "`arg` must be given as `arg = \"...\"`");
+                            }
                         }
-                        None => {}
+                        continue
                     }
+
+                    let kind = items.iter().find(|k| {
+                        k.name() == "kind"
+                    }).and_then(|a| a.value_str());
+                    let kind = match kind {
+                        Some(k) => {
+                            if k == "static" {
+                                cstore::NativeStatic
+                            } else if self.sess.target.target.options.is_like_osx
+                                      && k == "framework" {
+                                cstore::NativeFramework
+                            } else if k == "framework" {
+                                cstore::NativeFramework
+                            } else if k == "dylib" {
+                                cstore::NativeUnknown
+                            } else {
+                                self.sess.span_err(m.span,
+                                    &format!("unknown kind: `{}`",
+                                            k));
+                                cstore::NativeUnknown
+                            }
+                        }
+                        None => cstore::NativeUnknown
+                    };
+                    let whole_archive = items.iter().any(|k| k.name() == "whole_archive");
+                    if whole_archive && kind != cstore::NativeStatic {
+                        self.sess.span_err(m.span,
+                            "`whole_archive` can only be given for `kind = \"static\"` \
+                             libraries");
+                    }
+                    let n = items.iter().find(|n| {
+                        n.name() == "name"
+                    }).and_then(|a| a.value_str());
+                    let n = match n {
+                        Some(n) => n,
+                        None => {
+                            self.sess.span_err(m.span,
+                                "#[link(...)] specified without \
+                                 `name = \"foo\"`");
+                            InternedString::new("foo")
+                        }
+                    };
+                    register_native_lib(self.sess, Some(m.span), cstore::NativeLibrary {
+                        kind: kind,
+                        name: n.to_string(),
+                        cfg: cfg,
+                        whole_archive: whole_archive,
+                    });
                 }
             }
             _ => { }
@@ -374,16 +441,28 @@ impl<'a> CrateReader<'a> {
 
         let loader::Library { dylib, rlib, metadata } = lib;
 
+        // Of the metadata, only the dependencies of the crate are decoded
+        // here. Its items are decoded as they are looked up through its item
+        // index, and its codemap when one of its spans is first translated.
+        // The lang items and impls of every crate are still collected before
+        // type checking. The metadata of an rlib is read in place from the
+        // archive, while that of a dylib is compressed, so it is inflated
+        // whole when the dylib is loaded.
         let cnum_map = self.resolve_crate_deps(root, metadata.as_slice(), span);
-        let codemap_import_info = import_codemap(self.sess.codemap(), &metadata);
+        let decoded_docs = if self.sess.meta_stats() {
+            Some(RefCell::new(FnvHashMap()))
+        } else {
+            None
+        };
 
         let cmeta = Rc::new( cstore::crate_metadata {
             name: name.to_string(),
             data: metadata,
             cnum_map: cnum_map,
             cnum: cnum,
-            codemap_import_info: codemap_import_info,
+            codemap_import_info: RefCell::new(None),
             span: span,
+            decoded_docs: decoded_docs,
         });
 
         let source = cstore::CrateSource {
@@ -421,6 +500,8 @@ impl<'a> CrateReader<'a> {
                     rejected_via_hash: vec!(),
                     rejected_via_triple: vec!(),
                     rejected_via_kind: vec!(),
+                    rejected_via_version: vec!(),
+                    rejected_via_checksum: vec!(),
                     should_match_name: true,
                 };
                 let library = load_ctxt.load_library_crate();
@@ -472,6 +553,8 @@ impl<'a> CrateReader<'a> {
             rejected_via_hash: vec!(),
             rejected_via_triple: vec!(),
             rejected_via_kind: vec!(),
+            rejected_via_version: vec!(),
+            rejected_via_checksum: vec!(),
             should_match_name: true,
         };
         let library = match load_ctxt.maybe_load_library_crate() {
@@ -615,10 +698,14 @@ impl<'a> CrateReader<'a> {
 /// file they represent, just information about length, line breaks, and
 /// multibyte characters. This information is enough to generate valid debuginfo
 /// for items inlined from other crates.
-fn import_codemap(local_codemap: &codemap::CodeMap,
-                  metadata: &MetadataBlob)
-                  -> Vec<cstore::ImportedFileMap> {
-    let external_codemap = decoder::get_imported_filemaps(metadata.as_slice());
+///
+/// Decoding the codemap of a large crate is expensive, so this is only done
+/// the first time one of its spans is translated, see
+/// `cstore::crate_metadata::imported_filemaps()`.
+pub fn import_codemap(local_codemap: &codemap::CodeMap,
+                      cdata: &cstore::crate_metadata)
+                      -> Vec<cstore::ImportedFileMap> {
+    let external_codemap = decoder::get_imported_filemaps(cdata);
 
     let imported_filemaps = external_codemap.into_iter().map(|filemap_to_import| {
         // Try to find an existing FileMap that can be reused for the filemap to
@@ -26,6 +26,7 @@ use syntax::attr;
 use syntax::attr::AttrMetaMethods;
 use syntax::diagnostic::expect;
 use syntax::parse::token;
+use syntax::ptr::P;
 
 use std::collections::hash_map::HashMap;
 
@@ -49,7 +50,7 @@ pub fn each_lang_item<F>(cstore: &cstore::CStore,
     F: FnMut(ast::NodeId, usize) -> bool,
 {
     let crate_data = cstore.get_crate_data(cnum);
-    decoder::each_lang_item(&*crate_data, f)
+    decoder::each_lang_item(crate_data.data(), f)
 }
 
 /// Iterates over each child of the given item.
@@ -292,9 +293,15 @@ pub fn get_impl_vtables<'tcx>(tcx: &ty::ctxt<'tcx>,
 }
 
 pub fn get_native_libraries(cstore: &cstore::CStore, crate_num: ast::CrateNum)
-                            -> Vec<(cstore::NativeLibraryKind, String)> {
+                            -> Vec<cstore::NativeLibrary> {
     let cdata = cstore.get_crate_data(crate_num);
-    decoder::get_native_libraries(&*cdata)
+    decoder::get_native_libraries(cdata.data())
+}
+
+pub fn get_linker_args(cstore: &cstore::CStore, crate_num: ast::CrateNum)
+                       -> Vec<(String, Option<P<ast::MetaItem>>)> {
+    let cdata = cstore.get_crate_data(crate_num);
+    decoder::get_linker_args(cdata.data())
 }
 
 pub fn each_impl<F>(cstore: &cstore::CStore,
@@ -384,6 +391,13 @@ pub fn get_stability(cstore: &cstore::CStore,
     decoder::get_stability(&*cdata, def.node)
 }
 
+pub fn get_deprecation(cstore: &cstore::CStore,
+                       def: ast::DefId)
+                       -> Option<attr::Deprecation> {
+    let cdata = cstore.get_crate_data(def.krate);
+    decoder::get_deprecation(&*cdata, def.node)
+}
+
 pub fn is_staged_api(cstore: &cstore::CStore, def: ast::DefId) -> bool {
     let cdata = cstore.get_crate_data(def.krate);
     let attrs = decoder::get_crate_attributes(cdata.data());
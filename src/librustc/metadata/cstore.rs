@@ -18,18 +18,22 @@ pub use self::LinkagePreference::*;
 pub use self::NativeLibraryKind::*;
 
 use back::svh::Svh;
+use metadata::creader;
 use metadata::decoder;
 use metadata::loader;
 use session::search_paths::PathKind;
 use util::nodemap::{FnvHashMap, NodeMap};
 
 use std::cell::RefCell;
+use std::cmp;
 use std::rc::Rc;
 use std::path::PathBuf;
 use flate::Bytes;
+use rbml;
 use syntax::ast;
 use syntax::codemap;
 use syntax::parse::token::IdentInterner;
+use syntax::ptr::P;
 
 // A map from external crate numbers (as decoded from some crate file) to
 // local crate numbers (as generated during this session). Each external
@@ -58,8 +62,15 @@ pub struct crate_metadata {
     pub data: MetadataBlob,
     pub cnum_map: cnum_map,
     pub cnum: ast::CrateNum,
-    pub codemap_import_info: Vec<ImportedFileMap>,
+    // Filled in by `imported_filemaps` the first time a span from this
+    // crate is translated.
+    pub codemap_import_info: RefCell<Option<Rc<Vec<ImportedFileMap>>>>,
     pub span: codemap::Span,
+    // With `-Z meta-stats`, the item documents of the metadata looked up
+    // through its item index so far, as their lengths keyed by their offsets.
+    // Documents outside of the items, such as the dependencies, lang items,
+    // impls and codemap of the crate, are not counted.
+    pub decoded_docs: Option<RefCell<FnvHashMap<usize, usize>>>,
 }
 
 #[derive(Copy, Debug, PartialEq, Clone)]
@@ -75,6 +86,19 @@ pub enum NativeLibraryKind {
     NativeUnknown,   // default way to specify a dynamic library
 }
 
+/// A native library linked to through `#[link]` or `-l`.
+#[derive(Clone)]
+pub struct NativeLibrary {
+    pub kind: NativeLibraryKind,
+    pub name: String,
+    /// The `cfg(...)` predicate of the `#[link]` attribute, if any. The library is only linked
+    /// into outputs whose configuration matches it.
+    pub cfg: Option<P<ast::MetaItem>>,
+    /// Whether all of the objects of a static library are linked, rather than only those the
+    /// output refers to.
+    pub whole_archive: bool,
+}
+
 // Where a crate came from on the local filesystem. One of these two options
 // must be non-None.
 #[derive(PartialEq, Clone)]
@@ -89,8 +113,9 @@ pub struct CStore {
     /// Map from NodeId's of local extern crate statements to crate numbers
     extern_mod_crate_map: RefCell<NodeMap<ast::CrateNum>>,
     used_crate_sources: RefCell<Vec<CrateSource>>,
-    used_libraries: RefCell<Vec<(String, NativeLibraryKind)>>,
+    used_libraries: RefCell<Vec<NativeLibrary>>,
     used_link_args: RefCell<Vec<String>>,
+    used_linker_args: RefCell<Vec<(String, Option<P<ast::MetaItem>>)>>,
     pub intr: Rc<IdentInterner>,
 }
 
@@ -102,6 +127,7 @@ impl CStore {
             used_crate_sources: RefCell::new(Vec::new()),
             used_libraries: RefCell::new(Vec::new()),
             used_link_args: RefCell::new(Vec::new()),
+            used_linker_args: RefCell::new(Vec::new()),
             intr: intr
         }
     }
@@ -161,6 +187,7 @@ impl CStore {
         self.used_crate_sources.borrow_mut().clear();
         self.used_libraries.borrow_mut().clear();
         self.used_link_args.borrow_mut().clear();
+        self.used_linker_args.borrow_mut().clear();
     }
 
     // This method is used when generating the command line to pass through to
@@ -201,14 +228,12 @@ impl CStore {
         libs
     }
 
-    pub fn add_used_library(&self, lib: String, kind: NativeLibraryKind) {
-        assert!(!lib.is_empty());
-        self.used_libraries.borrow_mut().push((lib, kind));
+    pub fn add_used_library(&self, lib: NativeLibrary) {
+        assert!(!lib.name.is_empty());
+        self.used_libraries.borrow_mut().push(lib);
     }
 
-    pub fn get_used_libraries<'a>(&'a self)
-                              -> &'a RefCell<Vec<(String,
-                                                  NativeLibraryKind)>> {
+    pub fn get_used_libraries<'a>(&'a self) -> &'a RefCell<Vec<NativeLibrary>> {
         &self.used_libraries
     }
 
@@ -222,6 +247,15 @@ impl CStore {
         &self.used_link_args
     }
 
+    pub fn add_used_linker_arg(&self, arg: String, cfg: Option<P<ast::MetaItem>>) {
+        self.used_linker_args.borrow_mut().push((arg, cfg));
+    }
+
+    pub fn get_used_linker_args<'a>(&'a self)
+                                -> &'a RefCell<Vec<(String, Option<P<ast::MetaItem>>)>> {
+        &self.used_linker_args
+    }
+
     pub fn add_extern_mod_stmt_cnum(&self,
                                     emod_id: ast::NodeId,
                                     cnum: ast::CrateNum) {
@@ -232,32 +266,66 @@ impl CStore {
                                      -> Option<ast::CrateNum> {
         self.extern_mod_crate_map.borrow().get(&emod_id).cloned()
     }
+
+    /// With `-Z meta-stats`, prints how many bytes of the item documents of
+    /// each crate have been looked up, of those of all of its items.
+    pub fn print_decode_stats(&self) {
+        let metas = self.metas.borrow();
+        let mut cnums = metas.keys().cloned().collect::<Vec<_>>();
+        cnums.sort();
+        println!("item metadata decoded:");
+        for cnum in cnums {
+            let cdata = metas.get(&cnum).unwrap();
+            let decoded_docs = match cdata.decoded_docs {
+                Some(ref decoded_docs) => decoded_docs.borrow(),
+                None => continue,
+            };
+            let decoded = decoded_docs.values().fold(0, |sum, &len| sum + len);
+            let total = decoder::get_items_data_len(cdata.data());
+            let percent = decoded as f64 * 100.0 / cmp::max(total, 1) as f64;
+            println!("{:>22}: {} of {} bytes ({:.1}%) in {} documents",
+                     cdata.name, decoded, total, percent, decoded_docs.len());
+        }
+    }
 }
 
 impl crate_metadata {
     pub fn data<'a>(&'a self) -> &'a [u8] { self.data.as_slice() }
     pub fn name(&self) -> String { decoder::get_crate_name(self.data()) }
     pub fn hash(&self) -> Svh { decoder::get_crate_hash(self.data()) }
+
+    /// The FileMaps of this crate, which are imported into the local codemap
+    /// on first use.
+    pub fn imported_filemaps(&self, local_codemap: &codemap::CodeMap)
+                             -> Rc<Vec<ImportedFileMap>> {
+        let mut info = self.codemap_import_info.borrow_mut();
+        if info.is_none() {
+            *info = Some(Rc::new(creader::import_codemap(local_codemap, self)));
+        }
+        info.as_ref().unwrap().clone()
+    }
+
+    /// Records that an item document of this crate's metadata has been decoded.
+    pub fn note_decoded(&self, doc: rbml::Doc) {
+        if let Some(ref decoded_docs) = self.decoded_docs {
+            decoded_docs.borrow_mut().insert(doc.start, doc.end - doc.start);
+        }
+    }
 }
 
 impl MetadataBlob {
-    pub fn as_slice<'a>(&'a self) -> &'a [u8] {
-        let slice = match *self {
+    /// The metadata, with its header.
+    pub fn as_bytes<'a>(&'a self) -> &'a [u8] {
+        match *self {
             MetadataVec(ref vec) => &vec[..],
             MetadataArchive(ref ar) => ar.as_slice(),
-        };
-        if slice.len() < 4 {
-            &[] // corrupt metadata
-        } else {
-            let len = (((slice[0] as u32) << 24) |
-                       ((slice[1] as u32) << 16) |
-                       ((slice[2] as u32) << 8) |
-                       ((slice[3] as u32) << 0)) as usize;
-            if len + 4 <= slice.len() {
-                &slice[4.. len + 4]
-            } else {
-                &[] // corrupt or old metadata
-            }
+        }
+    }
+
+    pub fn as_slice<'a>(&'a self) -> &'a [u8] {
+        match decoder::get_metadata_header(self.as_bytes()) {
+            Some(header) => header.data,
+            None => &[], // corrupt or old metadata
         }
     }
 }
@@ -30,9 +30,10 @@ use middle::subst;
 use middle::ty::{ImplContainer, TraitContainer};
 use middle::ty::{self, Ty};
 use middle::astencode::vtable_decoder_helpers;
+use session::config;
 
 use std::collections::HashMap;
-use std::hash::{self, Hash, SipHasher};
+use std::hash::{self, Hash, Hasher, SipHasher};
 use std::io::prelude::*;
 use std::io;
 use std::num::FromPrimitive;
@@ -42,7 +43,7 @@ use std::str;
 
 use rbml::reader;
 use rbml;
-use serialize::Decodable;
+use serialize::{json, Decodable};
 use syntax::ast_map;
 use syntax::attr;
 use syntax::parse::token::{IdentInterner, special_idents};
@@ -107,11 +108,13 @@ fn find_item<'a>(item_id: ast::NodeId, items: rbml::Doc<'a>) -> rbml::Doc<'a> {
     }
 }
 
-// Looks up an item in the given metadata and returns an rbml doc pointing
-// to the item data.
-fn lookup_item<'a>(item_id: ast::NodeId, data: &'a [u8]) -> rbml::Doc<'a> {
-    let items = reader::get_doc(rbml::Doc::new(data), tag_items);
-    find_item(item_id, items)
+// Looks up an item in the metadata of the given crate and returns an rbml
+// doc pointing to the item data.
+fn lookup_item<'a>(item_id: ast::NodeId, cdata: Cmd<'a>) -> rbml::Doc<'a> {
+    let items = reader::get_doc(rbml::Doc::new(cdata.data()), tag_items);
+    let item_doc = find_item(item_id, items);
+    cdata.note_decoded(item_doc);
+    item_doc
 }
 
 #[derive(PartialEq)]
@@ -391,7 +394,7 @@ pub fn get_trait_def<'tcx>(cdata: Cmd,
                            item_id: ast::NodeId,
                            tcx: &ty::ctxt<'tcx>) -> ty::TraitDef<'tcx>
 {
-    let item_doc = lookup_item(item_id, cdata.data());
+    let item_doc = lookup_item(item_id, cdata);
     let generics = doc_generics(item_doc, tcx, cdata, tag_item_generics);
     let unsafety = parse_unsafety(item_doc);
     let associated_type_names = parse_associated_type_names(item_doc);
@@ -411,7 +414,7 @@ pub fn get_predicates<'tcx>(cdata: Cmd,
                             tcx: &ty::ctxt<'tcx>)
                             -> ty::GenericPredicates<'tcx>
 {
-    let item_doc = lookup_item(item_id, cdata.data());
+    let item_doc = lookup_item(item_id, cdata);
     doc_predicates(item_doc, tcx, cdata, tag_item_generics)
 }
 
@@ -420,14 +423,14 @@ pub fn get_super_predicates<'tcx>(cdata: Cmd,
                                   tcx: &ty::ctxt<'tcx>)
                                   -> ty::GenericPredicates<'tcx>
 {
-    let item_doc = lookup_item(item_id, cdata.data());
+    let item_doc = lookup_item(item_id, cdata);
     doc_predicates(item_doc, tcx, cdata, tag_item_super_predicates)
 }
 
 pub fn get_type<'tcx>(cdata: Cmd, id: ast::NodeId, tcx: &ty::ctxt<'tcx>)
                       -> ty::TypeScheme<'tcx>
 {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     let t = item_type(ast::DefId { krate: cdata.cnum, node: id }, item_doc, tcx,
                       cdata);
     let generics = doc_generics(item_doc, tcx, cdata, tag_item_generics);
@@ -438,15 +441,23 @@ pub fn get_type<'tcx>(cdata: Cmd, id: ast::NodeId, tcx: &ty::ctxt<'tcx>)
 }
 
 pub fn get_stability(cdata: Cmd, id: ast::NodeId) -> Option<attr::Stability> {
-    let item = lookup_item(id, cdata.data());
+    let item = lookup_item(id, cdata);
     reader::maybe_get_doc(item, tag_items_data_item_stability).map(|doc| {
         let mut decoder = reader::Decoder::new(doc);
         Decodable::decode(&mut decoder).unwrap()
     })
 }
 
+pub fn get_deprecation(cdata: Cmd, id: ast::NodeId) -> Option<attr::Deprecation> {
+    let item = lookup_item(id, cdata);
+    reader::maybe_get_doc(item, tag_items_data_item_deprecation).map(|doc| {
+        let mut decoder = reader::Decoder::new(doc);
+        Decodable::decode(&mut decoder).unwrap()
+    })
+}
+
 pub fn get_repr_attrs(cdata: Cmd, id: ast::NodeId) -> Vec<attr::ReprAttr> {
-    let item = lookup_item(id, cdata.data());
+    let item = lookup_item(id, cdata);
     match reader::maybe_get_doc(item, tag_items_data_item_repr).map(|doc| {
         let mut decoder = reader::Decoder::new(doc);
         Decodable::decode(&mut decoder).unwrap()
@@ -460,7 +471,7 @@ pub fn get_impl_polarity<'tcx>(cdata: Cmd,
                                id: ast::NodeId)
                                -> Option<ast::ImplPolarity>
 {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     let fam = item_family(item_doc);
     match fam {
         Family::Impl => {
@@ -475,7 +486,7 @@ pub fn get_impl_trait<'tcx>(cdata: Cmd,
                             tcx: &ty::ctxt<'tcx>)
                             -> Option<Rc<ty::TraitRef<'tcx>>>
 {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     let fam = item_family(item_doc);
     match fam {
         Family::Impl | Family::DefaultImpl => {
@@ -492,15 +503,24 @@ pub fn get_impl_vtables<'tcx>(cdata: Cmd,
                               tcx: &ty::ctxt<'tcx>)
                               -> ty::vtable_res<'tcx>
 {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     let vtables_doc = reader::get_doc(item_doc, tag_item_impl_vtables);
     let mut decoder = reader::Decoder::new(vtables_doc);
     decoder.read_vtable_res(tcx, cdata)
 }
 
 
+/// The number of bytes taken by the documents of all of the items of a crate,
+/// which `lookup_item` finds through the item index.
+pub fn get_items_data_len(data: &[u8]) -> usize {
+    let items = reader::get_doc(rbml::Doc::new(data), tag_items);
+    let items_data = reader::get_doc(items, tag_items_data);
+    items_data.end - items_data.start
+}
+
 pub fn get_symbol(data: &[u8], id: ast::NodeId) -> String {
-    return item_symbol(lookup_item(id, data));
+    let items = reader::get_doc(rbml::Doc::new(data), tag_items);
+    return item_symbol(find_item(id, items));
 }
 
 // Something that a name can resolve to.
@@ -512,10 +532,10 @@ pub enum DefLike {
 }
 
 /// Iterates over the language items in the given crate.
-pub fn each_lang_item<F>(cdata: Cmd, mut f: F) -> bool where
+pub fn each_lang_item<F>(data: &[u8], mut f: F) -> bool where
     F: FnMut(ast::NodeId, usize) -> bool,
 {
-    let root = rbml::Doc::new(cdata.data());
+    let root = rbml::Doc::new(data);
     let lang_items = reader::get_doc(root, tag_lang_items);
     reader::tagged_docs(lang_items, tag_lang_items_item, |item_doc| {
         let id_doc = reader::get_doc(item_doc, tag_lang_items_item_id);
@@ -560,6 +580,8 @@ fn each_child_of_item_or_crate<F, G>(intr: Rc<IdentInterner>,
         match maybe_find_item(child_def_id.node, other_crates_items) {
             None => {}
             Some(child_item_doc) => {
+                crate_data.note_decoded(child_item_doc);
+
                 // Hand off the item to the callback.
                 let child_name = item_name(&*intr, child_item_doc);
                 let def_like = item_to_def_like(child_item_doc,
@@ -584,12 +606,14 @@ fn each_child_of_item_or_crate<F, G>(intr: Rc<IdentInterner>,
                                                cdata);
         let items = reader::get_doc(rbml::Doc::new(cdata.data()), tag_items);
         if let Some(inherent_impl_doc) = maybe_find_item(inherent_impl_def_id.node, items) {
+            cdata.note_decoded(inherent_impl_doc);
             let _ = reader::tagged_docs(inherent_impl_doc,
                                         tag_item_impl_item,
                                         |impl_item_def_id_doc| {
                 let impl_item_def_id = item_def_id(impl_item_def_id_doc,
                                                    cdata);
                 if let Some(impl_method_doc) = maybe_find_item(impl_item_def_id.node, items) {
+                    cdata.note_decoded(impl_method_doc);
                     if let StaticMethod = item_family(impl_method_doc) {
                         // Hand off the static method to the callback.
                         let static_method_name = item_name(&*intr, impl_method_doc);
@@ -634,6 +658,8 @@ fn each_child_of_item_or_crate<F, G>(intr: Rc<IdentInterner>,
 
         // Get the item.
         if let Some(child_item_doc) = maybe_find_item(child_def_id.node, other_crates_items) {
+            crate_data.note_decoded(child_item_doc);
+
             // Hand off the item to the callback.
             let def_like = item_to_def_like(child_item_doc,
                                             child_def_id,
@@ -663,6 +689,7 @@ pub fn each_child_of_item<F, G>(intr: Rc<IdentInterner>,
         None => return,
         Some(item_doc) => item_doc,
     };
+    cdata.note_decoded(item_doc);
 
     each_child_of_item_or_crate(intr,
                                 cdata,
@@ -692,7 +719,7 @@ pub fn each_top_level_item_of_crate<F, G>(intr: Rc<IdentInterner>,
 }
 
 pub fn get_item_path(cdata: Cmd, id: ast::NodeId) -> Vec<ast_map::PathElem> {
-    item_path(lookup_item(id, cdata.data()))
+    item_path(lookup_item(id, cdata))
 }
 
 pub type DecodeInlinedItem<'a> =
@@ -706,7 +733,7 @@ pub fn maybe_get_item_ast<'tcx>(cdata: Cmd, tcx: &ty::ctxt<'tcx>, id: ast::NodeI
                                 mut decode_inlined_item: DecodeInlinedItem)
                                 -> csearch::FoundAst<'tcx> {
     debug!("Looking up item: {}", id);
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     let path = item_path(item_doc).init().to_vec();
     match decode_inlined_item(cdata, tcx, path, item_doc) {
         Ok(ii) => csearch::FoundAst::Found(ii),
@@ -714,7 +741,7 @@ pub fn maybe_get_item_ast<'tcx>(cdata: Cmd, tcx: &ty::ctxt<'tcx>, id: ast::NodeI
             match item_parent_item(item_doc) {
                 Some(did) => {
                     let did = translate_def_id(cdata, did);
-                    let parent_item = lookup_item(did.node, cdata.data());
+                    let parent_item = lookup_item(did.node, cdata);
                     match decode_inlined_item(cdata, tcx, path, parent_item) {
                         Ok(ii) => csearch::FoundAst::FoundParent(did, ii),
                         Err(_) => csearch::FoundAst::NotFound
@@ -730,11 +757,9 @@ pub fn get_enum_variant_defs(intr: &IdentInterner,
                              cdata: Cmd,
                              id: ast::NodeId)
                              -> Vec<(def::Def, ast::Name, ast::Visibility)> {
-    let data = cdata.data();
-    let items = reader::get_doc(rbml::Doc::new(data), tag_items);
-    let item = find_item(id, items);
+    let item = lookup_item(id, cdata);
     enum_variant_ids(item, cdata).iter().map(|did| {
-        let item = find_item(did.node, items);
+        let item = lookup_item(did.node, cdata);
         let name = item_name(intr, item);
         let visibility = item_visibility(item);
         match item_to_def_like(item, *did, cdata.cnum) {
@@ -746,12 +771,10 @@ pub fn get_enum_variant_defs(intr: &IdentInterner,
 
 pub fn get_enum_variants<'tcx>(intr: Rc<IdentInterner>, cdata: Cmd, id: ast::NodeId,
                                tcx: &ty::ctxt<'tcx>) -> Vec<Rc<ty::VariantInfo<'tcx>>> {
-    let data = cdata.data();
-    let items = reader::get_doc(rbml::Doc::new(data), tag_items);
-    let item = find_item(id, items);
+    let item = lookup_item(id, cdata);
     let mut disr_val = 0;
     enum_variant_ids(item, cdata).iter().map(|did| {
-        let item = find_item(did.node, items);
+        let item = lookup_item(did.node, cdata);
         let ctor_ty = item_type(ast::DefId { krate: cdata.cnum, node: id},
                                 item, tcx, cdata);
         let name = item_name(&*intr, item);
@@ -823,7 +846,7 @@ fn get_explicit_self(item: rbml::Doc) -> ty::ExplicitSelfCategory {
 pub fn get_impl_items(cdata: Cmd, impl_id: ast::NodeId)
                       -> Vec<ty::ImplOrTraitItemId> {
     let mut impl_items = Vec::new();
-    reader::tagged_docs(lookup_item(impl_id, cdata.data()),
+    reader::tagged_docs(lookup_item(impl_id, cdata),
                         tag_item_impl_item, |doc| {
         let def_id = item_def_id(doc, cdata);
         match item_sort(doc) {
@@ -843,12 +866,12 @@ pub fn get_trait_name(intr: Rc<IdentInterner>,
                       cdata: Cmd,
                       id: ast::NodeId)
                       -> ast::Name {
-    let doc = lookup_item(id, cdata.data());
+    let doc = lookup_item(id, cdata);
     item_name(&*intr, doc)
 }
 
 pub fn is_static_method(cdata: Cmd, id: ast::NodeId) -> bool {
-    let doc = lookup_item(id, cdata.data());
+    let doc = lookup_item(id, cdata);
     match item_sort(doc) {
         Some('r') | Some('p') => {
             get_explicit_self(doc) == ty::StaticExplicitSelfCategory
@@ -862,13 +885,13 @@ pub fn get_impl_or_trait_item<'tcx>(intr: Rc<IdentInterner>,
                                     id: ast::NodeId,
                                     tcx: &ty::ctxt<'tcx>)
                                     -> ty::ImplOrTraitItem<'tcx> {
-    let method_doc = lookup_item(id, cdata.data());
+    let method_doc = lookup_item(id, cdata);
 
     let def_id = item_def_id(method_doc, cdata);
 
     let container_id = item_reqd_and_translated_parent_item(cdata.cnum,
                                                             method_doc);
-    let container_doc = lookup_item(container_id.node, cdata.data());
+    let container_doc = lookup_item(container_id.node, cdata);
     let container = match item_family(container_doc) {
         Trait => TraitContainer(container_id),
         _ => ImplContainer(container_id),
@@ -909,8 +932,7 @@ pub fn get_impl_or_trait_item<'tcx>(intr: Rc<IdentInterner>,
 
 pub fn get_trait_item_def_ids(cdata: Cmd, id: ast::NodeId)
                               -> Vec<ty::ImplOrTraitItemId> {
-    let data = cdata.data();
-    let item = lookup_item(id, data);
+    let item = lookup_item(id, cdata);
     let mut result = Vec::new();
     reader::tagged_docs(item, tag_item_trait_item, |mth| {
         let def_id = item_def_id(mth, cdata);
@@ -927,8 +949,7 @@ pub fn get_trait_item_def_ids(cdata: Cmd, id: ast::NodeId)
 }
 
 pub fn get_item_variances(cdata: Cmd, id: ast::NodeId) -> ty::ItemVariances {
-    let data = cdata.data();
-    let item_doc = lookup_item(id, data);
+    let item_doc = lookup_item(id, cdata);
     let variance_doc = reader::get_doc(item_doc, tag_item_variances);
     let mut decoder = reader::Decoder::new(variance_doc);
     Decodable::decode(&mut decoder).unwrap()
@@ -939,13 +960,12 @@ pub fn get_provided_trait_methods<'tcx>(intr: Rc<IdentInterner>,
                                         id: ast::NodeId,
                                         tcx: &ty::ctxt<'tcx>)
                                         -> Vec<Rc<ty::Method<'tcx>>> {
-    let data = cdata.data();
-    let item = lookup_item(id, data);
+    let item = lookup_item(id, cdata);
     let mut result = Vec::new();
 
     reader::tagged_docs(item, tag_item_trait_item, |mth_id| {
         let did = item_def_id(mth_id, cdata);
-        let mth = lookup_item(did.node, data);
+        let mth = lookup_item(did.node, cdata);
 
         if item_sort(mth) == Some('p') {
             let trait_item = get_impl_or_trait_item(intr.clone(),
@@ -967,7 +987,7 @@ pub fn get_provided_trait_methods<'tcx>(intr: Rc<IdentInterner>,
 
 pub fn get_type_name_if_impl(cdata: Cmd,
                              node_id: ast::NodeId) -> Option<ast::Name> {
-    let item = lookup_item(node_id, cdata.data());
+    let item = lookup_item(node_id, cdata);
     if item_family(item) != Impl {
         return None;
     }
@@ -985,7 +1005,7 @@ pub fn get_methods_if_impl(intr: Rc<IdentInterner>,
                                   cdata: Cmd,
                                   node_id: ast::NodeId)
                                -> Option<Vec<MethodInfo> > {
-    let item = lookup_item(node_id, cdata.data());
+    let item = lookup_item(node_id, cdata);
     if item_family(item) != Impl {
         return None;
     }
@@ -1005,7 +1025,7 @@ pub fn get_methods_if_impl(intr: Rc<IdentInterner>,
 
     let mut impl_methods = Vec::new();
     for impl_method_id in &impl_method_ids {
-        let impl_method_doc = lookup_item(impl_method_id.node, cdata.data());
+        let impl_method_doc = lookup_item(impl_method_id.node, cdata);
         let family = item_family(impl_method_doc);
         match family {
             StaticMethod | Method => {
@@ -1028,7 +1048,7 @@ pub fn get_tuple_struct_definition_if_ctor(cdata: Cmd,
                                            node_id: ast::NodeId)
     -> Option<ast::DefId>
 {
-    let item = lookup_item(node_id, cdata.data());
+    let item = lookup_item(node_id, cdata);
     let mut ret = None;
     reader::tagged_docs(item, tag_items_data_item_is_tuple_struct_ctor, |_| {
         ret = Some(item_reqd_and_translated_parent_item(cdata.cnum, item));
@@ -1045,7 +1065,7 @@ pub fn get_item_attrs(cdata: Cmd,
     // look at the definition
     let node_id = get_tuple_struct_definition_if_ctor(cdata, orig_node_id);
     let node_id = node_id.map(|x| x.node).unwrap_or(orig_node_id);
-    let item = lookup_item(node_id, cdata.data());
+    let item = lookup_item(node_id, cdata);
     get_attributes(item)
 }
 
@@ -1072,8 +1092,7 @@ fn struct_field_family_to_visibility(family: Family) -> ast::Visibility {
 
 pub fn get_struct_fields(intr: Rc<IdentInterner>, cdata: Cmd, id: ast::NodeId)
     -> Vec<ty::field_ty> {
-    let data = cdata.data();
-    let item = lookup_item(id, data);
+    let item = lookup_item(id, cdata);
     let mut result = Vec::new();
     reader::tagged_docs(item, tag_item_field, |an_item| {
         let f = item_family(an_item);
@@ -1166,18 +1185,6 @@ fn get_attributes(md: rbml::Doc) -> Vec<ast::Attribute> {
     return attrs;
 }
 
-fn list_crate_attributes(md: rbml::Doc, hash: &Svh,
-                         out: &mut io::Write) -> io::Result<()> {
-    try!(write!(out, "=Crate Attributes ({})=\n", *hash));
-
-    let r = get_attributes(md);
-    for attr in &r {
-        try!(write!(out, "{}\n", pprust::attribute_to_string(attr)));
-    }
-
-    write!(out, "\n\n")
-}
-
 pub fn get_crate_attributes(data: &[u8]) -> Vec<ast::Attribute> {
     get_attributes(rbml::Doc::new(data))
 }
@@ -1212,12 +1219,86 @@ pub fn get_crate_deps(data: &[u8]) -> Vec<CrateDep> {
     return deps;
 }
 
-fn list_crate_deps(data: &[u8], out: &mut io::Write) -> io::Result<()> {
-    try!(write!(out, "=External Dependencies=\n"));
-    for dep in &get_crate_deps(data) {
-        try!(write!(out, "{} {}-{}\n", dep.cnum, dep.name, dep.hash));
+/// The header at the start of the metadata of a library, see `common.rs`.
+pub struct MetadataHeader<'a> {
+    pub schema_version: u32,
+    pub rustc_version: &'a [u8],
+    pub checksum: u64,
+    pub data: &'a [u8],
+}
+
+/// Why this compiler can't read the metadata of a library.
+pub enum MetadataError {
+    /// The metadata has no header, so it was written by a rustc older than
+    /// the header itself.
+    Unversioned,
+    /// The metadata was written by the given, different, rustc.
+    Incompatible(String),
+    /// The metadata is shorter than its header says, or doesn't match its
+    /// checksum.
+    Corrupt,
+}
+
+/// Reads the header of the metadata of a library, or returns `None` if it has
+/// none. The metadata may be followed by trailing garbage, which is ignored.
+pub fn get_metadata_header(data: &[u8]) -> Option<MetadataHeader> {
+    fn take<'a>(data: &'a [u8], pos: &mut usize, len: usize) -> Option<&'a [u8]> {
+        if data.len() - *pos < len {
+            return None
+        }
+        let ret = &data[*pos..*pos + len];
+        *pos += len;
+        Some(ret)
+    }
+    fn be(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0, |n, &b| (n << 8) | b as u64)
+    }
+
+    let mut pos = 0;
+    macro_rules! take {
+        ($len:expr) => (match take(data, &mut pos, $len) {
+            Some(bytes) => bytes,
+            None => return None,
+        })
+    }
+    if take!(metadata_magic.len()) != metadata_magic {
+        return None
+    }
+    let schema_version = be(take!(4)) as u32;
+    let version_len = be(take!(4)) as usize;
+    let rustc_version = take!(version_len);
+    let checksum = be(take!(8));
+    let data_len = be(take!(4)) as usize;
+    Some(MetadataHeader {
+        schema_version: schema_version,
+        rustc_version: rustc_version,
+        checksum: checksum,
+        data: take!(data_len),
+    })
+}
+
+/// Checks that this compiler can read the metadata of a library, before
+/// anything is decoded from it. Without `verify_checksum` only the header is
+/// read, which still finds metadata cut short of the length it records.
+/// Verifying the checksum means reading all of the metadata.
+pub fn check_metadata_header(data: &[u8], verify_checksum: bool) -> Result<(), MetadataError> {
+    let header = match get_metadata_header(data) {
+        Some(header) => header,
+        None if data.starts_with(metadata_magic) => return Err(MetadataError::Corrupt),
+        None => return Err(MetadataError::Unversioned),
+    };
+    let version = String::from_utf8_lossy(header.rustc_version).into_owned();
+    if header.schema_version != metadata_schema_version || version != rustc_version() {
+        return Err(MetadataError::Incompatible(version))
+    }
+    if !verify_checksum {
+        return Ok(())
+    }
+    let mut checksum = SipHasher::new();
+    checksum.write(header.data);
+    if checksum.finish() != header.checksum {
+        return Err(MetadataError::Corrupt)
     }
-    try!(write!(out, "\n"));
     Ok(())
 }
 
@@ -1251,11 +1332,327 @@ pub fn get_crate_name(data: &[u8]) -> String {
     maybe_get_crate_name(data).expect("no crate name in crate")
 }
 
-pub fn list_crate_metadata(bytes: &[u8], out: &mut io::Write) -> io::Result<()> {
-    let hash = get_crate_hash(bytes);
-    let md = rbml::Doc::new(bytes);
-    try!(list_crate_attributes(md, &hash, out));
-    list_crate_deps(bytes, out)
+/// Returns the panic strategy a crate was compiled with. Crates compiled
+/// before the strategy was recorded always unwind.
+pub fn get_panic_strategy(data: &[u8]) -> config::PanicStrategy {
+    let cratedoc = rbml::Doc::new(data);
+    match reader::maybe_get_doc(cratedoc, tag_panic_strategy) {
+        Some(doc) if reader::doc_as_u8(doc) != 0 => config::PanicStrategy::Abort,
+        _ => config::PanicStrategy::Unwind,
+    }
+}
+
+/// Returns the paths of the reachable traits of a crate that were
+/// object-safe when it was compiled.
+pub fn get_object_safe_traits(data: &[u8]) -> Vec<String> {
+    let cratedoc = rbml::Doc::new(data);
+    let mut paths = Vec::new();
+    if let Some(traits_doc) = reader::maybe_get_doc(cratedoc, tag_object_safe_traits) {
+        reader::tagged_docs(traits_doc, tag_object_safe_trait, |doc| {
+            paths.push(doc.as_str_slice().to_string());
+            true
+        });
+    }
+    paths
+}
+
+// The summary of a library's metadata printed by `-Z ls`. With
+// `-Z ls-json` it is printed as a single JSON object.
+#[derive(RustcEncodable)]
+struct CrateSummary {
+    name: String,
+    hash: String,
+    triple: Option<String>,
+    rustc_version: String,
+    schema_version: u32,
+    // What is wrong with metadata that is summarized anyway, see
+    // `list_crate_metadata`.
+    warning: Option<String>,
+    attributes: Vec<String>,
+    dependencies: Vec<DependencySummary>,
+    items: Vec<ItemSummary>,
+    native_libraries: Vec<NativeLibrarySummary>,
+    macros: Vec<String>,
+    lang_items: Vec<LangItemSummary>,
+}
+
+// What `-Z ls` prints for metadata of a schema this rustc can't decode:
+// everything but the name comes from the header.
+#[derive(RustcEncodable)]
+struct HeaderSummary {
+    name: Option<String>,
+    rustc_version: String,
+    schema_version: u32,
+    error: String,
+}
+
+#[derive(RustcEncodable)]
+struct DependencySummary {
+    cnum: ast::CrateNum,
+    name: String,
+    hash: String,
+}
+
+#[derive(RustcEncodable)]
+struct ItemSummary {
+    path: String,
+    kind: &'static str,
+    // Whether the item's AST is encoded, so that other crates can inline it.
+    inlinable: bool,
+}
+
+#[derive(RustcEncodable)]
+struct NativeLibrarySummary {
+    name: String,
+    kind: &'static str,
+    cfg: Option<String>,
+}
+
+#[derive(RustcEncodable)]
+struct LangItemSummary {
+    name: &'static str,
+    path: Option<String>,
+}
+
+fn family_name(family: Family) -> Option<&'static str> {
+    match family {
+        ImmStatic => Some("static"),
+        MutStatic => Some("static mut"),
+        Fn => Some("fn"),
+        CtorFn => Some("constructor"),
+        StaticMethod => Some("associated fn"),
+        Method => Some("method"),
+        Type => Some("type"),
+        Mod => Some("mod"),
+        ForeignMod => Some("extern block"),
+        Enum => Some("enum"),
+        TupleVariant | StructVariant => Some("variant"),
+        Trait => Some("trait"),
+        Struct => Some("struct"),
+        Constant => Some("const"),
+        // Impls have no path of their own and fields are listed with
+        // their struct.
+        Impl | DefaultImpl | PublicField | InheritedField => None,
+    }
+}
+
+// Like `item_path`, but as a string prefixed with the crate's name and
+// without interning the elements. Returns `None` for the crate root.
+fn item_path_string(item_doc: rbml::Doc, crate_name: &str) -> Option<String> {
+    let path_doc = match reader::maybe_get_doc(item_doc, tag_path) {
+        Some(doc) => doc,
+        None => return None,
+    };
+    let mut path = crate_name.to_string();
+    let mut empty = true;
+    reader::docs(path_doc, |tag, elt_doc| {
+        if tag == tag_path_elem_mod || tag == tag_path_elem_name {
+            path.push_str("::");
+            path.push_str(elt_doc.as_str_slice());
+            empty = false;
+        }
+        true
+    });
+    if empty { None } else { Some(path) }
+}
+
+fn get_item_summaries(data: &[u8], crate_name: &str) -> Vec<ItemSummary> {
+    let items = reader::get_doc(rbml::Doc::new(data), tag_items);
+    let items_data = reader::get_doc(items, tag_items_data);
+    let mut result = Vec::new();
+    reader::tagged_docs(items_data, tag_items_data_item, |item_doc| {
+        if item_visibility(item_doc) != ast::Public {
+            return true
+        }
+        let kind = match family_name(item_family(item_doc)) {
+            Some(kind) => kind,
+            None => return true,
+        };
+        if let Some(path) = item_path_string(item_doc, crate_name) {
+            let ast_doc = reader::maybe_get_doc(item_doc, astencode_tag::tag_ast as usize);
+            result.push(ItemSummary {
+                path: path,
+                kind: kind,
+                inlinable: ast_doc.is_some(),
+            });
+        }
+        true
+    });
+    result.sort_by(|a, b| a.path.cmp(&b.path));
+    result
+}
+
+fn get_crate_summary(header: &MetadataHeader, warning: Option<String>) -> CrateSummary {
+    let data = header.data;
+    let name = get_crate_name(data);
+
+    let mut lang_item_summaries = Vec::new();
+    let items = reader::get_doc(rbml::Doc::new(data), tag_items);
+    each_lang_item(data, |node_id, index| {
+        lang_item_summaries.push(LangItemSummary {
+            name: lang_items::LanguageItems::item_name(index),
+            path: maybe_find_item(node_id, items).and_then(|item_doc| {
+                item_path_string(item_doc, &name)
+            }),
+        });
+        true
+    });
+
+    let mut macros = Vec::new();
+    let macro_defs = reader::get_doc(rbml::Doc::new(data), tag_macro_defs);
+    reader::tagged_docs(macro_defs, tag_macro_def, |macro_doc| {
+        let name_doc = reader::get_doc(macro_doc, tag_paths_data_name);
+        macros.push(name_doc.as_str_slice().to_string());
+        true
+    });
+
+    CrateSummary {
+        hash: get_crate_hash(data).as_str().to_string(),
+        triple: get_crate_triple(data),
+        rustc_version: String::from_utf8_lossy(header.rustc_version).into_owned(),
+        schema_version: header.schema_version,
+        warning: warning,
+        attributes: get_crate_attributes(data).iter().map(|attr| {
+            pprust::attribute_to_string(attr)
+        }).collect(),
+        dependencies: get_crate_deps(data).into_iter().map(|dep| {
+            DependencySummary {
+                cnum: dep.cnum,
+                name: dep.name,
+                hash: dep.hash.as_str().to_string(),
+            }
+        }).collect(),
+        items: get_item_summaries(data, &name),
+        native_libraries: get_native_libraries(data).into_iter().map(|lib| {
+            NativeLibrarySummary {
+                name: lib.name,
+                kind: match lib.kind {
+                    cstore::NativeStatic => "static",
+                    cstore::NativeFramework => "framework",
+                    cstore::NativeUnknown => "dylib",
+                },
+                cfg: lib.cfg.map(|cfg| pprust::meta_item_to_string(&cfg)),
+            }
+        }).collect(),
+        macros: macros,
+        lang_items: lang_item_summaries,
+        name: name,
+    }
+}
+
+// Like `maybe_get_crate_name`, but for metadata of any schema: gives up
+// instead of panicking if the tag it finds runs past the end of the data.
+fn maybe_get_header_crate_name(data: &[u8]) -> Option<String> {
+    reader::maybe_get_doc(rbml::Doc::new(data), tag_crate_crate_name).and_then(|doc| {
+        if doc.end > data.len() {
+            return None
+        }
+        str::from_utf8(&data[doc.start..doc.end]).ok().map(|name| name.to_string())
+    })
+}
+
+/// Prints a summary of the metadata of a library, given the metadata
+/// including its header, for `-Z ls`. With `as_json` the summary is printed
+/// as a JSON object.
+///
+/// What the header says is always printed. Metadata that this rustc would
+/// refuse to load is still summarized, with a warning, as long as it has the
+/// same schema.
+pub fn list_crate_metadata(bytes: &[u8], as_json: bool,
+                           out: &mut io::Write) -> io::Result<()> {
+    let header = match get_metadata_header(bytes) {
+        Some(header) => header,
+        None if bytes.starts_with(metadata_magic) => {
+            return write!(out, "metadata is corrupt\n")
+        }
+        None => return write!(out, "no versioned metadata found\n"),
+    };
+    let problem = match check_metadata_header(bytes, true) {
+        Ok(()) => None,
+        Err(MetadataError::Incompatible(version)) => {
+            Some(format!("metadata was written by {}, expected {}", version, rustc_version()))
+        }
+        Err(MetadataError::Corrupt) => {
+            Some("metadata doesn't match its checksum".to_string())
+        }
+        Err(MetadataError::Unversioned) => unreachable!(),
+    };
+
+    // The tags of another schema may mean something else, so only the header
+    // and the crate name, if it is where this schema puts it, are printed.
+    if header.schema_version != metadata_schema_version {
+        let summary = HeaderSummary {
+            name: maybe_get_header_crate_name(header.data),
+            rustc_version: String::from_utf8_lossy(header.rustc_version).into_owned(),
+            schema_version: header.schema_version,
+            error: format!("metadata schema {} can't be read, expected {}",
+                           header.schema_version, metadata_schema_version),
+        };
+        if as_json {
+            return write!(out, "{}\n", json::as_json(&summary))
+        }
+        try!(write!(out, "=Crate=\n"));
+        if let Some(ref name) = summary.name {
+            try!(write!(out, "name: {}\n", name));
+        }
+        try!(write!(out, "compiled by: {}\n", summary.rustc_version));
+        try!(write!(out, "schema: {}\n", summary.schema_version));
+        return write!(out, "error: {}\n", summary.error)
+    }
+
+    let summary = get_crate_summary(&header, problem);
+    if as_json {
+        return write!(out, "{}\n", json::as_json(&summary))
+    }
+
+    try!(write!(out, "=Crate=\n"));
+    try!(write!(out, "name: {}\n", summary.name));
+    try!(write!(out, "compiled by: {}\n", summary.rustc_version));
+    try!(write!(out, "schema: {}\n", summary.schema_version));
+    if let Some(ref warning) = summary.warning {
+        try!(write!(out, "warning: {}, so the rest may be wrong\n", warning));
+    }
+    try!(write!(out, "hash: {}\n", summary.hash));
+    if let Some(ref triple) = summary.triple {
+        try!(write!(out, "target: {}\n", triple));
+    }
+    try!(write!(out, "\n"));
+
+    try!(write!(out, "=Crate Attributes ({})=\n", summary.hash));
+    for attr in &summary.attributes {
+        try!(write!(out, "{}\n", attr));
+    }
+
+    try!(write!(out, "\n=External Dependencies=\n"));
+    for dep in &summary.dependencies {
+        try!(write!(out, "{} {}-{}\n", dep.cnum, dep.name, dep.hash));
+    }
+
+    try!(write!(out, "\n=Exported Items=\n"));
+    for item in &summary.items {
+        let inlinable = if item.inlinable { " (inlinable)" } else { "" };
+        try!(write!(out, "{} {}{}\n", item.kind, item.path, inlinable));
+    }
+
+    try!(write!(out, "\n=Native Libraries=\n"));
+    for lib in &summary.native_libraries {
+        match lib.cfg {
+            Some(ref cfg) => try!(write!(out, "{} {} if {}\n", lib.kind, lib.name, cfg)),
+            None => try!(write!(out, "{} {}\n", lib.kind, lib.name)),
+        }
+    }
+
+    try!(write!(out, "\n=Macros=\n"));
+    for name in &summary.macros {
+        try!(write!(out, "{}\n", name));
+    }
+
+    try!(write!(out, "\n=Lang Items=\n"));
+    for item in &summary.lang_items {
+        let path = item.path.as_ref().map(|path| &path[..]).unwrap_or("<unknown>");
+        try!(write!(out, "{}: {}\n", item.name, path));
+    }
+    Ok(())
 }
 
 // Translates a def_id from an external crate to a def_id for the current
@@ -1294,7 +1691,7 @@ pub fn each_implementation_for_type<F>(cdata: Cmd,
                                        mut callback: F)
     where F: FnMut(ast::DefId),
 {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     reader::tagged_docs(item_doc,
                         tag_items_data_item_inherent_impl,
                         |impl_doc| {
@@ -1309,7 +1706,7 @@ pub fn each_implementation_for_trait<F>(cdata: Cmd,
                                         mut callback: F) where
     F: FnMut(ast::DefId),
 {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
 
     let _ = reader::tagged_docs(item_doc,
                                 tag_items_data_item_extension_impl,
@@ -1322,13 +1719,13 @@ pub fn each_implementation_for_trait<F>(cdata: Cmd,
 
 pub fn get_trait_of_item(cdata: Cmd, id: ast::NodeId, tcx: &ty::ctxt)
                          -> Option<ast::DefId> {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     let parent_item_id = match item_parent_item(item_doc) {
         None => return None,
         Some(item_id) => item_id,
     };
     let parent_item_id = translate_def_id(cdata, parent_item_id);
-    let parent_item_doc = lookup_item(parent_item_id.node, cdata.data());
+    let parent_item_doc = lookup_item(parent_item_id.node, cdata);
     match item_family(parent_item_doc) {
         Trait => Some(item_def_id(parent_item_doc, cdata)),
         Impl | DefaultImpl => {
@@ -1340,9 +1737,8 @@ pub fn get_trait_of_item(cdata: Cmd, id: ast::NodeId, tcx: &ty::ctxt)
 }
 
 
-pub fn get_native_libraries(cdata: Cmd)
-                            -> Vec<(cstore::NativeLibraryKind, String)> {
-    let libraries = reader::get_doc(rbml::Doc::new(cdata.data()),
+pub fn get_native_libraries(data: &[u8]) -> Vec<cstore::NativeLibrary> {
+    let libraries = reader::get_doc(rbml::Doc::new(data),
                                     tag_native_libraries);
     let mut result = Vec::new();
     reader::tagged_docs(libraries, tag_native_libraries_lib, |lib_doc| {
@@ -1351,12 +1747,37 @@ pub fn get_native_libraries(cdata: Cmd)
         let kind: cstore::NativeLibraryKind =
             FromPrimitive::from_u32(reader::doc_as_u32(kind_doc)).unwrap();
         let name = name_doc.as_str().to_string();
-        result.push((kind, name));
+        let whole_archive_doc = reader::get_doc(lib_doc, tag_native_libraries_whole_archive);
+        result.push(cstore::NativeLibrary {
+            kind: kind,
+            name: name,
+            cfg: get_native_libraries_cfg(lib_doc),
+            whole_archive: reader::doc_as_u8(whole_archive_doc) != 0,
+        });
         true
     });
     return result;
 }
 
+/// The `#[link(arg = "...")]` arguments of a crate, with their `cfg(...)` predicates.
+pub fn get_linker_args(data: &[u8]) -> Vec<(String, Option<P<ast::MetaItem>>)> {
+    let libraries = reader::get_doc(rbml::Doc::new(data),
+                                    tag_native_libraries);
+    let mut result = Vec::new();
+    reader::tagged_docs(libraries, tag_native_libraries_linker_arg, |arg_doc| {
+        let value_doc = reader::get_doc(arg_doc, tag_native_libraries_linker_arg_value);
+        result.push((value_doc.as_str().to_string(), get_native_libraries_cfg(arg_doc)));
+        true
+    });
+    result
+}
+
+fn get_native_libraries_cfg(doc: rbml::Doc) -> Option<P<ast::MetaItem>> {
+    reader::maybe_get_doc(doc, tag_native_libraries_cfg).map(|cfg_doc| {
+        get_meta_items(cfg_doc).pop().unwrap()
+    })
+}
+
 pub fn get_plugin_registrar_fn(data: &[u8]) -> Option<ast::NodeId> {
     reader::maybe_get_doc(rbml::Doc::new(data), tag_plugin_registrar_fn)
         .map(|doc| FromPrimitive::from_u32(reader::doc_as_u32(doc)).unwrap())
@@ -1416,7 +1837,7 @@ pub fn get_missing_lang_items(cdata: Cmd)
 
 pub fn get_method_arg_names(cdata: Cmd, id: ast::NodeId) -> Vec<String> {
     let mut ret = Vec::new();
-    let method_doc = lookup_item(id, cdata.data());
+    let method_doc = lookup_item(id, cdata);
     match reader::maybe_get_doc(method_doc, tag_method_argument_names) {
         Some(args_doc) => {
             reader::tagged_docs(args_doc, tag_method_argument_name, |name_doc| {
@@ -1444,7 +1865,7 @@ pub fn get_reachable_extern_fns(cdata: Cmd) -> Vec<ast::DefId> {
 }
 
 pub fn is_typedef(cdata: Cmd, id: ast::NodeId) -> bool {
-    let item_doc = lookup_item(id, cdata.data());
+    let item_doc = lookup_item(id, cdata);
     match item_family(item_doc) {
         Type => true,
         _ => false,
@@ -1533,24 +1954,27 @@ pub fn is_associated_type(cdata: Cmd, id: ast::NodeId) -> bool {
     let items = reader::get_doc(rbml::Doc::new(cdata.data()), tag_items);
     match maybe_find_item(id, items) {
         None => false,
-        Some(item) => item_sort(item) == Some('t'),
+        Some(item) => {
+            cdata.note_decoded(item);
+            item_sort(item) == Some('t')
+        }
     }
 }
 
 pub fn is_defaulted_trait(cdata: Cmd, trait_id: ast::NodeId) -> bool {
-    let trait_doc = lookup_item(trait_id, cdata.data());
+    let trait_doc = lookup_item(trait_id, cdata);
     assert!(item_family(trait_doc) == Family::Trait);
     let defaulted_doc = reader::get_doc(trait_doc, tag_defaulted_trait);
     reader::doc_as_u8(defaulted_doc) != 0
 }
 
 pub fn is_default_impl(cdata: Cmd, impl_id: ast::NodeId) -> bool {
-    let impl_doc = lookup_item(impl_id, cdata.data());
+    let impl_doc = lookup_item(impl_id, cdata);
     item_family(impl_doc) == Family::DefaultImpl
 }
 
-pub fn get_imported_filemaps(metadata: &[u8]) -> Vec<codemap::FileMap> {
-    let crate_doc = rbml::Doc::new(metadata);
+pub fn get_imported_filemaps(cdata: Cmd) -> Vec<codemap::FileMap> {
+    let crate_doc = rbml::Doc::new(cdata.data());
     let cm_doc = reader::get_doc(crate_doc, tag_codemap);
 
     let mut filemaps = vec![];
@@ -22,9 +22,11 @@ use metadata::cstore;
 use metadata::decoder;
 use metadata::tyencode;
 use middle::def;
+use middle::reachable;
 use middle::ty::lookup_item_type;
 use middle::ty::{self, Ty};
 use middle::stability;
+use middle::traits;
 use util::nodemap::{FnvHashMap, NodeMap, NodeSet};
 
 use serialize::Encodable;
@@ -310,7 +312,9 @@ fn encode_enum_variant_info(ecx: &EncodeContext,
         encode_repr_attrs(rbml_w, ecx, &variant.node.attrs);
 
         let stab = stability::lookup(ecx.tcx, ast_util::local_def(variant.node.id));
+        let depr = stability::lookup_deprecation(ecx.tcx, ast_util::local_def(variant.node.id));
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
 
         match variant.node.kind {
             ast::TupleVariantKind(_) => {},
@@ -553,7 +557,9 @@ fn encode_info_for_mod(ecx: &EncodeContext,
     encode_visibility(rbml_w, vis);
 
     let stab = stability::lookup(ecx.tcx, ast_util::local_def(id));
+    let depr = stability::lookup_deprecation(ecx.tcx, ast_util::local_def(id));
     encode_stability(rbml_w, stab);
+    encode_deprecation(rbml_w, depr);
 
     // Encode the reexports of this module, if this module is public.
     if vis == ast::Public {
@@ -657,7 +663,9 @@ fn encode_info_for_struct(ecx: &EncodeContext,
         encode_def_id(rbml_w, local_def(id));
 
         let stab = stability::lookup(ecx.tcx, field.id);
+        let depr = stability::lookup_deprecation(ecx.tcx, field.id);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
 
         rbml_w.end_tag();
     }
@@ -688,7 +696,9 @@ fn encode_info_for_struct_ctor(ecx: &EncodeContext,
     }
 
     let stab = stability::lookup(ecx.tcx, ast_util::local_def(ctor_id));
+    let depr = stability::lookup_deprecation(ecx.tcx, ast_util::local_def(ctor_id));
     encode_stability(rbml_w, stab);
+    encode_deprecation(rbml_w, depr);
 
     // indicate that this is a tuple struct ctor, because downstream users will normally want
     // the tuple struct definition, but without this there is no way for them to tell that
@@ -819,7 +829,9 @@ fn encode_info_for_method<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
     encode_item_sort(rbml_w, 'r');
 
     let stab = stability::lookup(ecx.tcx, m.def_id);
+    let depr = stability::lookup_deprecation(ecx.tcx, m.def_id);
     encode_stability(rbml_w, stab);
+    encode_deprecation(rbml_w, depr);
 
     // The type for methods gets encoded twice, which is unfortunate.
     encode_bounds_and_type_for_item(rbml_w, ecx, m.def_id.local_id());
@@ -827,11 +839,14 @@ fn encode_info_for_method<'a, 'tcx>(ecx: &EncodeContext<'a, 'tcx>,
     let elem = ast_map::PathName(m.name);
     encode_path(rbml_w, impl_path.chain(Some(elem).into_iter()));
     if let Some(impl_item) = impl_item_opt {
-        if let ast::MethodImplItem(ref sig, _) = impl_item.node {
+        if let ast::MethodImplItem(ref sig, ref body) = impl_item.node {
             encode_attributes(rbml_w, &impl_item.attrs);
             let scheme = ty::lookup_item_type(ecx.tcx, m.def_id);
             let any_types = !scheme.generics.types.is_empty();
-            if any_types || is_default_impl || attr::requests_inline(&impl_item.attrs) {
+            let auto_inline = ecx.reachable.contains(&m.def_id.node) &&
+                reachable::is_auto_inlinable(ecx.tcx, &impl_item.attrs, sig.abi, body);
+            if any_types || is_default_impl || attr::requests_inline(&impl_item.attrs) ||
+               auto_inline {
                 encode_inlined_item(ecx, rbml_w, IIImplItemRef(local_def(parent_id),
                                                                impl_item));
             }
@@ -867,7 +882,9 @@ fn encode_info_for_associated_type(ecx: &EncodeContext,
     encode_bounds_and_type_for_item(rbml_w, ecx, associated_type.def_id.local_id());
 
     let stab = stability::lookup(ecx.tcx, associated_type.def_id);
+    let depr = stability::lookup_deprecation(ecx.tcx, associated_type.def_id);
     encode_stability(rbml_w, stab);
+    encode_deprecation(rbml_w, depr);
 
     let elem = ast_map::PathName(associated_type.name);
     encode_path(rbml_w, impl_path.chain(Some(elem).into_iter()));
@@ -960,6 +977,14 @@ fn encode_stability(rbml_w: &mut Encoder, stab_opt: Option<attr::Stability>) {
     });
 }
 
+fn encode_deprecation(rbml_w: &mut Encoder, depr_opt: Option<attr::Deprecation>) {
+    depr_opt.map(|depr| {
+        rbml_w.start_tag(tag_items_data_item_deprecation);
+        depr.encode(rbml_w).unwrap();
+        rbml_w.end_tag();
+    });
+}
+
 fn encode_info_for_item(ecx: &EncodeContext,
                         rbml_w: &mut Encoder,
                         item: &ast::Item,
@@ -981,6 +1006,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
 
     let def_id = local_def(item.id);
     let stab = stability::lookup(tcx, ast_util::local_def(item.id));
+    let depr = stability::lookup_deprecation(tcx, ast_util::local_def(item.id));
 
     match item.node {
       ast::ItemStatic(_, m, _) => {
@@ -998,6 +1024,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_path(rbml_w, path);
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         encode_attributes(rbml_w, &item.attrs);
         rbml_w.end_tag();
       }
@@ -1013,9 +1040,10 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_inlined_item(ecx, rbml_w, IIItemRef(item));
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         rbml_w.end_tag();
       }
-      ast::ItemFn(ref decl, _, _, ref generics, _) => {
+      ast::ItemFn(ref decl, _, abi, ref generics, ref body) => {
         add_to_index(item, rbml_w, index);
         rbml_w.start_tag(tag_items_data_item);
         encode_def_id(rbml_w, def_id);
@@ -1025,7 +1053,11 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_name(rbml_w, item.ident.name);
         encode_path(rbml_w, path);
         encode_attributes(rbml_w, &item.attrs);
-        if tps_len > 0 || attr::requests_inline(&item.attrs) {
+        // The bodies of small leaf functions are only worth their space in
+        // the metadata if other crates can call the functions.
+        let auto_inline = ecx.reachable.contains(&item.id) &&
+            reachable::is_auto_inlinable(ecx.tcx, &item.attrs, abi, body);
+        if tps_len > 0 || attr::requests_inline(&item.attrs) || auto_inline {
             encode_inlined_item(ecx, rbml_w, IIItemRef(item));
         }
         if tps_len == 0 {
@@ -1033,6 +1065,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         }
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         encode_method_argument_names(rbml_w, &**decl);
         rbml_w.end_tag();
       }
@@ -1062,6 +1095,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         }
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         rbml_w.end_tag();
       }
       ast::ItemTy(..) => {
@@ -1074,6 +1108,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_path(rbml_w, path);
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         rbml_w.end_tag();
       }
       ast::ItemEnum(ref enum_definition, _) => {
@@ -1098,6 +1133,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
 
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         rbml_w.end_tag();
 
         encode_enum_variant_info(ecx,
@@ -1132,6 +1168,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_attributes(rbml_w, &item.attrs);
         encode_path(rbml_w, path.clone());
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         encode_visibility(rbml_w, vis);
         encode_repr_attrs(rbml_w, ecx, &item.attrs);
 
@@ -1212,6 +1249,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         }
         encode_path(rbml_w, path.clone());
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         rbml_w.end_tag();
 
         // Iterate down the trait items, emitting them. We rely on the
@@ -1273,6 +1311,7 @@ fn encode_info_for_item(ecx: &EncodeContext,
         encode_attributes(rbml_w, &item.attrs);
         encode_visibility(rbml_w, vis);
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         for &method_def_id in &*ty::trait_item_def_ids(tcx, def_id) {
             rbml_w.start_tag(tag_item_trait_item);
             match method_def_id {
@@ -1315,7 +1354,9 @@ fn encode_info_for_item(ecx: &EncodeContext,
             encode_parent_item(rbml_w, def_id);
 
             let stab = stability::lookup(tcx, item_def_id.def_id());
+            let depr = stability::lookup_deprecation(tcx, item_def_id.def_id());
             encode_stability(rbml_w, stab);
+            encode_deprecation(rbml_w, depr);
 
             let trait_item_type =
                 ty::impl_or_trait_item(tcx, item_def_id.def_id());
@@ -1422,7 +1463,9 @@ fn encode_info_for_foreign_item(ecx: &EncodeContext,
         }
         encode_attributes(rbml_w, &*nitem.attrs);
         let stab = stability::lookup(ecx.tcx, ast_util::local_def(nitem.id));
+        let depr = stability::lookup_deprecation(ecx.tcx, ast_util::local_def(nitem.id));
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         encode_symbol(ecx, rbml_w, nitem.id);
         encode_method_argument_names(rbml_w, &*fndecl);
       }
@@ -1435,7 +1478,9 @@ fn encode_info_for_foreign_item(ecx: &EncodeContext,
         encode_bounds_and_type_for_item(rbml_w, ecx, nitem.id);
         encode_attributes(rbml_w, &*nitem.attrs);
         let stab = stability::lookup(ecx.tcx, ast_util::local_def(nitem.id));
+        let depr = stability::lookup_deprecation(ecx.tcx, ast_util::local_def(nitem.id));
         encode_stability(rbml_w, stab);
+        encode_deprecation(rbml_w, depr);
         encode_symbol(ecx, rbml_w, nitem.id);
         encode_name(rbml_w, nitem.ident.name);
       }
@@ -1722,17 +1767,31 @@ fn encode_lang_items(ecx: &EncodeContext, rbml_w: &mut Encoder) {
 fn encode_native_libraries(ecx: &EncodeContext, rbml_w: &mut Encoder) {
     rbml_w.start_tag(tag_native_libraries);
 
-    for &(ref lib, kind) in ecx.tcx.sess.cstore.get_used_libraries()
-                               .borrow().iter() {
-        match kind {
-            cstore::NativeStatic => {} // these libraries are not propagated
-            cstore::NativeFramework | cstore::NativeUnknown => {
-                rbml_w.start_tag(tag_native_libraries_lib);
-                rbml_w.wr_tagged_u32(tag_native_libraries_kind, kind as u32);
-                rbml_w.wr_tagged_str(tag_native_libraries_name, lib);
-                rbml_w.end_tag();
-            }
+    // Static libraries are bundled into the rlib rather than linked again
+    // downstream, but are still listed so that the crates linking the rlib
+    // know whether it has to be linked whole.
+    for lib in ecx.tcx.sess.cstore.get_used_libraries().borrow().iter() {
+        rbml_w.start_tag(tag_native_libraries_lib);
+        rbml_w.wr_tagged_u32(tag_native_libraries_kind, lib.kind as u32);
+        rbml_w.wr_tagged_str(tag_native_libraries_name, &lib.name);
+        rbml_w.wr_tagged_u8(tag_native_libraries_whole_archive, lib.whole_archive as u8);
+        if let Some(ref cfg) = lib.cfg {
+            rbml_w.start_tag(tag_native_libraries_cfg);
+            encode_meta_item(rbml_w, cfg);
+            rbml_w.end_tag();
+        }
+        rbml_w.end_tag();
+    }
+
+    for &(ref arg, ref cfg) in ecx.tcx.sess.cstore.get_used_linker_args().borrow().iter() {
+        rbml_w.start_tag(tag_native_libraries_linker_arg);
+        rbml_w.wr_tagged_str(tag_native_libraries_linker_arg_value, arg);
+        if let Some(ref cfg) = *cfg {
+            rbml_w.start_tag(tag_native_libraries_cfg);
+            encode_meta_item(rbml_w, cfg);
+            rbml_w.end_tag();
         }
+        rbml_w.end_tag();
     }
 
     rbml_w.end_tag();
@@ -1896,6 +1955,33 @@ fn encode_reachable_extern_fns(ecx: &EncodeContext, rbml_w: &mut Encoder) {
     rbml_w.end_tag();
 }
 
+/// Encodes the paths of the reachable traits that are object-safe, so that
+/// a later build of this crate can detect traits that lose object safety.
+fn encode_object_safe_traits(ecx: &EncodeContext, rbml_w: &mut Encoder) {
+    rbml_w.start_tag(tag_object_safe_traits);
+
+    // `reachable` is a hash set; sort the traits so that the metadata is
+    // the same from one build to the next.
+    let mut trait_ids: Vec<ast::DefId> = ecx.reachable.iter().filter_map(|&id| {
+        match ecx.tcx.map.find(id) {
+            Some(ast_map::NodeItem(i)) => match i.node {
+                ast::ItemTrait(..) => Some(local_def(id)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }).collect();
+    trait_ids.sort();
+
+    for def_id in trait_ids {
+        if traits::is_object_safe(ecx.tcx, def_id) {
+            rbml_w.wr_tagged_str(tag_object_safe_trait, &ty::item_path_str(ecx.tcx, def_id));
+        }
+    }
+
+    rbml_w.end_tag();
+}
+
 fn encode_crate_dep(rbml_w: &mut Encoder,
                     dep: decoder::CrateDep) {
     rbml_w.start_tag(tag_crate_dep);
@@ -1916,6 +2002,11 @@ fn encode_crate_triple(rbml_w: &mut Encoder, triple: &str) {
     rbml_w.wr_tagged_str(tag_crate_triple, triple);
 }
 
+fn encode_panic_strategy(rbml_w: &mut Encoder, strategy: config::PanicStrategy) {
+    let abort = strategy == config::PanicStrategy::Abort;
+    rbml_w.wr_tagged_u8(tag_panic_strategy, abort as u8);
+}
+
 fn encode_dylib_dependency_formats(rbml_w: &mut Encoder, ecx: &EncodeContext) {
     let tag = tag_dylib_dependency_formats;
     match ecx.tcx.dependency_formats.borrow().get(&config::CrateTypeDylib) {
@@ -1936,7 +2027,7 @@ fn encode_dylib_dependency_formats(rbml_w: &mut Encoder, ecx: &EncodeContext) {
 
 // NB: Increment this as you change the metadata encoding version.
 #[allow(non_upper_case_globals)]
-pub const metadata_encoding_version : &'static [u8] = &[b'r', b'u', b's', b't', 0, 0, 0, 2 ];
+pub const metadata_encoding_version : &'static [u8] = &[b'r', b'u', b's', b't', 0, 0, 0, 3 ];
 
 pub fn encode_metadata(parms: EncodeParams, krate: &ast::Crate) -> Vec<u8> {
     let mut wr = Cursor::new(Vec::new());
@@ -1963,16 +2054,28 @@ pub fn encode_metadata(parms: EncodeParams, krate: &ast::Crate) -> Vec<u8> {
     // being interpreted causing the out-of-bounds.
     //
     // The root cause of why these extra bytes were appearing was never
-    // discovered, and in the meantime the solution we're employing is to insert
-    // the length of the metadata to the start of the metadata. Later on this
-    // will allow us to slice the metadata to the precise length that we just
-    // generated regardless of trailing bytes that end up in it.
-    let len = v.len() as u32;
-    v.insert(0, (len >>  0) as u8);
-    v.insert(0, (len >>  8) as u8);
-    v.insert(0, (len >> 16) as u8);
-    v.insert(0, (len >> 24) as u8);
-    return v;
+    // discovered, and in the meantime the solution we're employing is to record
+    // the length of the metadata in its header. Later on this will allow us to
+    // slice the metadata to the precise length that we just generated
+    // regardless of trailing bytes that end up in it.
+    let version = rustc_version();
+    let mut checksum = SipHasher::new();
+    checksum.write(&v);
+
+    let mut header = metadata_magic.to_vec();
+    push_be(&mut header, metadata_schema_version as u64, 4);
+    push_be(&mut header, version.len() as u64, 4);
+    header.push_all(version.as_bytes());
+    push_be(&mut header, checksum.finish(), 8);
+    push_be(&mut header, v.len() as u64, 4);
+    header.push_all(&v);
+    return header;
+}
+
+fn push_be(v: &mut Vec<u8>, n: u64, bytes: usize) {
+    for i in (0..bytes).rev() {
+        v.push((n >> (i * 8)) as u8);
+    }
 }
 
 fn encode_metadata_inner(wr: &mut Cursor<Vec<u8>>,
@@ -2040,6 +2143,7 @@ fn encode_metadata_inner(wr: &mut Cursor<Vec<u8>>,
                            .target_triple
                            );
     encode_hash(&mut rbml_w, &ecx.link_meta.crate_hash);
+    encode_panic_strategy(&mut rbml_w, tcx.sess.opts.cg.panic);
     encode_dylib_dependency_formats(&mut rbml_w, &ecx);
 
     let mut i = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap();
@@ -2084,6 +2188,7 @@ fn encode_metadata_inner(wr: &mut Cursor<Vec<u8>>,
     i = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap();
     encode_misc_info(&ecx, krate, &mut rbml_w);
     encode_reachable_extern_fns(&ecx, &mut rbml_w);
+    encode_object_safe_traits(&ecx, &mut rbml_w);
     stats.misc_bytes = rbml_w.writer.seek(SeekFrom::Current(0)).unwrap() - i;
 
     // Encode and index the items.
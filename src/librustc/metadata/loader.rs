@@ -79,9 +79,15 @@
 //!    This is filtering for files like `libfoo*.rlib` and such.
 //! 3. Is the file an actual rust library? This is done by loading the metadata
 //!    from the library and making sure it's actually there.
-//! 4. Does the name in the metadata agree with the name of the library?
-//! 5. Does the target in the metadata agree with the current target?
-//! 6. Does the SVH match? (more on this later)
+//! 4. Can the metadata be read? The header of the metadata records the version
+//!    of the rustc that wrote it, which must be this one, the length of the
+//!    metadata, which must all be there, and a checksum of the metadata, which
+//!    it must match. Hashing the metadata reads every byte of it, even though
+//!    only the items looked up through its index are decoded, and
+//!    `-Z no-verify-metadata` skips it.
+//! 5. Does the name in the metadata agree with the name of the library?
+//! 6. Does the target in the metadata agree with the current target?
+//! 7. Does the SVH match? (more on this later)
 //!
 //! If the file answers `yes` to all these questions, then the file is
 //! considered as being *candidate* for being accepted. It is illegal to have
@@ -219,6 +225,7 @@ use session::search_paths::PathKind;
 use llvm;
 use llvm::{False, ObjectFile, mk_section_iter};
 use llvm::archive_ro::ArchiveRO;
+use metadata::common::rustc_version;
 use metadata::cstore::{MetadataBlob, MetadataVec, MetadataArchive};
 use metadata::decoder;
 use metadata::encoder;
@@ -259,6 +266,8 @@ pub struct Context<'a> {
     pub rejected_via_hash: Vec<CrateMismatch>,
     pub rejected_via_triple: Vec<CrateMismatch>,
     pub rejected_via_kind: Vec<CrateMismatch>,
+    pub rejected_via_version: Vec<CrateMismatch>,
+    pub rejected_via_checksum: Vec<CrateMismatch>,
     pub should_match_name: bool,
 }
 
@@ -307,7 +316,12 @@ impl<'a> Context<'a> {
     }
 
     pub fn report_load_errs(&mut self) {
-        let message = if !self.rejected_via_hash.is_empty() {
+        let message = if !self.rejected_via_version.is_empty() {
+            format!("found crate `{}` compiled by an incompatible version of rustc",
+                    self.ident)
+        } else if !self.rejected_via_checksum.is_empty() {
+            format!("found crate `{}` with corrupt metadata", self.ident)
+        } else if !self.rejected_via_hash.is_empty() {
             format!("found possibly newer version of crate `{}`",
                     self.ident)
         } else if !self.rejected_via_triple.is_empty() {
@@ -325,6 +339,24 @@ impl<'a> Context<'a> {
         };
         self.sess.span_err(self.span, &message[..]);
 
+        if !self.rejected_via_version.is_empty() {
+            let mismatches = self.rejected_via_version.iter();
+            for (i, &CrateMismatch { ref path, ref got }) in mismatches.enumerate() {
+                self.sess.fileline_note(self.span,
+                    &format!("crate `{}` path #{}: {} compiled by {}, expected {}",
+                             self.ident, i+1, path.display(), got, rustc_version()));
+            }
+            self.sess.fileline_help(self.span, "please recompile that crate using this \
+                                                compiler");
+        }
+        if !self.rejected_via_checksum.is_empty() {
+            let mismatches = self.rejected_via_checksum.iter();
+            for (i, &CrateMismatch { ref path, .. }) in mismatches.enumerate() {
+                self.sess.fileline_note(self.span,
+                    &format!("crate `{}` path #{}: {}",
+                             self.ident, i+1, path.display()));
+            }
+        }
         if !self.rejected_via_triple.is_empty() {
             let mismatches = self.rejected_via_triple.iter();
             for (i, &CrateMismatch{ ref path, ref got }) in mismatches.enumerate() {
@@ -529,7 +561,8 @@ impl<'a> Context<'a> {
             let metadata = match get_metadata_section(self.target.options.is_like_osx,
                                                       &lib) {
                 Ok(blob) => {
-                    if self.crate_matches(blob.as_slice(), &lib) {
+                    if self.crate_readable(blob.as_bytes(), &lib) &&
+                       self.crate_matches(blob.as_slice(), &lib) {
                         blob
                     } else {
                         info!("metadata mismatch");
@@ -567,6 +600,32 @@ impl<'a> Context<'a> {
         return if error > 0 {None} else {ret}
     }
 
+    // Whether this compiler can read the metadata of a library. Libraries it
+    // can't read are left for the error report, should no other candidate be
+    // found.
+    fn crate_readable(&mut self, metadata: &[u8], libpath: &Path) -> bool {
+        let verify_checksum = !self.sess.opts.debugging_opts.no_verify_metadata;
+        let got = match decoder::check_metadata_header(metadata, verify_checksum) {
+            Ok(()) => return true,
+            Err(decoder::MetadataError::Corrupt) => {
+                info!("Rejecting via metadata checksum");
+                self.rejected_via_checksum.push(CrateMismatch {
+                    path: libpath.to_path_buf(),
+                    got: String::new(),
+                });
+                return false
+            }
+            Err(decoder::MetadataError::Unversioned) => "an older rustc".to_string(),
+            Err(decoder::MetadataError::Incompatible(version)) => version,
+        };
+        info!("Rejecting via rustc version: expected {} got {}", rustc_version(), got);
+        self.rejected_via_version.push(CrateMismatch {
+            path: libpath.to_path_buf(),
+            got: got,
+        });
+        false
+    }
+
     fn crate_matches(&mut self, crate_data: &[u8], libpath: &Path) -> bool {
         if self.should_match_name {
             match decoder::maybe_get_crate_name(crate_data) {
@@ -811,12 +870,20 @@ pub fn read_meta_section_name(is_osx: bool) -> &'static str {
 }
 
 // A diagnostic function for dumping crate metadata to an output stream
-pub fn list_file_metadata(is_osx: bool, path: &Path,
+pub fn list_file_metadata(is_osx: bool, path: &Path, as_json: bool,
                           out: &mut io::Write) -> io::Result<()> {
     match get_metadata_section(is_osx, path) {
-        Ok(bytes) => decoder::list_crate_metadata(bytes.as_slice(), out),
+        Ok(bytes) => decoder::list_crate_metadata(bytes.as_bytes(), as_json, out),
         Err(msg) => {
             write!(out, "{}\n", msg)
         }
     }
 }
+
+/// Reads the paths of the traits that were object-safe in a previously
+/// compiled library, for the `object_safety_regression` lint.
+pub fn read_object_safe_traits(is_osx: bool, path: &Path) -> Result<Vec<String>, String> {
+    get_metadata_section(is_osx, path).map(|bytes| {
+        decoder::get_object_safe_traits(bytes.as_slice())
+    })
+}
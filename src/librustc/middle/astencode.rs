@@ -229,12 +229,11 @@ impl<'a, 'b, 'tcx> DecodeContext<'a, 'b, 'tcx> {
     }
 
     /// Translates a `Span` from an extern crate to the corresponding `Span`
-    /// within the local crate's codemap. `creader::import_codemap()` will
-    /// already have allocated any additionally needed FileMaps in the local
-    /// codemap as a side-effect of creating the crate_metadata's
-    /// `codemap_import_info`.
+    /// within the local crate's codemap. The first span translated from a
+    /// crate makes `creader::import_codemap()` allocate any additionally
+    /// needed FileMaps in the local codemap.
     pub fn tr_span(&self, span: Span) -> Span {
-        let imported_filemaps = &self.cdata.codemap_import_info[..];
+        let imported_filemaps = self.cdata.imported_filemaps(self.tcx.sess.codemap());
 
         let span = if span.lo > span.hi {
             // Currently macro expansion sometimes produces invalid Span values
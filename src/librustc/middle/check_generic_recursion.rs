@@ -0,0 +1,302 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! This compiler pass detects polymorphic recursion that would lead to an
+//! infinite number of monomorphizations, e.g.:
+//!
+//! ```ignore
+//! fn generic<T>() {
+//!     generic::<Option<T>>();
+//! }
+//! ```
+//!
+//! Without this check, such code is only rejected once trans hits the
+//! recursion limit, with a span pointing somewhere in the middle of the
+//! instantiation chain. Here we build a graph whose nodes are the type
+//! parameters of every local generic function. A call `bar::<U>()` inside
+//! `foo<T>` adds an edge from each parameter of `foo` mentioned in `U` to
+//! the corresponding parameter of `bar`; the edge is "growing" if the
+//! parameter appears strictly inside `U` rather than being `U` itself. Any
+//! cycle that contains a growing edge means each trip around the cycle
+//! instantiates a strictly larger type, so monomorphization cannot finish.
+//! Parameters that only appear inside projections such as `T::Item` add no
+//! edges, since normalizing the projection may produce a smaller type.
+//!
+//! Only cycles that are actually instantiated are reported: a generic
+//! function that is never reached from a non-generic function (or from a
+//! constant) in this crate is never monomorphized here, and has always
+//! compiled fine, so rejecting it would be a breaking change.
+//!
+//! Calls that are only resolved during trans (trait methods invoked on
+//! type parameters or trait objects) are not followed, so this check is
+//! conservative: trans still reports the recursion limit for those.
+
+use middle::def::{DefFn, DefMethod};
+use middle::subst::ParamSpace;
+use middle::ty::{self, Ty};
+use util::nodemap::{DefIdSet, FnvHashMap};
+use util::ppaux::{Repr, ty_to_string};
+
+use std::collections::{HashSet, VecDeque};
+use syntax::ast;
+use syntax::ast_map;
+use syntax::ast_util::local_def;
+use syntax::codemap::Span;
+use syntax::visit::Visitor;
+use syntax::visit;
+
+/// A type parameter of a local function: the function, and the space and
+/// index of the parameter.
+type ParamNode = (ast::DefId, ParamSpace, usize);
+
+struct Edge<'tcx> {
+    from: ParamNode,
+    to: ParamNode,
+    span: Span,
+    // The type the callee parameter is instantiated with, if it strictly
+    // contains the caller parameter.
+    growing: Option<Ty<'tcx>>,
+}
+
+struct CallGraphVisitor<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    // Stack of the functions we are currently inside of. Closures are
+    // attributed to their enclosing function, since they share its
+    // type parameters.
+    fn_stack: Vec<ast::DefId>,
+    edges: Vec<Edge<'tcx>>,
+    // Every call to a local function, by caller.
+    callees: FnvHashMap<ast::DefId, Vec<ast::DefId>>,
+    // Functions that trans instantiates without any outer substitution:
+    // non-generic functions and methods, and functions named from constants.
+    roots: Vec<ast::DefId>,
+}
+
+pub fn check_crate(tcx: &ty::ctxt) {
+    let mut visitor = CallGraphVisitor {
+        tcx: tcx,
+        fn_stack: Vec::new(),
+        edges: Vec::new(),
+        callees: FnvHashMap(),
+        roots: Vec::new(),
+    };
+    visit::walk_crate(&mut visitor, tcx.map.krate());
+
+    let instantiated = instantiated_fns(&visitor.callees, visitor.roots);
+    report_growing_cycles(tcx, &visitor.edges, &instantiated);
+}
+
+impl<'a, 'tcx> CallGraphVisitor<'a, 'tcx> {
+    fn record_call(&mut self, span: Span, callee: ast::DefId, substs: &ty::ItemSubsts<'tcx>) {
+        // Only local functions have bodies we can follow.
+        if callee.krate != ast::LOCAL_CRATE {
+            return;
+        }
+
+        let caller = match self.fn_stack.last() {
+            Some(&caller) => caller,
+            None => {
+                // Named from a static or constant, whose substitutions are
+                // always concrete.
+                self.roots.push(callee);
+                return;
+            }
+        };
+        self.callees.entry(caller).or_insert(Vec::new()).push(callee);
+
+        for (space, index, &ty) in substs.substs.types.iter_enumerated() {
+            let mut walker = ty.walk();
+            while let Some(component) = walker.next() {
+                // A parameter reached through a projection such as
+                // `T::Next` does not make the type grow: the projection may
+                // well normalize to something smaller than `T`.
+                if let ty::ty_projection(..) = component.sty {
+                    walker.skip_current_subtree();
+                    continue;
+                }
+                if let ty::ty_param(ref p) = component.sty {
+                    let growing = if component == ty { None } else { Some(ty) };
+                    debug!("record_call: {:?}/{:?}/{} -> {:?}/{:?}/{} via {}",
+                           caller, p.space, p.idx, callee, space, index,
+                           ty.repr(self.tcx));
+                    self.edges.push(Edge {
+                        from: (caller, p.space, p.idx as usize),
+                        to: (callee, space, index),
+                        span: span,
+                        growing: growing,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for CallGraphVisitor<'a, 'tcx> {
+    fn visit_fn(&mut self, fk: visit::FnKind<'v>, fd: &'v ast::FnDecl,
+                b: &'v ast::Block, s: Span, id: ast::NodeId) {
+        match fk {
+            visit::FkItemFn(..) | visit::FkMethod(..) => {
+                let def_id = local_def(id);
+                if !is_generic_fn(self.tcx, def_id) {
+                    self.roots.push(def_id);
+                }
+                self.fn_stack.push(def_id);
+                visit::walk_fn(self, fk, fd, b, s);
+                self.fn_stack.pop();
+            }
+            visit::FkFnBlock(..) => {
+                visit::walk_fn(self, fk, fd, b, s);
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &ast::Expr) {
+        match expr.node {
+            ast::ExprPath(..) => {
+                match ty::resolve_expr(self.tcx, expr) {
+                    DefFn(did, _) | DefMethod(did, _) => {
+                        let substs = ty::node_id_item_substs(self.tcx, expr.id);
+                        self.record_call(expr.span, did, &substs);
+                    }
+                    _ => {}
+                }
+            }
+            ast::ExprMethodCall(..) => {
+                let method_call = ty::MethodCall::expr(expr.id);
+                let callee = self.tcx.method_map.borrow().get(&method_call).cloned();
+                if let Some(ty::MethodCallee { origin: ty::MethodStatic(did), substs, .. }) =
+                        callee {
+                    let substs = ty::ItemSubsts { substs: substs };
+                    self.record_call(expr.span, did, &substs);
+                }
+            }
+            _ => {}
+        }
+
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// Whether the function or method has type parameters of its own or from its
+/// impl or trait, i.e. whether trans only translates it when instantiated.
+fn is_generic_fn(tcx: &ty::ctxt, def_id: ast::DefId) -> bool {
+    match tcx.map.find(def_id.node) {
+        Some(ast_map::NodeItem(_)) => {
+            !ty::lookup_item_type(tcx, def_id).generics.types.is_empty()
+        }
+        _ => {
+            match ty::impl_or_trait_item(tcx, def_id) {
+                ty::MethodTraitItem(ref method) => !method.generics.types.is_empty(),
+                _ => true,
+            }
+        }
+    }
+}
+
+/// The local functions reachable through direct calls from `roots`.
+fn instantiated_fns(callees: &FnvHashMap<ast::DefId, Vec<ast::DefId>>,
+                    roots: Vec<ast::DefId>)
+                    -> DefIdSet {
+    let mut seen = DefIdSet();
+    let mut worklist = roots;
+    while let Some(did) = worklist.pop() {
+        if !seen.insert(did) {
+            continue;
+        }
+        if let Some(out) = callees.get(&did) {
+            worklist.extend(out.iter().cloned());
+        }
+    }
+    seen
+}
+
+fn report_growing_cycles<'tcx>(tcx: &ty::ctxt<'tcx>,
+                               edges: &[Edge<'tcx>],
+                               instantiated: &DefIdSet) {
+    let mut successors: FnvHashMap<ParamNode, Vec<usize>> = FnvHashMap();
+    for (i, edge) in edges.iter().enumerate() {
+        successors.entry(edge.from).or_insert(Vec::new()).push(i);
+    }
+
+    // Report each function at most once, even if it participates in
+    // several growing cycles.
+    let mut reported = HashSet::new();
+
+    for edge in edges {
+        let grown_ty = match edge.growing {
+            Some(ty) => ty,
+            None => continue,
+        };
+        // A cycle that is never entered from concrete code never reaches
+        // trans; every function on the cycle is reachable from every other,
+        // so checking one of them is enough.
+        if reported.contains(&edge.from.0) || !instantiated.contains(&edge.from.0) {
+            continue;
+        }
+
+        let path = match find_path(&successors, edges, edge.to, edge.from) {
+            Some(path) => path,
+            None => continue,
+        };
+
+        span_err!(tcx.sess, edge.span, E0374,
+                  "instantiating `{}` here recurses infinitely: each call instantiates \
+                   it with `{}`, which grows on every iteration",
+                  ty::item_path_str(tcx, edge.to.0),
+                  ty_to_string(tcx, grown_ty));
+        reported.insert(edge.from.0);
+        for &i in &path {
+            let step = &edges[i];
+            tcx.sess.span_note(step.span,
+                               &format!("`{}` calls `{}` here",
+                                        ty::item_path_str(tcx, step.from.0),
+                                        ty::item_path_str(tcx, step.to.0)));
+            reported.insert(step.from.0);
+        }
+    }
+}
+
+/// Breadth-first search for a path of edges from `start` to `goal`,
+/// returning the indices of the edges taken.
+fn find_path(successors: &FnvHashMap<ParamNode, Vec<usize>>,
+             edges: &[Edge],
+             start: ParamNode,
+             goal: ParamNode)
+             -> Option<Vec<usize>> {
+    let mut queue = VecDeque::new();
+    let mut came_from: FnvHashMap<ParamNode, Option<usize>> = FnvHashMap();
+    came_from.insert(start, None);
+    queue.push_back(start);
+
+    while let Some(node) = queue.pop_front() {
+        if node == goal {
+            let mut path = Vec::new();
+            let mut current = node;
+            while let Some(&Some(i)) = came_from.get(&current) {
+                path.push(i);
+                current = edges[i].from;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        if let Some(out) = successors.get(&node) {
+            for &i in out {
+                let next = edges[i].to;
+                if !came_from.contains_key(&next) {
+                    came_from.insert(next, Some(i));
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    None
+}
@@ -315,6 +315,7 @@ lets_do_this! {
     StrDupUniqFnLangItem,            "strdup_uniq",             strdup_uniq_fn;
 
     StartFnLangItem,                 "start",                   start_fn;
+    AbortOnPanicFnLangItem,          "abort_on_panic",          abort_on_panic_fn;
 
     EhPersonalityLangItem,           "eh_personality",          eh_personality;
 
@@ -13,7 +13,8 @@
 // All items that can be referred to through an exported name are
 // reachable, and when a reachable thing is inline or generic, it
 // makes all other generics or inline functions that it references
-// reachable as well.
+// reachable as well. Small leaf functions count as inline, see
+// `is_auto_inlinable`.
 
 use middle::def;
 use middle::ty;
@@ -36,17 +37,102 @@ fn generics_require_inlining(generics: &ast::Generics) -> bool {
     !generics.ty_params.is_empty()
 }
 
+/// Returns true if a function without type parameters or `#[inline]` should
+/// have its body serialized for cross-crate inlining anyway, because it is a
+/// small leaf function: one that calls no other functions (overloaded
+/// operators included) and has at most `-C cross-crate-inline-threshold`
+/// expressions.
+pub fn is_auto_inlinable(tcx: &ty::ctxt, attrs: &[ast::Attribute], abi: abi::Abi,
+                         body: &ast::Block) -> bool {
+    let threshold = tcx.sess.opts.cg.cross_crate_inline_threshold;
+    if threshold == 0 || abi != abi::Rust {
+        return false
+    }
+    match attr::find_inline_attr(None, attrs) {
+        attr::InlineAttr::None => {}
+        _ => return false,
+    }
+    if attr::contains_name(attrs, "no_mangle") || attr::contains_name(attrs, "export_name") {
+        return false
+    }
+
+    let mut visitor = LeafFnVisitor {
+        tcx: tcx,
+        exprs: 0,
+        threshold: threshold,
+        is_leaf: true,
+    };
+    visit::walk_block(&mut visitor, body);
+    visitor.is_leaf
+}
+
+struct LeafFnVisitor<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    exprs: usize,
+    threshold: usize,
+    is_leaf: bool,
+}
+
+impl<'a, 'tcx> LeafFnVisitor<'a, 'tcx> {
+    fn is_overloaded(&self, expr: &ast::Expr) -> bool {
+        let method_map = self.tcx.method_map.borrow();
+        if method_map.contains_key(&ty::MethodCall::expr(expr.id)) {
+            return true
+        }
+        match self.tcx.adjustments.borrow().get(&expr.id) {
+            Some(&ty::AdjustDerefRef(ref adj)) => {
+                (0..adj.autoderefs).any(|i| {
+                    method_map.contains_key(&ty::MethodCall::autoderef(expr.id, i as u32))
+                })
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for LeafFnVisitor<'a, 'tcx> {
+    fn visit_expr(&mut self, expr: &ast::Expr) {
+        self.exprs += 1;
+        let is_leaf = match expr.node {
+            // Calling a tuple struct or variant constructor is fine.
+            ast::ExprCall(ref callee, _) => {
+                match self.tcx.def_map.borrow().get(&callee.id).map(|d| d.full_def()) {
+                    Some(def::DefStruct(..)) | Some(def::DefVariant(..)) => true,
+                    _ => false,
+                }
+            }
+            ast::ExprMethodCall(..) |
+            ast::ExprClosure(..) |
+            ast::ExprInlineAsm(..) => false,
+            _ => !self.is_overloaded(expr),
+        };
+        if !is_leaf || self.exprs > self.threshold {
+            self.is_leaf = false;
+        }
+        if self.is_leaf {
+            visit::walk_expr(self, expr)
+        }
+    }
+
+    fn visit_item(&mut self, _item: &ast::Item) {
+        self.is_leaf = false;
+    }
+}
+
 // Returns true if the given item must be inlined because it may be
-// monomorphized or it was marked with `#[inline]`. This will only return
-// true for functions.
-fn item_might_be_inlined(item: &ast::Item) -> bool {
+// monomorphized, it was marked with `#[inline]` or it is small enough to be
+// inlined automatically. This will only return true for functions.
+fn item_might_be_inlined(tcx: &ty::ctxt, item: &ast::Item) -> bool {
     if attr::requests_inline(&item.attrs) {
         return true
     }
 
     match item.node {
-        ast::ItemImpl(_, _, ref generics, _, _, _) |
-        ast::ItemFn(_, _, _, ref generics, _) => {
+        ast::ItemFn(_, _, abi, ref generics, ref body) => {
+            generics_require_inlining(generics) ||
+                is_auto_inlinable(tcx, &item.attrs, abi, body)
+        }
+        ast::ItemImpl(_, _, ref generics, _, _, _) => {
             generics_require_inlining(generics)
         }
         _ => false,
@@ -55,16 +141,18 @@ fn item_might_be_inlined(item: &ast::Item) -> bool {
 
 fn method_might_be_inlined(tcx: &ty::ctxt, sig: &ast::MethodSig,
                            impl_item: &ast::ImplItem,
+                           body: &ast::Block,
                            impl_src: ast::DefId) -> bool {
     if attr::requests_inline(&impl_item.attrs) ||
-        generics_require_inlining(&sig.generics) {
+        generics_require_inlining(&sig.generics) ||
+        is_auto_inlinable(tcx, &impl_item.attrs, sig.abi, body) {
         return true
     }
     if is_local(impl_src) {
         {
             match tcx.map.find(impl_src.node) {
                 Some(ast_map::NodeItem(item)) => {
-                    item_might_be_inlined(&*item)
+                    item_might_be_inlined(tcx, &*item)
                 }
                 Some(..) | None => {
                     tcx.sess.span_bug(impl_item.span, "impl did is not an item")
@@ -88,6 +176,9 @@ struct ReachableContext<'a, 'tcx: 'a> {
     worklist: Vec<ast::NodeId>,
     // Whether any output of this compilation is a library
     any_library: bool,
+    // Whether any output of this compilation is a cdylib, which exports
+    // `#[no_mangle]` statics as well as extern functions
+    any_cdylib: bool,
 }
 
 impl<'a, 'tcx, 'v> Visitor<'v> for ReachableContext<'a, 'tcx> {
@@ -156,13 +247,17 @@ impl<'a, 'tcx> ReachableContext<'a, 'tcx> {
     // Creates a new reachability computation context.
     fn new(tcx: &'a ty::ctxt<'tcx>) -> ReachableContext<'a, 'tcx> {
         let any_library = tcx.sess.crate_types.borrow().iter().any(|ty| {
-            *ty != config::CrateTypeExecutable
+            *ty != config::CrateTypeExecutable && *ty != config::CrateTypeCdylib
+        });
+        let any_cdylib = tcx.sess.crate_types.borrow().iter().any(|ty| {
+            *ty == config::CrateTypeCdylib
         });
         ReachableContext {
             tcx: tcx,
             reachable_symbols: NodeSet(),
             worklist: Vec::new(),
             any_library: any_library,
+            any_cdylib: any_cdylib,
         }
     }
 
@@ -177,7 +272,7 @@ impl<'a, 'tcx> ReachableContext<'a, 'tcx> {
         match self.tcx.map.find(node_id) {
             Some(ast_map::NodeItem(item)) => {
                 match item.node {
-                    ast::ItemFn(..) => item_might_be_inlined(&*item),
+                    ast::ItemFn(..) => item_might_be_inlined(self.tcx, &*item),
                     _ => false,
                 }
             }
@@ -189,9 +284,10 @@ impl<'a, 'tcx> ReachableContext<'a, 'tcx> {
             }
             Some(ast_map::NodeImplItem(impl_item)) => {
                 match impl_item.node {
-                    ast::MethodImplItem(ref sig, _) => {
+                    ast::MethodImplItem(ref sig, ref body) => {
                         if generics_require_inlining(&sig.generics) ||
-                                attr::requests_inline(&impl_item.attrs) {
+                                attr::requests_inline(&impl_item.attrs) ||
+                                is_auto_inlinable(self.tcx, &impl_item.attrs, sig.abi, body) {
                             true
                         } else {
                             let impl_did = self.tcx
@@ -252,12 +348,20 @@ impl<'a, 'tcx> ReachableContext<'a, 'tcx> {
             // anything as external except for `extern fn` types. These
             // functions may still participate in some form of native interface,
             // but all other rust-only interfaces can be private (they will not
-            // participate in linkage after this product is produced)
+            // participate in linkage after this product is produced). A
+            // cdylib also exports the statics that are given a symbol name.
             if let ast_map::NodeItem(item) = *node {
-                if let ast::ItemFn(_, _, abi, _, _) = item.node {
-                    if abi != abi::Rust {
-                        self.reachable_symbols.insert(search_item);
+                let reachable = match item.node {
+                    ast::ItemFn(_, _, abi, _, _) => abi != abi::Rust,
+                    ast::ItemStatic(..) => {
+                        self.any_cdylib &&
+                            (attr::contains_name(&item.attrs, "no_mangle") ||
+                             attr::contains_name(&item.attrs, "export_name"))
                     }
+                    _ => false,
+                };
+                if reachable {
+                    self.reachable_symbols.insert(search_item);
                 }
             }
         } else {
@@ -272,7 +376,7 @@ impl<'a, 'tcx> ReachableContext<'a, 'tcx> {
             ast_map::NodeItem(item) => {
                 match item.node {
                     ast::ItemFn(_, _, _, _, ref search_block) => {
-                        if item_might_be_inlined(&*item) {
+                        if item_might_be_inlined(self.tcx, &*item) {
                             visit::walk_block(self, &**search_block)
                         }
                     }
@@ -316,7 +420,7 @@ impl<'a, 'tcx> ReachableContext<'a, 'tcx> {
                 match impl_item.node {
                     ast::MethodImplItem(ref sig, ref body) => {
                         let did = self.tcx.map.get_parent_did(search_item);
-                        if method_might_be_inlined(self.tcx, sig, impl_item, did) {
+                        if method_might_be_inlined(self.tcx, sig, impl_item, body, did) {
                             visit::walk_block(self, body)
                         }
                     }
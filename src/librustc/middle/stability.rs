@@ -24,7 +24,7 @@ use syntax::ast;
 use syntax::ast::{Attribute, Block, Crate, DefId, FnDecl, NodeId, Variant};
 use syntax::ast::{Item, Generics, StructField};
 use syntax::ast_util::is_local;
-use syntax::attr::{Stability, AttrMetaMethods};
+use syntax::attr::{Stability, Deprecation, AttrMetaMethods};
 use syntax::visit::{FnKind, Visitor};
 use syntax::feature_gate::emit_feature_err;
 use util::nodemap::{NodeMap, DefIdMap, FnvHashSet, FnvHashMap};
@@ -39,7 +39,9 @@ pub struct Index {
     // stability for crate-local items; unmarked stability == no entry
     local: NodeMap<Stability>,
     // cache for extern-crate items; unmarked stability == entry with None
-    extern_cache: DefIdMap<Option<Stability>>
+    extern_cache: DefIdMap<Option<Stability>>,
+    // deprecation for crate-local items in crates without staged_api
+    local_depr: NodeMap<Deprecation>,
 }
 
 // A private tree-walker for producing an Index.
@@ -47,6 +49,7 @@ struct Annotator<'a> {
     sess: &'a Session,
     index: &'a mut Index,
     parent: Option<Stability>,
+    parent_depr: Option<Deprecation>,
     export_map: &'a PublicItems,
 }
 
@@ -93,14 +96,33 @@ impl<'a> Annotator<'a> {
             // Emit warnings for non-staged-api crates. These should be errors.
             for attr in attrs {
                 let tag = attr.name();
-                if tag == "unstable" || tag == "stable" || tag == "deprecated" {
+                if tag == "unstable" || tag == "stable" {
                     attr::mark_used(attr);
                     self.sess.span_warn(attr.span(),
                                         "stability attributes are deprecated \
                                          and will soon become errors");
                 }
             }
-            f(self);
+
+            // `#[deprecated]` is available to all crates, and is inherited
+            // just like stability is in staged_api crates.
+            match attr::find_deprecation(self.sess.diagnostic(), attrs, item_sp) {
+                Some(depr) => {
+                    debug!("annotate: found {:?}", depr);
+                    self.index.local_depr.insert(id, depr.clone());
+                    let parent_depr = replace(&mut self.parent_depr, Some(depr));
+                    f(self);
+                    self.parent_depr = parent_depr;
+                }
+                None => {
+                    if use_parent {
+                        if let Some(depr) = self.parent_depr.clone() {
+                            self.index.local_depr.insert(id, depr);
+                        }
+                    }
+                    f(self);
+                }
+            }
         }
     }
 }
@@ -175,6 +197,7 @@ impl Index {
             sess: sess,
             index: self,
             parent: None,
+            parent_depr: None,
             export_map: export_map,
         };
         annotator.annotate(ast::CRATE_NODE_ID, true, &krate.attrs, krate.span,
@@ -197,7 +220,8 @@ impl Index {
         Index {
             staged_api: staged_api,
             local: NodeMap(),
-            extern_cache: DefIdMap()
+            extern_cache: DefIdMap(),
+            local_depr: NodeMap(),
         }
     }
 }
@@ -237,6 +261,10 @@ impl<'a, 'tcx> Checker<'a, 'tcx> {
         let cross_crate = !is_local(id);
         if !cross_crate { return }
 
+        // Crates without staged_api have no stability levels to check; their
+        // deprecation is handled by a lint.
+        if !is_staged_api(self.tcx, id) { return }
+
         match *stab {
             Some(Stability { level: attr::Unstable, ref feature, ref reason, .. }) => {
                 self.used_features.insert(feature.clone(), attr::Unstable);
@@ -287,32 +315,32 @@ impl<'a, 'v, 'tcx> Visitor<'v> for Checker<'a, 'tcx> {
         if item.span == DUMMY_SP && item.ident.as_str() == "__test" { return }
 
         check_item(self.tcx, item, true,
-                   &mut |id, sp, stab| self.check(id, sp, stab));
+                   &mut |id, sp, stab, _| self.check(id, sp, stab));
         visit::walk_item(self, item);
     }
 
     fn visit_expr(&mut self, ex: &ast::Expr) {
         check_expr(self.tcx, ex,
-                   &mut |id, sp, stab| self.check(id, sp, stab));
+                   &mut |id, sp, stab, _| self.check(id, sp, stab));
         visit::walk_expr(self, ex);
     }
 
     fn visit_path(&mut self, path: &ast::Path, id: ast::NodeId) {
         check_path(self.tcx, path, id,
-                   &mut |id, sp, stab| self.check(id, sp, stab));
+                   &mut |id, sp, stab, _| self.check(id, sp, stab));
         visit::walk_path(self, path)
     }
 
     fn visit_pat(&mut self, pat: &ast::Pat) {
         check_pat(self.tcx, pat,
-                  &mut |id, sp, stab| self.check(id, sp, stab));
+                  &mut |id, sp, stab, _| self.check(id, sp, stab));
         visit::walk_pat(self, pat)
     }
 }
 
 /// Helper for discovering nodes to check for stability
 pub fn check_item(tcx: &ty::ctxt, item: &ast::Item, warn_about_defns: bool,
-                  cb: &mut FnMut(ast::DefId, Span, &Option<Stability>)) {
+                  cb: &mut FnMut(ast::DefId, Span, &Option<Stability>, &Option<Deprecation>)) {
     match item.node {
         ast::ItemExternCrate(_) => {
             // compiler-generated `extern crate` items have a dummy span.
@@ -349,7 +377,7 @@ pub fn check_item(tcx: &ty::ctxt, item: &ast::Item, warn_about_defns: bool,
 
 /// Helper for discovering nodes to check for stability
 pub fn check_expr(tcx: &ty::ctxt, e: &ast::Expr,
-                  cb: &mut FnMut(ast::DefId, Span, &Option<Stability>)) {
+                  cb: &mut FnMut(ast::DefId, Span, &Option<Stability>, &Option<Deprecation>)) {
     let span;
     let id = match e.node {
         ast::ExprMethodCall(i, _, _) => {
@@ -458,7 +486,7 @@ pub fn check_expr(tcx: &ty::ctxt, e: &ast::Expr,
 }
 
 pub fn check_path(tcx: &ty::ctxt, path: &ast::Path, id: ast::NodeId,
-                  cb: &mut FnMut(ast::DefId, Span, &Option<Stability>)) {
+                  cb: &mut FnMut(ast::DefId, Span, &Option<Stability>, &Option<Deprecation>)) {
     match tcx.def_map.borrow().get(&id).map(|d| d.full_def()) {
         Some(def::DefPrimTy(..)) => {}
         Some(def) => {
@@ -470,7 +498,7 @@ pub fn check_path(tcx: &ty::ctxt, path: &ast::Path, id: ast::NodeId,
 }
 
 pub fn check_pat(tcx: &ty::ctxt, pat: &ast::Pat,
-                 cb: &mut FnMut(ast::DefId, Span, &Option<Stability>)) {
+                 cb: &mut FnMut(ast::DefId, Span, &Option<Stability>, &Option<Deprecation>)) {
     debug!("check_pat(pat = {:?})", pat);
     if is_internal(tcx, pat.span) { return; }
 
@@ -511,11 +539,18 @@ pub fn check_pat(tcx: &ty::ctxt, pat: &ast::Pat,
 }
 
 fn maybe_do_stability_check(tcx: &ty::ctxt, id: ast::DefId, span: Span,
-                            cb: &mut FnMut(ast::DefId, Span, &Option<Stability>)) {
-    if !is_staged_api(tcx, id) { return  }
+                            cb: &mut FnMut(ast::DefId, Span, &Option<Stability>,
+                                           &Option<Deprecation>)) {
     if is_internal(tcx, span) { return }
-    let ref stability = lookup(tcx, id);
-    cb(id, span, stability);
+    if is_staged_api(tcx, id) {
+        let ref stability = lookup(tcx, id);
+        cb(id, span, stability, &None);
+    } else {
+        let ref deprecation = lookup_deprecation(tcx, id);
+        if deprecation.is_some() {
+            cb(id, span, &None, deprecation);
+        }
+    }
 }
 
 fn is_internal(tcx: &ty::ctxt, span: Span) -> bool {
@@ -575,6 +610,27 @@ pub fn lookup(tcx: &ty::ctxt, id: DefId) -> Option<Stability> {
     })
 }
 
+/// Lookup the `#[deprecated]` attribute for a node in a crate without
+/// staged_api, loading external crate metadata as necessary.
+pub fn lookup_deprecation(tcx: &ty::ctxt, id: DefId) -> Option<Deprecation> {
+    debug!("lookup_deprecation(id={})",
+           id.repr(tcx));
+
+    // is this definition the implementation of a trait method?
+    match ty::trait_item_of_item(tcx, id) {
+        Some(ty::MethodTraitItemId(trait_method_id)) if trait_method_id != id => {
+            return lookup_deprecation(tcx, trait_method_id)
+        }
+        _ => {}
+    }
+
+    if is_local(id) {
+        tcx.stability.borrow().local_depr.get(&id.node).cloned()
+    } else {
+        csearch::get_deprecation(&tcx.sess.cstore, id)
+    }
+}
+
 /// Given the list of enabled features that were not language features (i.e. that
 /// were expected to be library features), and the list of features used from
 /// libraries, identify activated features that don't exist and error about them.
@@ -12,8 +12,10 @@ use super::{
     FulfillmentError,
     FulfillmentErrorCode,
     MismatchedProjectionTypes,
+    MethodViolationCode,
     Obligation,
     ObligationCauseCode,
+    ObjectSafetyViolation,
     OutputTypeParameterMismatch,
     PredicateObligation,
     SelectionError,
@@ -24,6 +26,7 @@ use middle::infer::InferCtxt;
 use middle::ty::{self, AsPredicate, ReferencesError, ToPolyTraitRef, TraitRef};
 use middle::ty_fold::TypeFoldable;
 use std::collections::HashMap;
+use syntax::ast;
 use syntax::codemap::{DUMMY_SP, Span};
 use syntax::attr::{AttributeMethods, AttrMetaMethods};
 use util::ppaux::{Repr, UserString};
@@ -440,3 +443,53 @@ pub fn suggest_new_overflow_limit(tcx: &ty::ctxt, span: Span) {
             "consider adding a `#![recursion_limit=\"{}\"]` attribute to your crate",
             suggested_limit));
 }
+
+/// Explains why the trait `trait_def_id` is not object-safe, with one note
+/// per violation. Notes point at the offending method or trait when it is
+/// defined in the local crate, and at `span` otherwise.
+pub fn note_object_safety_violations(tcx: &ty::ctxt, span: Span, trait_def_id: ast::DefId) {
+    let def_span = |def_id: ast::DefId| {
+        if def_id.krate == ast::LOCAL_CRATE {
+            tcx.map.opt_span(def_id.node).unwrap_or(span)
+        } else {
+            span
+        }
+    };
+
+    for violation in super::object_safety_violations(tcx, trait_def_id) {
+        match violation {
+            ObjectSafetyViolation::SizedSelf => {
+                tcx.sess.span_note(
+                    def_span(trait_def_id),
+                    "the trait cannot require that `Self : Sized`");
+            }
+
+            ObjectSafetyViolation::SupertraitSelf => {
+                tcx.sess.span_note(
+                    def_span(trait_def_id),
+                    "the trait cannot use `Self` as a type parameter \
+                     in the supertrait listing");
+            }
+
+            ObjectSafetyViolation::Method(method, code) => {
+                let reason = match code {
+                    MethodViolationCode::StaticMethod => "has no receiver",
+                    MethodViolationCode::ReferencesSelf => {
+                        "references the `Self` type in its arguments or return type"
+                    }
+                    MethodViolationCode::Generic => "has generic type parameters",
+                };
+                let method_trait = method.container.id();
+                let msg = if method_trait == trait_def_id {
+                    format!("method `{}` {}", method.name.user_string(tcx), reason)
+                } else {
+                    format!("method `{}` of supertrait `{}` {}",
+                            method.name.user_string(tcx),
+                            ty::item_path_str(tcx, method_trait),
+                            reason)
+                };
+                tcx.sess.span_note(def_span(method.def_id), &msg);
+            }
+        }
+    }
+}
@@ -25,6 +25,7 @@ use syntax::ast;
 use syntax::codemap::{Span, DUMMY_SP};
 use util::ppaux::Repr;
 
+pub use self::error_reporting::note_object_safety_violations;
 pub use self::error_reporting::report_fulfillment_errors;
 pub use self::error_reporting::report_overflow_error;
 pub use self::error_reporting::suggest_new_overflow_limit;
@@ -0,0 +1,116 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Queries about the public surface of generic types that are of interest
+//! to library authors: the inferred variance of each type and lifetime
+//! parameter, and whether the type is `Send` and `Sync`. These back
+//! `-Z print-variance` and the corresponding section in rustdoc.
+
+use middle::infer;
+use middle::privacy::ExportedItems;
+use middle::subst::{self, Subst};
+use middle::traits;
+use middle::ty::{self, AsPredicate};
+
+use syntax::{ast, ast_map};
+use syntax::ast_util::local_def;
+use syntax::parse::token;
+
+/// Whether a type implements the `Send` and `Sync` auto traits.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct AutoTraits {
+    pub send: bool,
+    pub sync: bool,
+}
+
+/// Describes a variance the way it is worded in the reference.
+pub fn variance_name(variance: ty::Variance) -> &'static str {
+    match variance {
+        ty::Covariant => "covariant",
+        ty::Contravariant => "contravariant",
+        ty::Invariant => "invariant",
+        ty::Bivariant => "bivariant",
+    }
+}
+
+/// Returns the names of the lifetime and type parameters of the type
+/// `def_id`, in declaration order, along with their inferred variance.
+pub fn param_variances(tcx: &ty::ctxt, def_id: ast::DefId) -> Vec<(String, ty::Variance)> {
+    let generics = ty::lookup_item_type(tcx, def_id).generics;
+    let variances = ty::item_variances(tcx, def_id);
+
+    let regions = generics.regions.get_slice(subst::TypeSpace).iter().map(|r| {
+        (token::get_name(r.name).to_string(),
+         *variances.regions.get(r.space, r.index as usize))
+    });
+    let types = generics.types.get_slice(subst::TypeSpace).iter().map(|t| {
+        (token::get_name(t.name).to_string(),
+         *variances.types.get(t.space, t.index as usize))
+    });
+    regions.chain(types).collect()
+}
+
+/// Determines whether the local type `id` is `Send` and `Sync`, assuming
+/// that each of its type parameters is both `Send` and `Sync`. A type that
+/// is not `Send` under that assumption is never `Send`, whatever it is
+/// instantiated with.
+pub fn auto_traits(tcx: &ty::ctxt, id: ast::NodeId) -> AutoTraits {
+    let param_env = ty::ParameterEnvironment::for_item(tcx, id);
+
+    let mut caller_bounds = param_env.caller_bounds.clone();
+    for &param_ty in param_env.free_substs.types.iter() {
+        for &bound in &[ty::BoundSend, ty::BoundSync] {
+            if let Ok(trait_ref) = traits::trait_ref_for_builtin_bound(tcx, bound, param_ty) {
+                caller_bounds.push(trait_ref.as_predicate());
+            }
+        }
+    }
+    let param_env = param_env.with_caller_bounds(caller_bounds);
+
+    let span = tcx.map.span(id);
+    let ty = ty::lookup_item_type(tcx, local_def(id)).ty.subst(tcx, &param_env.free_substs);
+    let implements = |bound| {
+        let infcx = infer::new_infer_ctxt(tcx);
+        traits::type_known_to_meet_builtin_bound(&infcx, &param_env, ty, bound, span)
+    };
+
+    AutoTraits {
+        send: implements(ty::BoundSend),
+        sync: implements(ty::BoundSync),
+    }
+}
+
+/// Prints the parameter variances and auto traits of every exported
+/// generic struct and enum, for `-Z print-variance`.
+pub fn print_variances(tcx: &ty::ctxt, exported_items: &ExportedItems) {
+    let mut types = exported_items.iter().filter_map(|&id| {
+        match tcx.map.find(id) {
+            Some(ast_map::NodeItem(item)) => match item.node {
+                ast::ItemStruct(_, ref generics) |
+                ast::ItemEnum(_, ref generics) if generics.is_parameterized() => {
+                    Some((ty::item_path_str(tcx, local_def(id)), id))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }).collect::<Vec<_>>();
+    types.sort();
+
+    for (path, id) in types {
+        println!("{}", path);
+        for (name, variance) in param_variances(tcx, local_def(id)) {
+            println!("    {}: {}", name, variance_name(variance));
+        }
+        let auto_traits = auto_traits(tcx, id);
+        println!("    Send: {}", if auto_traits.send { "yes" } else { "no" });
+        println!("    Sync: {}", if auto_traits.sync { "yes" } else { "no" });
+    }
+}
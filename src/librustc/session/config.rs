@@ -139,6 +139,10 @@ pub enum PrintRequest {
     FileNames,
     Sysroot,
     CrateName,
+    Cfg,
+    TargetList,
+    TargetSpec,
+    NativeStaticLibs,
 }
 
 pub enum Input {
@@ -260,6 +264,7 @@ pub enum CrateType {
     CrateTypeDylib,
     CrateTypeRlib,
     CrateTypeStaticlib,
+    CrateTypeCdylib,
 }
 
 #[derive(Clone)]
@@ -277,6 +282,67 @@ impl Passes {
     }
 }
 
+/// How a panic is propagated: by unwinding the stack and running
+/// destructors, or by aborting the process once the message is printed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PanicStrategy {
+    Unwind,
+    Abort,
+}
+
+impl PanicStrategy {
+    pub fn desc(&self) -> &'static str {
+        match *self {
+            PanicStrategy::Unwind => "unwind",
+            PanicStrategy::Abort => "abort",
+        }
+    }
+}
+
+/// How much of the crate's upstream dependencies LLVM may optimize together
+/// with it at link time.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Lto {
+    No,
+    /// Merge the crate and all of its upstream rlibs into a single module.
+    Fat,
+    /// Import the small upstream functions that each codegen unit calls into
+    /// that unit, so that they can be inlined while the units are still
+    /// optimized in parallel.
+    Summary,
+}
+
+/// An LLVM sanitizer to instrument the crate with, along with the
+/// compiler-rt runtime that reports what it finds.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sanitizer {
+    Address,
+    Thread,
+    Memory,
+    Leak,
+}
+
+impl Sanitizer {
+    pub fn desc(&self) -> &'static str {
+        match *self {
+            Sanitizer::Address => "address",
+            Sanitizer::Thread => "thread",
+            Sanitizer::Memory => "memory",
+            Sanitizer::Leak => "leak",
+        }
+    }
+
+    /// The name of the compiler-rt runtime library for this sanitizer.
+    pub fn runtime(&self) -> &'static str {
+        match *self {
+            Sanitizer::Address => "asan",
+            Sanitizer::Thread => "tsan",
+            Sanitizer::Memory => "msan",
+            Sanitizer::Leak => "lsan",
+        }
+    }
+}
+
 /// Declare a macro that will define all CodegenOptions/DebuggingOptions fields and parsers all
 /// at once. The goal of this macro is to define an interface that can be
 /// programmatically used by the option parser in order to initialize the struct
@@ -362,11 +428,18 @@ macro_rules! options {
             Some("a space-separated list of passes, or `all`");
         pub const parse_opt_uint: Option<&'static str> =
             Some("a number");
+        pub const parse_panic_strategy: Option<&'static str> =
+            Some("either `unwind` or `abort`");
+        pub const parse_sanitizer: Option<&'static str> =
+            Some("one of: `address`, `thread`, `memory` or `leak`");
+        pub const parse_lto: Option<&'static str> =
+            Some("either `fat` or `summary`");
     }
 
     #[allow(dead_code)]
     mod $mod_set {
-        use super::{$struct_name, Passes, SomePasses, AllPasses};
+        use super::{$struct_name, Passes, SomePasses, AllPasses, PanicStrategy, Sanitizer,
+                    Lto};
 
         $(
             pub fn $opt(cg: &mut $struct_name, v: Option<&str>) -> bool {
@@ -453,6 +526,35 @@ macro_rules! options {
             }
         }
 
+        fn parse_panic_strategy(slot: &mut PanicStrategy, v: Option<&str>) -> bool {
+            match v {
+                Some("unwind") => *slot = PanicStrategy::Unwind,
+                Some("abort") => *slot = PanicStrategy::Abort,
+                _ => return false
+            }
+            true
+        }
+
+        fn parse_sanitizer(slot: &mut Option<Sanitizer>, v: Option<&str>) -> bool {
+            match v {
+                Some("address") => *slot = Some(Sanitizer::Address),
+                Some("thread") => *slot = Some(Sanitizer::Thread),
+                Some("memory") => *slot = Some(Sanitizer::Memory),
+                Some("leak") => *slot = Some(Sanitizer::Leak),
+                _ => return false
+            }
+            true
+        }
+
+        fn parse_lto(slot: &mut Lto, v: Option<&str>) -> bool {
+            match v {
+                None | Some("fat") => *slot = Lto::Fat,
+                Some("summary") => *slot = Lto::Summary,
+                _ => return false
+            }
+            true
+        }
+
         fn parse_passes(slot: &mut Passes, v: Option<&str>) -> bool {
             match v {
                 Some("all") => {
@@ -480,10 +582,15 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
         "tool to assemble archives with"),
     linker: Option<String> = (None, parse_opt_string,
         "system linker to link outputs with"),
+    linker_flavor: Option<String> = (None, parse_opt_string,
+        "how the linker takes its arguments (gcc, ld or lld)"),
+    objcopy: Option<String> = (None, parse_opt_string,
+        "tool to split debug info out of linked outputs with"),
     link_args: Option<Vec<String>> = (None, parse_opt_list,
         "extra arguments to pass to the linker (space separated)"),
-    lto: bool = (false, parse_bool,
-        "perform LLVM link-time optimizations"),
+    lto: Lto = (Lto::No, parse_lto,
+        "perform LLVM link-time optimizations, either of the whole program (`fat`, \
+         the default) or only of small upstream functions (`summary`)"),
     target_cpu: Option<String> = (None, parse_opt_string,
         "select target processor (llc -mcpu=help for details)"),
     target_feature: String = ("".to_string(), parse_string,
@@ -520,6 +627,10 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
          "extra data to put in each output filename"),
     codegen_units: usize = (1, parse_uint,
         "divide crate into N units to optimize in parallel"),
+    cross_crate_inline_threshold: usize = (10, parse_uint,
+        "export functions that call no other functions and have at most N \
+         expressions for inlining into other crates, as if marked #[inline]; \
+         0 disables this"),
     remark: Passes = (SomePasses(Vec::new()), parse_passes,
         "print remarks for these optimization passes (space separated, or \"all\")"),
     no_stack_check: bool = (false, parse_bool,
@@ -527,10 +638,25 @@ options! {CodegenOptions, CodegenSetter, basic_codegen_options,
     debuginfo: Option<usize> = (None, parse_opt_uint,
         "debug info emission level, 0 = no debug info, 1 = line tables only, \
          2 = full debug info with variable and type information"),
+    split_debuginfo: bool = (false, parse_bool,
+        "move the debug info of linked executables and dynamic libraries into \
+         a separate `.debug` file, leaving a stripped output that links to it; \
+         install the `.debug` file next to the output"),
     opt_level: Option<usize> = (None, parse_opt_uint,
         "Optimize with possible levels 0-3"),
     debug_assertions: Option<bool> = (None, parse_opt_bool,
         "explicitly enable the cfg(debug_assertions) directive"),
+    panic: PanicStrategy = (PanicStrategy::Unwind, parse_panic_strategy,
+        "panic strategy to compile crate with"),
+    coverage: bool = (false, parse_bool,
+        "instrument the crate to count how often each line runs, writing the \
+         counts in gcov format"),
+    profile_generate: Option<String> = (None, parse_opt_string,
+        "instrument the crate to record a profile into the given directory \
+         when it runs"),
+    profile_use: Option<String> = (None, parse_opt_string,
+        "optimize the crate with a profile recorded with profile-generate and \
+         merged by llvm-profdata"),
 }
 
 
@@ -559,8 +685,11 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "enable debug output from LLVM"),
     count_type_sizes: bool = (false, parse_bool,
         "count the sizes of aggregate types"),
+    no_verify_metadata: bool = (false, parse_bool,
+        "skip verifying the checksum of the metadata of each library loaded"),
     meta_stats: bool = (false, parse_bool,
-        "gather metadata statistics"),
+        "gather metadata statistics, including how many bytes of each library's item \
+         documents were looked up through its item index"),
     print_link_args: bool = (false, parse_bool,
         "Print the arguments passed to the linker"),
     gc: bool = (false, parse_bool,
@@ -572,7 +701,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     ast_json_noexpand: bool = (false, parse_bool,
         "Print the pre-expansion AST as JSON and halt"),
     ls: bool = (false, parse_bool,
-        "List the symbols defined by a library crate"),
+        "List the items, dependencies and other metadata of a library crate"),
+    ls_json: bool = (false, parse_bool,
+        "With -Z ls, print the library's metadata as JSON"),
     save_analysis: bool = (false, parse_bool,
         "Write syntax and type analysis information in addition to normal output"),
     print_move_fragments: bool = (false, parse_bool,
@@ -602,12 +733,25 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "Adds unstable command line options to rustc interface"),
     print_enum_sizes: bool = (false, parse_bool,
           "Print the size of enums and their variants"),
+    print_type_sizes: bool = (false, parse_bool,
+          "Print the layout of each monomorphized struct, enum and closure type"),
+    print_type_sizes_json: bool = (false, parse_bool,
+          "With -Z print-type-sizes, print each layout as a line of JSON"),
+    print_variance: bool = (false, parse_bool,
+          "Print the variance of each parameter of exported generic types, and whether \
+           they are Send and Sync when their type parameters are"),
     force_overflow_checks: Option<bool> = (None, parse_opt_bool,
           "Force overflow checks on or off"),
     force_dropflag_checks: Option<bool> = (None, parse_opt_bool,
           "Force drop flag checks on or off"),
     trace_macros: bool = (false, parse_bool,
           "For every macro invocation, print its name and arguments"),
+    sanitizer: Option<Sanitizer> = (None, parse_sanitizer,
+          "Instrument the crate with an LLVM sanitizer and link its runtime \
+           (x86_64 Linux only)"),
+    object_safety_baseline: Option<String> = (None, parse_opt_string,
+          "Path to a previous build of this crate to compare the object safety \
+           of public traits against"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -621,6 +765,7 @@ pub fn default_configuration(sess: &Session) -> ast::CrateConfig {
     let arch = &sess.target.target.arch;
     let wordsz = &sess.target.target.target_pointer_width;
     let os = &sess.target.target.target_os;
+    let env = &sess.target.target.target_env;
 
     let fam = match sess.target.target.options.is_like_windows {
         true  => InternedString::new("windows"),
@@ -631,6 +776,7 @@ pub fn default_configuration(sess: &Session) -> ast::CrateConfig {
     let mut ret = vec![ // Target bindings.
          attr::mk_word_item(fam.clone()),
          mk(InternedString::new("target_os"), intern(os)),
+         mk(InternedString::new("target_env"), intern(env)),
          mk(InternedString::new("target_family"), fam),
          mk(InternedString::new("target_arch"), intern(arch)),
          mk(InternedString::new("target_endian"), intern(end)),
@@ -776,7 +922,7 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
                              assumed.", "[KIND=]NAME"),
         opt::multi("", "crate-type", "Comma separated list of types of crates
                                     for the compiler to emit",
-                   "[bin|lib|rlib|dylib|staticlib]"),
+                   "[bin|lib|rlib|dylib|cdylib|staticlib]"),
         opt::opt("", "crate-name", "Specify the name of the crate being built",
                "NAME"),
         opt::multi("", "emit", "Comma separated list of types of output for \
@@ -784,7 +930,8 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
                  "[asm|llvm-bc|llvm-ir|obj|link|dep-info]"),
         opt::multi("", "print", "Comma separated list of compiler information to \
                                print on stdout",
-                 "[crate-name|file-names|sysroot]"),
+                 "[crate-name|file-names|sysroot|cfg|target-list|target-spec-json|\
+                   native-static-libs]"),
         opt::flag("g",  "",  "Equivalent to -C debuginfo=2"),
         opt::flag("O", "", "Equivalent to -C opt-level=2"),
         opt::opt("o", "", "Write output to <filename>", "FILENAME"),
@@ -953,6 +1100,13 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             }
         }
     };
+    // Coverage counters are mapped back to source lines through the line
+    // tables of the debuginfo.
+    let debuginfo = if cg.coverage && debuginfo == NoDebugInfo {
+        LimitedDebugInfo
+    } else {
+        debuginfo
+    };
 
     let mut search_paths = SearchPaths::new();
     for s in &matches.opt_strs("L") {
@@ -985,6 +1139,10 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             "crate-name" => PrintRequest::CrateName,
             "file-names" => PrintRequest::FileNames,
             "sysroot" => PrintRequest::Sysroot,
+            "cfg" => PrintRequest::Cfg,
+            "target-list" => PrintRequest::TargetList,
+            "target-spec-json" => PrintRequest::TargetSpec,
+            "native-static-libs" => PrintRequest::NativeStaticLibs,
             req => {
                 early_error(&format!("unknown print request `{}`", req))
             }
@@ -1084,6 +1242,7 @@ pub fn parse_crate_types_from_list(list_list: Vec<String>) -> Result<Vec<CrateTy
                 "rlib"      => CrateTypeRlib,
                 "staticlib" => CrateTypeStaticlib,
                 "dylib"     => CrateTypeDylib,
+                "cdylib"    => CrateTypeCdylib,
                 "bin"       => CrateTypeExecutable,
                 _ => {
                     return Err(format!("unknown crate type: `{}`",
@@ -1105,7 +1264,8 @@ impl fmt::Display for CrateType {
             CrateTypeExecutable => "bin".fmt(f),
             CrateTypeDylib => "dylib".fmt(f),
             CrateTypeRlib => "rlib".fmt(f),
-            CrateTypeStaticlib => "staticlib".fmt(f)
+            CrateTypeStaticlib => "staticlib".fmt(f),
+            CrateTypeCdylib => "cdylib".fmt(f),
         }
     }
 }
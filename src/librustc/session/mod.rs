@@ -234,10 +234,11 @@ impl Session {
         self.opts.debugging_opts.print_llvm_passes
     }
     pub fn lto(&self) -> bool {
-        self.opts.cg.lto
+        self.opts.cg.lto == config::Lto::Fat
     }
     pub fn no_landing_pads(&self) -> bool {
-        self.opts.debugging_opts.no_landing_pads
+        self.opts.debugging_opts.no_landing_pads ||
+            self.opts.cg.panic == config::PanicStrategy::Abort
     }
     pub fn unstable_options(&self) -> bool {
         self.opts.debugging_opts.unstable_options
@@ -407,6 +408,42 @@ pub fn build_session_(sopts: config::Options,
         can_print_warnings: can_print_warnings
     };
 
+    if let Some(sanitizer) = sess.opts.debugging_opts.sanitizer {
+        // compiler-rt only provides the sanitizer runtimes we build for
+        // x86_64 Linux.
+        if sess.target.target.arch != "x86_64" || sess.target.target.target_os != "linux" {
+            sess.err(&format!("the {} sanitizer is only supported on x86_64 Linux, \
+                               not on `{}`",
+                              sanitizer.desc(), sess.target.target.llvm_target));
+        }
+    }
+
+    // Both need compiler-rt's profiling runtime.
+    let profiling = if sess.opts.cg.coverage {
+        Some("coverage instrumentation")
+    } else if sess.opts.cg.profile_generate.is_some() {
+        Some("profile instrumentation")
+    } else {
+        None
+    };
+    if let Some(what) = profiling {
+        let arch = &sess.target.target.arch[..];
+        if (arch != "x86_64" && arch != "x86") || sess.target.target.target_os != "linux" {
+            sess.err(&format!("{} is only supported on x86 and x86_64 Linux, not on `{}`",
+                              what, sess.target.target.llvm_target));
+        }
+    }
+
+    if sess.opts.cg.profile_generate.is_some() && sess.opts.cg.profile_use.is_some() {
+        sess.err("-C profile-generate and -C profile-use cannot be used together");
+    }
+
+    // `__rust_probestack` is only written for x86_64.
+    if sess.target.target.options.stack_probes && sess.target.target.arch != "x86_64" {
+        sess.err(&format!("stack probes are only supported on x86_64, not on `{}`",
+                          sess.target.target.llvm_target));
+    }
+
     sess
 }
 
@@ -0,0 +1,626 @@
+// Copyright 2014-2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! [Flexible target specification.](https://github.com/rust-lang/rfcs/pull/131)
+//!
+//! Rust targets a wide variety of usecases, and in the interest of flexibility,
+//! allows new target triples to be defined in configuration files. Most users
+//! will not need to care about these, but this is invaluable when porting Rust
+//! to a new platform, and allows for an unprecedented level of control over how
+//! the compiler works.
+//!
+//! # Using custom targets
+//!
+//! A target triple, as passed via `rustc --target=TRIPLE`, will first be
+//! compared against the list of built-in targets. This is to ease distributing
+//! rustc (no need for configuration files) and also to hold these built-in
+//! targets as immutable and sacred. If `TRIPLE` is not one of the built-in
+//! targets, rustc will check if a file named `TRIPLE` exists. If it does, it
+//! will be loaded as the target configuration. If the file does not exist,
+//! rustc will search each directory in the environment variable
+//! `RUST_TARGET_PATH` for a file named `TRIPLE.json`. The first one found will
+//! be loaded. If no file is found in any of those directories, a fatal error
+//! will be given.  `RUST_TARGET_PATH` includes `/etc/rustc` as its last entry,
+//! to be searched by default.
+//!
+//! Projects defining their own targets should use
+//! `--target=path/to/my-awesome-platform.json` instead of adding to
+//! `RUST_TARGET_PATH`.
+//!
+//! # Defining a new target
+//!
+//! Targets are defined using [JSON](http://json.org/). The `Target` struct in
+//! this module defines the format the JSON file should take, though each
+//! underscore in the field names should be replaced with a hyphen (`-`) in the
+//! JSON file. Some fields are required in every target specification, such as
+//! `data-layout`, `llvm-target`, `target-endian`, `target-pointer-width`, and
+//! `arch`. In general, options passed to rustc with `-C` override the target's
+//! settings, though `target-feature` and `link-args` will *add* to the list
+//! specified by the target, rather than replace.
+//!
+//! A target that differs from a built-in one in a few options can name it
+//! with an `inherits` key, such as `"inherits": "x86_64-unknown-linux-gnu"`,
+//! and give only the keys it changes. `rustc --print target-spec-json`
+//! prints the full specification of a target.
+
+use serialize::json::{Json, ToJson};
+use syntax::{diagnostic, abi};
+use std::collections::BTreeMap;
+use std::default::Default;
+use std::io::prelude::*;
+
+mod windows_base;
+mod linux_base;
+mod apple_base;
+mod apple_ios_base;
+mod freebsd_base;
+mod dragonfly_base;
+mod bitrig_base;
+mod openbsd_base;
+
+// this would use a match in `load_specific` if stringify! were allowed in
+// pattern position, so the triples are spelled out next to the modules.
+macro_rules! supported_targets {
+    ( $(($triple:expr, $module:ident)),+ ) => (
+        $(mod $module;)*
+
+        /// The triples of all the built-in targets.
+        pub static TARGETS: &'static [&'static str] = &[$($triple),*];
+
+        fn load_specific(target: &str) -> Option<Target> {
+            match target {
+                $(
+                    $triple => {
+                        let t = $module::target();
+                        debug!("Got builtin target: {:?}", t);
+                        Some(t)
+                    }
+                )*
+                _ => None
+            }
+        }
+    )
+}
+
+supported_targets! {
+    ("x86_64-unknown-linux-gnu", x86_64_unknown_linux_gnu),
+    ("x86_64-unknown-linux-musl", x86_64_unknown_linux_musl),
+    ("i686-unknown-linux-gnu", i686_unknown_linux_gnu),
+    ("mips-unknown-linux-gnu", mips_unknown_linux_gnu),
+    ("mipsel-unknown-linux-gnu", mipsel_unknown_linux_gnu),
+    ("powerpc-unknown-linux-gnu", powerpc_unknown_linux_gnu),
+    ("arm-unknown-linux-gnueabi", arm_unknown_linux_gnueabi),
+    ("arm-unknown-linux-gnueabihf", arm_unknown_linux_gnueabihf),
+    ("aarch64-unknown-linux-gnu", aarch64_unknown_linux_gnu),
+
+    ("arm-linux-androideabi", arm_linux_androideabi),
+    ("aarch64-linux-android", aarch64_linux_android),
+
+    ("x86_64-unknown-freebsd", x86_64_unknown_freebsd),
+
+    ("i686-unknown-dragonfly", i686_unknown_dragonfly),
+    ("x86_64-unknown-dragonfly", x86_64_unknown_dragonfly),
+
+    ("x86_64-unknown-bitrig", x86_64_unknown_bitrig),
+    ("x86_64-unknown-openbsd", x86_64_unknown_openbsd),
+
+    ("x86_64-apple-darwin", x86_64_apple_darwin),
+    ("i686-apple-darwin", i686_apple_darwin),
+
+    ("i386-apple-ios", i386_apple_ios),
+    ("x86_64-apple-ios", x86_64_apple_ios),
+    ("aarch64-apple-ios", aarch64_apple_ios),
+    ("armv7-apple-ios", armv7_apple_ios),
+    ("armv7s-apple-ios", armv7s_apple_ios),
+
+    ("x86_64-pc-windows-gnu", x86_64_pc_windows_gnu),
+    ("i686-pc-windows-gnu", i686_pc_windows_gnu)
+}
+
+/// Everything `rustc` knows about how to compile for a specific target.
+///
+/// Every field here must be specified, and has no default value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Target {
+    /// [Data layout](http://llvm.org/docs/LangRef.html#data-layout) to pass to LLVM.
+    pub data_layout: String,
+    /// Target triple to pass to LLVM.
+    pub llvm_target: String,
+    /// String to use as the `target_endian` `cfg` variable.
+    pub target_endian: String,
+    /// String to use as the `target_pointer_width` `cfg` variable.
+    pub target_pointer_width: String,
+    /// OS name to use for conditional compilation.
+    pub target_os: String,
+    /// Environment name to use for conditional compilation, such as the C library the target
+    /// uses ("gnu" or "musl"). Empty if the OS has only one.
+    pub target_env: String,
+    /// Architecture to use for ABI considerations. Valid options: "x86", "x86_64", "arm",
+    /// "aarch64", "mips", and "powerpc". "mips" includes "mipsel".
+    pub arch: String,
+    /// Optional settings with defaults.
+    pub options: TargetOptions,
+}
+
+/// Optional aspects of a target specification.
+///
+/// This has an implementation of `Default`, see each field for what the default is. In general,
+/// these try to take "minimal defaults" that don't assume anything about the runtime they run in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TargetOptions {
+    /// Linker to invoke. Defaults to "cc".
+    pub linker: String,
+    /// How the linker expects its arguments: "gcc" for a C compiler driver, which is handed the
+    /// linker's own options as `-Wl,` arguments, "ld" for a GNU-style linker such as `ld.bfd`,
+    /// `ld.gold` or `ld.lld` run directly, or "lld" for LLVM's `lld` driver. Defaults to "gcc".
+    /// Only "gcc" links executables unless `pre_link_objects` provides their startup objects.
+    pub linker_flavor: String,
+    /// Linker arguments that are unconditionally passed *before* any user-defined libraries.
+    pub pre_link_args: Vec<String>,
+    /// Linker arguments that are unconditionally passed *after* any user-defined libraries.
+    pub post_link_args: Vec<String>,
+    /// Objects to link before all others into executables, such as the C runtime's `crt1.o`.
+    /// They are looked up in the target's library directory in the sysroot.
+    pub pre_link_objects: Vec<String>,
+    /// Replaces `pre_link_objects` when linking a static position independent executable.
+    pub pre_link_objects_pie: Vec<String>,
+    /// Objects to link after all others into executables, looked up like `pre_link_objects`.
+    pub post_link_objects: Vec<String>,
+    /// Tool that moves the debug info of linked outputs into separate files for `-C
+    /// split-debuginfo`, taking the arguments of GNU objcopy. Defaults to "objcopy".
+    pub objcopy: String,
+    /// Default CPU to pass to LLVM. Corresponds to `llc -mcpu=$cpu`. Defaults to "default".
+    pub cpu: String,
+    /// Default target features to pass to LLVM. These features will *always* be passed, and cannot
+    /// be disabled even via `-C`. Corresponds to `llc -mattr=$features`.
+    pub features: String,
+    /// Whether dynamic linking is available on this target. Defaults to false.
+    pub dynamic_linking: bool,
+    /// Whether executables are available on this target. iOS, for example, only allows static
+    /// libraries. Defaults to false.
+    pub executables: bool,
+    /// Whether LLVM's segmented stack prelude is supported by whatever runtime is available.
+    /// Will emit stack checks and calls to __morestack. Defaults to false.
+    pub morestack: bool,
+    /// Whether functions whose stack frames may be larger than a page probe them from the top
+    /// down, by jumping to the `__rust_probestack` routine in the morestack library, so that
+    /// they hit the guard page at the end of the stack rather than skip over it. The frame size
+    /// is an estimate made after optimization to err on the large side, not a bound, see
+    /// `back::write::probe_large_frames`. Defaults to false.
+    pub stack_probes: bool,
+    /// Relocation model to use in object file. Corresponds to `llc
+    /// -relocation-model=$relocation_model`. Defaults to "pic".
+    pub relocation_model: String,
+    /// Code model to use. Corresponds to `llc -code-model=$code_model`. Defaults to "default".
+    pub code_model: String,
+    /// Do not emit code that uses the "red zone", if the ABI has one. Defaults to false.
+    pub disable_redzone: bool,
+    /// Eliminate frame pointers from stack frames if possible. Defaults to true.
+    pub eliminate_frame_pointer: bool,
+    /// Emit each function in its own section. Defaults to true.
+    pub function_sections: bool,
+    /// String to prepend to the name of every dynamic library. Defaults to "lib".
+    pub dll_prefix: String,
+    /// String to append to the name of every dynamic library. Defaults to ".so".
+    pub dll_suffix: String,
+    /// String to append to the name of every executable.
+    pub exe_suffix: String,
+    /// String to prepend to the name of every static library. Defaults to "lib".
+    pub staticlib_prefix: String,
+    /// String to append to the name of every static library. Defaults to ".a".
+    pub staticlib_suffix: String,
+    /// Whether the target toolchain is like OSX's. Only useful for compiling against iOS/OS X, in
+    /// particular running dsymutil and some other stuff like `-dead_strip`. Defaults to false.
+    pub is_like_osx: bool,
+    /// Whether the target toolchain is like Windows'. Only useful for compiling against Windows,
+    /// only really used for figuring out how to find libraries, since Windows uses its own
+    /// library naming convention. Defaults to false.
+    pub is_like_windows: bool,
+    /// Whether the target toolchain is like Android's. Only useful for compiling against Android.
+    /// Defaults to false.
+    pub is_like_android: bool,
+    /// Whether the linker support GNU-like arguments such as -O. Defaults to false.
+    pub linker_is_gnu: bool,
+    /// Whether the linker support rpaths or not. Defaults to false.
+    pub has_rpath: bool,
+    /// Whether to disable linking to compiler-rt. Defaults to false, as LLVM will emit references
+    /// to the functions that compiler-rt provides.
+    pub no_compiler_rt: bool,
+    /// Dynamically linked executables can be compiled as position independent if the default
+    /// relocation model of position independent code is not changed. This is a requirement to take
+    /// advantage of ASLR, as otherwise the functions in the executable are not randomized and can
+    /// be used during an exploit of a vulnerability in any code.
+    pub position_independent_executables: bool,
+    /// Whether executables are linked statically, including the C library and the objects in
+    /// `pre_link_objects` and `post_link_objects`, which rustc then ships for the target. If
+    /// they are also position independent, they are linked with `-static-pie`, which needs GCC 8
+    /// or later with the `gcc` linker flavor. Defaults to false.
+    pub static_executables: bool,
+}
+
+impl Default for TargetOptions {
+    /// Create a set of "sane defaults" for any target. This is still incomplete, and if used for
+    /// compilation, will certainly not work.
+    fn default() -> TargetOptions {
+        TargetOptions {
+            linker: "cc".to_string(),
+            linker_flavor: "gcc".to_string(),
+            pre_link_args: Vec::new(),
+            post_link_args: Vec::new(),
+            pre_link_objects: Vec::new(),
+            pre_link_objects_pie: Vec::new(),
+            post_link_objects: Vec::new(),
+            objcopy: "objcopy".to_string(),
+            cpu: "generic".to_string(),
+            features: "".to_string(),
+            dynamic_linking: false,
+            executables: false,
+            morestack: false,
+            stack_probes: false,
+            relocation_model: "pic".to_string(),
+            code_model: "default".to_string(),
+            disable_redzone: false,
+            eliminate_frame_pointer: true,
+            function_sections: true,
+            dll_prefix: "lib".to_string(),
+            dll_suffix: ".so".to_string(),
+            exe_suffix: "".to_string(),
+            staticlib_prefix: "lib".to_string(),
+            staticlib_suffix: ".a".to_string(),
+            is_like_osx: false,
+            is_like_windows: false,
+            is_like_android: false,
+            linker_is_gnu: false,
+            has_rpath: false,
+            no_compiler_rt: false,
+            position_independent_executables: false,
+            static_executables: false,
+        }
+    }
+}
+
+impl Target {
+    /// Given a function ABI, turn "System" into the correct ABI for this target.
+    pub fn adjust_abi(&self, abi: abi::Abi) -> abi::Abi {
+        match abi {
+            abi::System => {
+                if self.options.is_like_windows && self.arch == "x86" {
+                    abi::Stdcall
+                } else {
+                    abi::C
+                }
+            },
+            abi => abi
+        }
+    }
+
+    /// Load a target descriptor from a JSON object.
+    ///
+    /// If the object has an `inherits` key naming a built-in target, the
+    /// target starts out as a copy of that one and every other key is
+    /// optional. Otherwise `data-layout`, `llvm-target`, `target-endian`,
+    /// `target-pointer-width`, `arch` and `os` are required. Keys that aren't
+    /// options of a target are warned about and ignored.
+    pub fn from_json(obj: Json) -> Result<Target, String> {
+        let handler = diagnostic::default_handler(diagnostic::Auto, None, true);
+
+        let fields = match obj.as_object() {
+            Some(fields) => fields,
+            None => return Err("the target specification must be a JSON object".to_string()),
+        };
+        let mut known_keys = vec!["inherits".to_string()];
+
+        let get_string = |name: &str| -> Result<Option<String>, String> {
+            match obj.find(name) {
+                None => Ok(None),
+                Some(value) => match value.as_string() {
+                    Some(s) => Ok(Some(s.to_string())),
+                    None => Err(format!("`{}` must be a string", name)),
+                },
+            }
+        };
+
+        let inherited = match try!(get_string("inherits")) {
+            Some(name) => match load_specific(&name) {
+                Some(target) => Some(target),
+                None => return Err(format!("`inherits` names `{}`, which is not a \
+                                            built-in target", name)),
+            },
+            None => None,
+        };
+
+        macro_rules! target_field {
+            ($field:ident, $key_name:expr) => ( {
+                known_keys.push($key_name.to_string());
+                match (try!(get_string($key_name)), inherited.as_ref()) {
+                    (Some(value), _) => value,
+                    (None, Some(target)) => target.$field.clone(),
+                    (None, None) => return Err(format!("`{}` is required", $key_name)),
+                }
+            } );
+        }
+
+        let mut base = Target {
+            data_layout: target_field!(data_layout, "data-layout"),
+            llvm_target: target_field!(llvm_target, "llvm-target"),
+            target_endian: target_field!(target_endian, "target-endian"),
+            target_pointer_width: target_field!(target_pointer_width, "target-pointer-width"),
+            arch: target_field!(arch, "arch"),
+            target_os: target_field!(target_os, "os"),
+            target_env: {
+                known_keys.push("env".to_string());
+                match try!(get_string("env")) {
+                    Some(env) => env,
+                    None => inherited.as_ref().map(|t| t.target_env.clone())
+                                     .unwrap_or(String::new()),
+                }
+            },
+            options: match inherited {
+                Some(ref target) => target.options.clone(),
+                None => Default::default(),
+            },
+        };
+
+        macro_rules! key {
+            ($key_name:ident) => ( {
+                let name = (stringify!($key_name)).replace("_", "-");
+                if let Some(value) = try!(get_string(&name[..])) {
+                    base.options.$key_name = value;
+                }
+                known_keys.push(name);
+            } );
+            ($key_name:ident, bool) => ( {
+                let name = (stringify!($key_name)).replace("_", "-");
+                if let Some(value) = obj.find(&name[..]) {
+                    match value.as_boolean() {
+                        Some(b) => base.options.$key_name = b,
+                        None => return Err(format!("`{}` must be a boolean", name)),
+                    }
+                }
+                known_keys.push(name);
+            } );
+            ($key_name:ident, list) => ( {
+                let name = (stringify!($key_name)).replace("_", "-");
+                if let Some(value) = obj.find(&name[..]) {
+                    let list = value.as_array().and_then(|v| {
+                        v.iter().map(|a| a.as_string().map(|s| s.to_string()))
+                                .collect::<Option<Vec<String>>>()
+                    });
+                    match list {
+                        Some(list) => base.options.$key_name = list,
+                        None => return Err(format!("`{}` must be a list of strings", name)),
+                    }
+                }
+                known_keys.push(name);
+            } );
+        }
+
+        key!(cpu);
+        key!(linker);
+        key!(linker_flavor);
+        key!(objcopy);
+        key!(relocation_model);
+        key!(code_model);
+        key!(dll_prefix);
+        key!(dll_suffix);
+        key!(exe_suffix);
+        key!(staticlib_prefix);
+        key!(staticlib_suffix);
+        key!(features);
+        key!(dynamic_linking, bool);
+        key!(executables, bool);
+        key!(morestack, bool);
+        key!(stack_probes, bool);
+        key!(disable_redzone, bool);
+        key!(eliminate_frame_pointer, bool);
+        key!(function_sections, bool);
+        key!(is_like_osx, bool);
+        key!(is_like_windows, bool);
+        key!(is_like_android, bool);
+        key!(linker_is_gnu, bool);
+        key!(has_rpath, bool);
+        key!(no_compiler_rt, bool);
+        key!(position_independent_executables, bool);
+        key!(static_executables, bool);
+        key!(pre_link_args, list);
+        key!(post_link_args, list);
+        key!(pre_link_objects, list);
+        key!(pre_link_objects_pie, list);
+        key!(post_link_objects, list);
+
+        for key in fields.keys() {
+            if !known_keys.contains(key) {
+                handler.warn(&format!("unknown key `{}` in the target specification \
+                                       is ignored", key));
+            }
+        }
+
+        Ok(base)
+    }
+
+    /// Search RUST_TARGET_PATH for a JSON file specifying the given target
+    /// triple. Note that it could also just be a bare filename already, so also
+    /// check for that. If one of the hardcoded targets we know about, just
+    /// return it directly.
+    ///
+    /// The error string could come from any of the APIs called, including
+    /// filesystem access and JSON decoding.
+    pub fn search(target: &str) -> Result<Target, String> {
+        use std::env;
+        use std::ffi::OsString;
+        use std::fs::File;
+        use std::path::{Path, PathBuf};
+        use serialize::json;
+
+        fn load_file(path: &Path) -> Result<Target, String> {
+            let mut f = try!(File::open(path).map_err(|e| e.to_string()));
+            let mut contents = Vec::new();
+            try!(f.read_to_end(&mut contents).map_err(|e| e.to_string()));
+            let obj = try!(json::from_reader(&mut &contents[..])
+                                .map_err(|e| e.to_string()));
+            Target::from_json(obj).map_err(|e| format!("{}: {}", path.display(), e))
+        }
+
+        if let Some(t) = load_specific(target) {
+            return Ok(t);
+        }
+        if target == "x86_64-w64-mingw32" {
+            return Ok(x86_64_pc_windows_gnu::target());
+        } else if target == "i686-w64-mingw32" {
+            return Ok(i686_pc_windows_gnu::target());
+        }
+
+        let path = Path::new(target);
+
+        if path.is_file() {
+            return load_file(&path);
+        }
+
+        let path = {
+            let mut target = target.to_string();
+            target.push_str(".json");
+            PathBuf::from(target)
+        };
+
+        let target_path = env::var_os("RUST_TARGET_PATH")
+                              .unwrap_or(OsString::new());
+
+        // FIXME 16351: add a sane default search path?
+
+        for dir in env::split_paths(&target_path) {
+            let p =  dir.join(&path);
+            if p.is_file() {
+                return load_file(&p);
+            }
+        }
+
+        Err(format!("Could not find specification for target {:?}", target))
+    }
+}
+
+impl ToJson for Target {
+    /// The specification in the format `Target::from_json` reads, with every option spelled
+    /// out, so that it can be used as the starting point of a custom target.
+    fn to_json(&self) -> Json {
+        let mut d = BTreeMap::new();
+
+        macro_rules! target_val {
+            ($attr:ident) => ( target_val!($attr, (stringify!($attr)).replace("_", "-")) );
+            ($attr:ident, $key_name:expr) => ( {
+                d.insert($key_name.to_string(), self.$attr.to_json());
+            } );
+        }
+
+        macro_rules! option_val {
+            ($attr:ident) => ( {
+                let name = (stringify!($attr)).replace("_", "-");
+                d.insert(name, self.options.$attr.to_json());
+            } );
+        }
+
+        target_val!(data_layout);
+        target_val!(llvm_target);
+        target_val!(target_endian);
+        target_val!(target_pointer_width);
+        target_val!(arch);
+        target_val!(target_os, "os");
+        target_val!(target_env, "env");
+
+        option_val!(cpu);
+        option_val!(linker);
+        option_val!(linker_flavor);
+        option_val!(objcopy);
+        option_val!(relocation_model);
+        option_val!(code_model);
+        option_val!(dll_prefix);
+        option_val!(dll_suffix);
+        option_val!(exe_suffix);
+        option_val!(staticlib_prefix);
+        option_val!(staticlib_suffix);
+        option_val!(features);
+        option_val!(dynamic_linking);
+        option_val!(executables);
+        option_val!(morestack);
+        option_val!(stack_probes);
+        option_val!(disable_redzone);
+        option_val!(eliminate_frame_pointer);
+        option_val!(function_sections);
+        option_val!(is_like_osx);
+        option_val!(is_like_windows);
+        option_val!(is_like_android);
+        option_val!(linker_is_gnu);
+        option_val!(has_rpath);
+        option_val!(no_compiler_rt);
+        option_val!(position_independent_executables);
+        option_val!(static_executables);
+        option_val!(pre_link_args);
+        option_val!(post_link_args);
+        option_val!(pre_link_objects);
+        option_val!(pre_link_objects_pie);
+        option_val!(post_link_objects);
+
+        Json::Object(d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serialize::json::{self, Json, ToJson};
+    use super::{Target, TARGETS, load_specific};
+
+    fn parse(s: &str) -> Json {
+        json::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn builtin_targets_round_trip() {
+        for triple in TARGETS {
+            let target = load_specific(triple).unwrap();
+            assert_eq!(Target::from_json(target.to_json()), Ok(target.clone()));
+            // Inheriting from a target and changing nothing gives it back.
+            let spec = format!("{{\"inherits\": \"{}\"}}", triple);
+            assert_eq!(Target::from_json(parse(&spec)), Ok(target));
+        }
+    }
+
+    #[test]
+    fn inherits_builtin_target() {
+        let target = Target::from_json(parse(r#"{
+            "inherits": "x86_64-unknown-linux-gnu",
+            "os": "myos",
+            "morestack": false,
+            "pre-link-args": ["-nostdlib"]
+        }"#)).unwrap();
+        let base = load_specific("x86_64-unknown-linux-gnu").unwrap();
+        assert_eq!(target.target_os, "myos");
+        assert_eq!(target.llvm_target, base.llvm_target);
+        assert_eq!(target.options.morestack, false);
+        assert_eq!(target.options.pre_link_args, ["-nostdlib".to_string()]);
+        assert_eq!(target.options.linker, base.options.linker);
+    }
+
+    #[test]
+    fn invalid_specifications() {
+        fn error(s: &str) -> String {
+            Target::from_json(parse(s)).unwrap_err()
+        }
+        assert_eq!(error(r#"{"inherits": "x86_64-unknown-nowhere"}"#),
+                   "`inherits` names `x86_64-unknown-nowhere`, which is not a built-in target");
+        assert_eq!(error(r#"{"data-layout": "e", "target-endian": "little"}"#),
+                   "`llvm-target` is required");
+        assert_eq!(error(r#"{"inherits": "x86_64-unknown-linux-gnu", "morestack": "no"}"#),
+                   "`morestack` must be a boolean");
+        assert_eq!(error(r#"{"inherits": "x86_64-unknown-linux-gnu", "cpu": 1}"#),
+                   "`cpu` must be a string");
+        assert_eq!(error(r#"{"inherits": "x86_64-unknown-linux-gnu", "pre-link-args": [1]}"#),
+                   "`pre-link-args` must be a list of strings");
+        assert_eq!(error("[]"), "the target specification must be a JSON object");
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+use target::Target;
+
+pub fn target() -> Target {
+    let mut base = super::linux_base::opts();
+    base.cpu = "x86-64".to_string();
+    base.pre_link_args.push("-m64".to_string());
+    base.morestack = false;
+    base.stack_probes = true;
+
+    // Executables are linked statically against the musl that ships with
+    // rustc for this target, so that they run on any Linux system without
+    // depending on its C library. The system compiler driver is still used as
+    // the linker, but it is kept from adding the host's C runtime objects and
+    // libraries, which the objects below and the `libc` crate stand in for.
+    base.pre_link_args.push("-nostdlib".to_string());
+    base.pre_link_args.push("-Wl,--eh-frame-hdr".to_string());
+
+    // libc.a and libunwind.a depend on each other, so the libraries are
+    // searched repeatedly until nothing new is resolved.
+    base.pre_link_args.push("-Wl,-(".to_string());
+    base.post_link_args.push("-Wl,-)".to_string());
+
+    base.pre_link_objects.push("crt1.o".to_string());
+    base.pre_link_objects.push("crti.o".to_string());
+    base.post_link_objects.push("crtn.o".to_string());
+
+    base.static_executables = true;
+    // Static position independent executables need `-static-pie`, which C
+    // compiler drivers only take since GCC 8.
+    base.position_independent_executables = false;
+    base.dynamic_linking = false;
+    base.has_rpath = false;
+
+    Target {
+        data_layout: "e-p:64:64:64-i1:8:8-i8:8:8-i16:16:16-i32:32:32-i64:64:64-\
+                      f32:32:32-f64:64:64-v64:64:64-v128:128:128-a:0:64-\
+                      s0:64:64-f80:128:128-n8:16:32:64-S128".to_string(),
+        llvm_target: "x86_64-unknown-linux-musl".to_string(),
+        target_endian: "little".to_string(),
+        target_pointer_width: "64".to_string(),
+        arch: "x86_64".to_string(),
+        target_os: "linux".to_string(),
+        target_env: "musl".to_string(),
+        options: base,
+    }
+}
@@ -122,6 +122,12 @@ pub fn compile_input(sess: Session,
                                                    id,
                                                    control.make_glob_map);
 
+        // With -Z no-trans, nothing more is decoded after analysis.
+        if control.after_analysis.stop == Compilation::Stop &&
+           analysis.ty_cx.sess.meta_stats() {
+            analysis.ty_cx.sess.cstore.print_decode_stats();
+        }
+
         controller_entry_point!(after_analysis,
                                 CompileState::state_after_analysis(input,
                                                                    &analysis.ty_cx.sess,
@@ -136,6 +142,10 @@ pub fn compile_input(sess: Session,
         }
         let (tcx, trans) = phase_4_translate_to_llvm(analysis);
 
+        if tcx.sess.meta_stats() {
+            tcx.sess.cstore.print_decode_stats();
+        }
+
         if log_enabled!(::log::INFO) {
             println!("Post-trans");
             tcx.print_debug_stats();
@@ -665,6 +675,9 @@ pub fn phase_3_run_analysis_passes<'tcx>(sess: Session,
     time(time_passes, "rvalue checking", (), |_|
          middle::check_rvalues::check_crate(&ty_cx, krate));
 
+    time(time_passes, "generic recursion checking", (), |_|
+         middle::check_generic_recursion::check_crate(&ty_cx));
+
     // Avoid overwhelming user with errors if type checking failed.
     // I'm not sure how helpful this is, to be honest, but it avoids a
     // lot of annoying errors in the compile-fail tests (basically,
@@ -696,6 +709,10 @@ pub fn phase_3_run_analysis_passes<'tcx>(sess: Session,
     // The above three passes generate errors w/o aborting
     ty_cx.sess.abort_if_errors();
 
+    if ty_cx.sess.opts.debugging_opts.print_variance {
+        middle::type_properties::print_variances(&ty_cx, &exported_items);
+    }
+
     ty::CrateAnalysis {
         export_map: export_map,
         ty_cx: ty_cx,
@@ -850,6 +867,9 @@ pub fn collect_crate_types(session: &Session,
                 Some(ref n) if *n == "dylib" => {
                     Some(config::CrateTypeDylib)
                 }
+                Some(ref n) if *n == "cdylib" => {
+                    Some(config::CrateTypeCdylib)
+                }
                 Some(ref n) if *n == "lib" => {
                     Some(config::default_lib_output())
                 }
@@ -69,6 +69,7 @@ use rustc::lint::Lint;
 use rustc::lint;
 use rustc::metadata;
 use rustc::util::common::time;
+use serialize::json::ToJson;
 
 use std::cmp::Ordering::Equal;
 use std::env;
@@ -406,6 +407,7 @@ impl RustcDefaultCalls {
                     let mut v = Vec::new();
                     metadata::loader::list_file_metadata(sess.target.target.options.is_like_osx,
                                                          path,
+                                                         sess.opts.debugging_opts.ls_json,
                                                          &mut v).unwrap();
                     println!("{}", String::from_utf8(v).unwrap());
                 }
@@ -425,7 +427,8 @@ impl RustcDefaultCalls {
                         odir: &Option<PathBuf>,
                         ofile: &Option<PathBuf>)
                         -> Compilation {
-        if sess.opts.prints.is_empty() {
+        // Nothing to print before compiling.
+        if sess.opts.prints.iter().all(|p| *p == PrintRequest::NativeStaticLibs) {
             return Compilation::Continue;
         }
 
@@ -433,6 +436,28 @@ impl RustcDefaultCalls {
         for req in &sess.opts.prints {
             match *req {
                 PrintRequest::Sysroot => println!("{}", sess.sysroot().display()),
+                PrintRequest::TargetList => {
+                    for target in rustc_back::target::TARGETS {
+                        println!("{}", target);
+                    }
+                }
+                PrintRequest::TargetSpec => {
+                    println!("{}", sess.target.target.to_json().pretty());
+                }
+                PrintRequest::Cfg => {
+                    for item in &config::build_configuration(sess) {
+                        match item.node {
+                            ast::MetaWord(ref name) => println!("{}", name),
+                            ast::MetaNameValue(ref name, ref value) => {
+                                if let ast::LitStr(ref value, _) = value.node {
+                                    println!("{}=\"{}\"", name, value.escape_default());
+                                }
+                            }
+                            ast::MetaList(..) => {}
+                        }
+                    }
+                }
+                PrintRequest::NativeStaticLibs => {}
                 PrintRequest::FileNames |
                 PrintRequest::CrateName => {
                     let input = match input {
@@ -466,7 +491,15 @@ impl RustcDefaultCalls {
                 }
             }
         }
-        return Compilation::Stop;
+
+        // The native libraries of a static library are only known once it
+        // has been linked, so they are printed then; compilation has to go
+        // on for that, whatever else was asked for.
+        if sess.opts.prints.contains(&PrintRequest::NativeStaticLibs) {
+            Compilation::Continue
+        } else {
+            Compilation::Stop
+        }
     }
 }
 
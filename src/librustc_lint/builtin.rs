@@ -28,19 +28,21 @@
 //! Use the former for unit-like structs and the latter for structs with
 //! a `pub fn new()`.
 
-use metadata::{csearch, decoder};
+use metadata::{csearch, decoder, loader};
 use middle::def::*;
 use middle::subst::Substs;
 use middle::ty::{self, Ty};
 use middle::{def, pat_util, stability};
 use middle::const_eval::{eval_const_expr_partial, const_int, const_uint};
 use middle::cfg;
+use middle::traits;
 use util::ppaux::ty_to_string;
 use util::nodemap::{FnvHashMap, NodeSet};
 use lint::{Level, Context, LintPass, LintArray, Lint};
 
 use std::collections::{HashSet, BitSet};
 use std::collections::hash_map::Entry::{Occupied, Vacant};
+use std::path::Path;
 use std::{cmp, slice};
 use std::{i8, i16, i32, i64, u8, u16, u32, u64, f32, f64};
 
@@ -1744,8 +1746,22 @@ declare_lint! {
 pub struct Stability;
 
 impl Stability {
-    fn lint(&self, cx: &Context, _id: ast::DefId, span: Span, stability: &Option<attr::Stability>) {
+    fn lint(&self, cx: &Context, _id: ast::DefId, span: Span,
+            stability: &Option<attr::Stability>, deprecation: &Option<attr::Deprecation>) {
         // Deprecated attributes apply in-crate and cross-crate.
+        if let Some(ref depr) = *deprecation {
+            let mut msg = match depr.note {
+                Some(ref note) => format!("use of deprecated item: {}", *note),
+                None => "use of deprecated item".to_string(),
+            };
+            msg.push_str(&format!(" (deprecated since {})", depr.since));
+            if let Some(ref suggestion) = depr.suggestion {
+                msg.push_str(&format!("; use `{}` instead", *suggestion));
+            }
+            cx.span_lint(DEPRECATED, span, &msg[..]);
+            return;
+        }
+
         let (lint, label) = match *stability {
             Some(attr::Stability { deprecated_since: Some(_), .. }) =>
                 (DEPRECATED, "deprecated"),
@@ -1775,22 +1791,86 @@ impl LintPass for Stability {
 
     fn check_item(&mut self, cx: &Context, item: &ast::Item) {
         stability::check_item(cx.tcx, item, false,
-                              &mut |id, sp, stab| self.lint(cx, id, sp, stab));
+                              &mut |id, sp, stab, depr| self.lint(cx, id, sp, stab, depr));
     }
 
     fn check_expr(&mut self, cx: &Context, e: &ast::Expr) {
         stability::check_expr(cx.tcx, e,
-                              &mut |id, sp, stab| self.lint(cx, id, sp, stab));
+                              &mut |id, sp, stab, depr| self.lint(cx, id, sp, stab, depr));
     }
 
     fn check_path(&mut self, cx: &Context, path: &ast::Path, id: ast::NodeId) {
         stability::check_path(cx.tcx, path, id,
-                              &mut |id, sp, stab| self.lint(cx, id, sp, stab));
+                              &mut |id, sp, stab, depr| self.lint(cx, id, sp, stab, depr));
     }
 
     fn check_pat(&mut self, cx: &Context, pat: &ast::Pat) {
         stability::check_pat(cx.tcx, pat,
-                             &mut |id, sp, stab| self.lint(cx, id, sp, stab))
+                             &mut |id, sp, stab, depr| self.lint(cx, id, sp, stab, depr))
+    }
+}
+
+declare_lint! {
+    pub OBJECT_SAFETY_REGRESSION,
+    Warn,
+    "detects public traits that were object-safe in a previous build but no longer are"
+}
+
+/// Compares the object safety of public traits against the build of this
+/// crate given with `-Z object-safety-baseline`, if any.
+pub struct ObjectSafetyRegression {
+    // Paths of the traits that were object-safe in the baseline build.
+    baseline: Option<HashSet<String>>,
+}
+
+impl ObjectSafetyRegression {
+    pub fn new() -> ObjectSafetyRegression {
+        ObjectSafetyRegression { baseline: None }
+    }
+}
+
+impl LintPass for ObjectSafetyRegression {
+    fn get_lints(&self) -> LintArray {
+        lint_array!(OBJECT_SAFETY_REGRESSION)
+    }
+
+    fn check_crate(&mut self, cx: &Context, _: &ast::Crate) {
+        let path = match cx.sess().opts.debugging_opts.object_safety_baseline {
+            Some(ref path) => Path::new(path).to_path_buf(),
+            None => return,
+        };
+        let is_osx = cx.sess().target.target.options.is_like_osx;
+        match loader::read_object_safe_traits(is_osx, &path) {
+            Ok(paths) => self.baseline = Some(paths.into_iter().collect()),
+            Err(msg) => {
+                cx.sess().err(&format!("couldn't read object safety baseline `{}`: {}",
+                                       path.display(), msg));
+            }
+        }
+    }
+
+    fn check_item(&mut self, cx: &Context, it: &ast::Item) {
+        if let ast::ItemTrait(..) = it.node {
+            let baseline = match self.baseline {
+                Some(ref baseline) => baseline,
+                None => return,
+            };
+            if !cx.exported_items.contains(&it.id) {
+                return;
+            }
+
+            let def_id = local_def(it.id);
+            let path = ty::item_path_str(cx.tcx, def_id);
+            if baseline.contains(&path) && !traits::is_object_safe(cx.tcx, def_id) {
+                cx.span_lint(OBJECT_SAFETY_REGRESSION, it.span,
+                             &format!("trait `{}` was object-safe in the baseline build, \
+                                       but no longer is", path));
+
+                if cx.current_level(OBJECT_SAFETY_REGRESSION) != Level::Allow {
+                    traits::note_object_safety_violations(cx.tcx, it.span, def_id);
+                }
+            }
+        }
     }
 }
 
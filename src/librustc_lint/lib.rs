@@ -116,6 +116,7 @@ pub fn register_builtins(store: &mut lint::LintStore, sess: Option<&Session>) {
                           RawPointerDerive,
                           MissingDoc,
                           MissingDebugImplementations,
+                          ObjectSafetyRegression,
                           );
 
     add_lint_group!(sess, "bad_style",
@@ -503,6 +503,9 @@ pub type DebugLocRef = *mut DebugLoc_opaque;
 #[allow(missing_copy_implementations)]
 pub enum SMDiagnostic_opaque {}
 pub type SMDiagnosticRef = *mut SMDiagnostic_opaque;
+#[allow(missing_copy_implementations)]
+pub enum ProfileReader_opaque {}
+pub type ProfileReaderRef = *mut ProfileReader_opaque;
 
 pub type DiagnosticHandler = unsafe extern "C" fn(DiagnosticInfoRef, *mut c_void);
 pub type InlineAsmDiagHandler = unsafe extern "C" fn(SMDiagnosticRef, *const c_void, c_uint);
@@ -709,6 +712,10 @@ extern {
     pub fn LLVMAddNamedMetadataOperand(M: ModuleRef,
                                        Str: *const c_char,
                                        Val: ValueRef);
+    pub fn LLVMGetNamedMetadataNumOperands(M: ModuleRef, Str: *const c_char) -> c_uint;
+    pub fn LLVMGetNamedMetadataOperands(M: ModuleRef,
+                                        Str: *const c_char,
+                                        Dest: *mut ValueRef);
 
     /* Operations on scalar constants */
     pub fn LLVMConstInt(IntTy: TypeRef, N: c_ulonglong, SignExtend: Bool)
@@ -985,6 +992,7 @@ extern {
     pub fn LLVMGetFirstBasicBlock(Fn: ValueRef) -> BasicBlockRef;
     pub fn LLVMGetLastBasicBlock(Fn: ValueRef) -> BasicBlockRef;
     pub fn LLVMGetNextBasicBlock(BB: BasicBlockRef) -> BasicBlockRef;
+    pub fn LLVMGetBasicBlockTerminator(BB: BasicBlockRef) -> ValueRef;
     pub fn LLVMGetPreviousBasicBlock(BB: BasicBlockRef) -> BasicBlockRef;
     pub fn LLVMGetEntryBasicBlock(Fn: ValueRef) -> BasicBlockRef;
 
@@ -2045,10 +2053,42 @@ extern {
     pub fn LLVMRustLinkInExternalBitcode(M: ModuleRef,
                                          bc: *const c_char,
                                          len: size_t) -> bool;
+    pub fn LLVMRustBuildImportSummary(bc: *const c_char,
+                                      len: size_t,
+                                      limit: c_uint,
+                                      candidate: extern "C" fn(*mut c_void,
+                                                               *const c_char,
+                                                               size_t,
+                                                               c_uint),
+                                      data: *mut c_void,
+                                      out: RustStringRef) -> bool;
+    pub fn LLVMRustCountExternalCalls(M: ModuleRef,
+                                      f: extern "C" fn(*mut c_void,
+                                                       *const c_char,
+                                                       size_t,
+                                                       c_uint),
+                                      data: *mut c_void);
+    pub fn LLVMRustImportFunctions(M: ModuleRef,
+                                   bc: *const c_char,
+                                   len: size_t,
+                                   names: *const *const c_char,
+                                   num_names: size_t) -> bool;
+    pub fn LLVMRustHasFunctionAttrString(Fn: ValueRef, Name: *const c_char) -> bool;
+    pub fn LLVMRustFrameSizeEstimate(Fn: ValueRef) -> uint64_t;
+    pub fn LLVMRustOutlineFunctionBody(Fn: ValueRef, Name: *const c_char) -> ValueRef;
     pub fn LLVMRustRunRestrictionPass(M: ModuleRef,
                                       syms: *const *const c_char,
                                       len: size_t);
     pub fn LLVMRustMarkAllFunctionsNounwind(M: ModuleRef);
+    pub fn LLVMRustAddInstrProfilingPass(PM: PassManagerRef, Output: *const c_char);
+
+    pub fn LLVMRustOpenProfile(Path: *const c_char) -> ProfileReaderRef;
+    pub fn LLVMRustGetProfileCounts(Reader: ProfileReaderRef,
+                                    Name: *const c_char,
+                                    Hash: u64,
+                                    Counts: *mut u64,
+                                    Len: size_t) -> i64;
+    pub fn LLVMRustFreeProfile(Reader: ProfileReaderRef);
 
     pub fn LLVMRustOpenArchive(path: *const c_char) -> ArchiveRef;
     pub fn LLVMRustArchiveReadSection(AR: ArchiveRef, name: *const c_char,
@@ -2235,9 +2275,13 @@ pub unsafe extern "C" fn rust_llvm_string_write_impl(sr: RustStringRef,
 }
 
 pub fn build_string<F>(f: F) -> Option<String> where F: FnOnce(RustStringRef){
+    String::from_utf8(build_bytes(f)).ok()
+}
+
+pub fn build_bytes<F>(f: F) -> Vec<u8> where F: FnOnce(RustStringRef){
     let mut buf = RefCell::new(Vec::new());
     f(&mut buf as RustStringRepr as RustStringRef);
-    String::from_utf8(buf.into_inner()).ok()
+    buf.into_inner()
 }
 
 pub unsafe fn twine_to_string(tr: TwineRef) -> String {
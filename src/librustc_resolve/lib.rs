@@ -89,6 +89,7 @@ use syntax::visit::{self, Visitor};
 use std::collections::{HashMap, HashSet};
 use std::collections::hash_map::Entry::{Occupied, Vacant};
 use std::cell::{Cell, RefCell};
+use std::cmp;
 use std::fmt;
 use std::mem::replace;
 use std::rc::{Rc, Weak};
@@ -280,6 +281,28 @@ enum FallbackSuggestion {
     TraitMethod(String),
 }
 
+/// An item found elsewhere in the module graph that the user may have meant
+/// when naming something that failed to resolve.
+struct ImportCandidate {
+    /// The full path to the item, starting at the crate root.
+    path: Vec<Name>,
+    /// The lev distance between the item's name and the unresolved name.
+    distance: usize,
+    /// The length of the longest prefix of `path` that can be named from
+    /// the module the error occurred in, if the whole path can't be.
+    private_at: Option<usize>,
+}
+
+/// An item found by `collect_import_candidates`, before deciding whether it
+/// is accessible from the module the error occurred in.
+struct CandidateItem {
+    path: Vec<Name>,
+    distance: usize,
+    /// The length of each prefix of `path` that ends in a private item,
+    /// along with the module that item is defined in.
+    private_steps: Vec<(usize, Rc<Module>)>,
+}
+
 #[derive(Copy, Clone)]
 enum TypeParameters<'a> {
     NoTypeParameters,
@@ -820,6 +843,9 @@ pub struct Resolver<'a, 'tcx:'a> {
 
     used_imports: HashSet<(NodeId, Namespace)>,
     used_crates: HashSet<CrateNum>,
+
+    // The items found for each name by `collect_import_candidates`.
+    import_candidates_cache: HashMap<(Name, Option<Namespace>), Rc<Vec<CandidateItem>>>,
 }
 
 #[derive(PartialEq)]
@@ -881,6 +907,7 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
             emit_errors: true,
             make_glob_map: make_glob_map == MakeGlobMap::Yes,
             glob_map: HashMap::new(),
+            import_candidates_cache: HashMap::new(),
         }
     }
 
@@ -2330,6 +2357,13 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                         let msg = format!("use of undeclared {} `{}`", kind,
                                           path_names_to_string(path, 0));
                         self.resolve_error(ty.span, &msg[..]);
+
+                        if maybe_qself.is_none() {
+                            let name = path.segments.last().unwrap().identifier.name;
+                            let current_module = self.current_module.clone();
+                            self.report_import_candidates(ty.span, &current_module,
+                                                          name, Some(TypeNS));
+                        }
                     }
                 }
             }
@@ -3085,6 +3119,156 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
         }
     }
 
+    /// Searches the module graph, including the modules of extern crates,
+    /// for items named `name` or something close to it. The candidates are
+    /// sorted so that exact matches and accessible items come first.
+    fn lookup_import_candidates(&mut self,
+                                from: &Rc<Module>,
+                                name: Name,
+                                namespace: Option<Namespace>)
+                                -> Vec<ImportCandidate> {
+        let cached = self.import_candidates_cache.get(&(name, namespace)).cloned();
+        let items = match cached {
+            Some(items) => items,
+            None => {
+                let items = Rc::new(self.collect_import_candidates(name, namespace));
+                self.import_candidates_cache.insert((name, namespace), items.clone());
+                items
+            }
+        };
+
+        // A private step can be named from within the module it is defined
+        // in; the first step that can't be makes the rest of the path
+        // inaccessible.
+        let mut candidates: Vec<_> = items.iter().map(|item| {
+            let private_at = item.private_steps.iter()
+                .find(|&&(_, ref parent)| !is_ancestor_or_self(parent, from))
+                .map(|&(len, _)| len);
+            ImportCandidate {
+                path: item.path.clone(),
+                distance: item.distance,
+                private_at: private_at,
+            }
+        }).collect();
+
+        candidates.sort_by(|a, b| {
+            (a.distance, a.private_at.is_some(), a.path.len())
+                .cmp(&(b.distance, b.private_at.is_some(), b.path.len()))
+        });
+        candidates
+    }
+
+    /// Walks the whole module graph for `lookup_import_candidates`. This
+    /// populates the module of every extern crate it reaches, so the result
+    /// is cached per name and namespace.
+    fn collect_import_candidates(&mut self,
+                                 name: Name,
+                                 namespace: Option<Namespace>)
+                                 -> Vec<CandidateItem> {
+        let name_str = token::get_name(name);
+        let max_distance = cmp::max(name_str.len() / 3, 1);
+
+        let mut items = Vec::new();
+        let mut seen_modules = HashSet::new();
+        let mut worklist = vec![(self.graph_root.get_module(), Vec::new(), Vec::new())];
+
+        while let Some((module, path, private_steps)) = worklist.pop() {
+            if let Some(did) = module.def_id.get() {
+                if !seen_modules.insert(did) {
+                    continue;
+                }
+            }
+            build_reduced_graph::populate_module_if_necessary(self, &module);
+
+            for (&child_name, bindings) in module.children.borrow().iter() {
+                let mut child_path = path.clone();
+                child_path.push(child_name);
+
+                let child_str = token::get_name(child_name);
+                let distance = lev_distance(&name_str, &child_str);
+                let defined = match namespace {
+                    Some(ns) => bindings.defined_in_namespace(ns),
+                    None => true,
+                };
+                if defined && distance <= max_distance {
+                    let is_public = match namespace {
+                        Some(ns) => bindings.defined_in_public_namespace(ns),
+                        None => bindings.defined_in_public_namespace(TypeNS) ||
+                                bindings.defined_in_public_namespace(ValueNS),
+                    };
+                    let mut private_steps: Vec<_> = private_steps.clone();
+                    if !is_public {
+                        private_steps.push((child_path.len(), module.clone()));
+                    }
+                    items.push(CandidateItem {
+                        path: child_path.clone(),
+                        distance: distance,
+                        private_steps: private_steps,
+                    });
+                }
+
+                if let Some(child_module) = bindings.get_module_if_available() {
+                    if child_module.kind.get() == NormalModuleKind {
+                        let mut private_steps = private_steps.clone();
+                        if !bindings.defined_in_public_namespace(TypeNS) {
+                            private_steps.push((child_path.len(), module.clone()));
+                        }
+                        worklist.push((child_module, child_path, private_steps));
+                    }
+                }
+            }
+
+            // An `extern crate` item is private to the module that declares
+            // it, so only the one in the crate root can be named from
+            // everywhere.
+            for (&crate_name, crate_module) in module.external_module_children.borrow().iter() {
+                let mut crate_path = path.clone();
+                crate_path.push(crate_name);
+                let mut private_steps = private_steps.clone();
+                private_steps.push((crate_path.len(), module.clone()));
+                worklist.push((crate_module.clone(), crate_path, private_steps));
+            }
+        }
+
+        items
+    }
+
+    /// Adds notes to an error about `name` failing to resolve, listing the
+    /// paths it could have been meant to refer to.
+    fn report_import_candidates(&mut self,
+                                span: Span,
+                                from: &Rc<Module>,
+                                name: Name,
+                                namespace: Option<Namespace>) {
+        if !self.emit_errors {
+            return;
+        }
+
+        // Don't overwhelm the user with suggestions.
+        const MAX_CANDIDATES: usize = 4;
+
+        let candidates = self.lookup_import_candidates(from, name, namespace);
+        let has_exact_match = candidates.iter().any(|c| c.distance == 0);
+        for candidate in candidates.iter()
+                                   .filter(|c| !has_exact_match || c.distance == 0)
+                                   .take(MAX_CANDIDATES) {
+            let path = names_to_string(&candidate.path);
+            match candidate.private_at {
+                None => {
+                    self.session.span_help(span, &format!("did you mean `{}`?", path));
+                }
+                Some(len) if len == candidate.path.len() => {
+                    self.session.span_note(span, &format!("`{}` exists but is private", path));
+                }
+                Some(len) => {
+                    self.session.span_note(span,
+                        &format!("`{}` exists but is not accessible, because `{}` is private",
+                                 path, names_to_string(&candidate.path[..len])));
+                }
+            }
+        }
+    }
+
     fn resolve_expr(&mut self, expr: &Expr) {
         // First, record candidate traits for this expression if it could
         // result in the invocation of a method call.
@@ -3228,7 +3412,8 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                         format!("to call `{}::{}`", path_str, path_name)
                                 };
 
-                                if !msg.is_empty() {
+                                let suggested = !msg.is_empty();
+                                if suggested {
                                     msg = format!(". Did you mean {}?", msg)
                                 }
 
@@ -3236,6 +3421,12 @@ impl<'a, 'tcx> Resolver<'a, 'tcx> {
                                     expr.span,
                                     &format!("unresolved name `{}`{}",
                                              path_name, msg));
+
+                                if !suggested {
+                                    let current_module = self.current_module.clone();
+                                    self.report_import_candidates(expr.span, &current_module,
+                                                                  last_name, Some(ValueNS));
+                                }
                             }
                         }
                     }
@@ -3502,6 +3693,19 @@ fn path_names_to_string(path: &Path, depth: usize) -> String {
     names_to_string(&names[..])
 }
 
+/// Whether `ancestor` is `module` or one of the modules it is nested in.
+fn is_ancestor_or_self(ancestor: &Module, module: &Module) -> bool {
+    if ancestor as *const Module == module as *const Module {
+        return true;
+    }
+    match module.parent_link {
+        NoParentLink => false,
+        ModuleParentLink(ref parent, _) | BlockParentLink(ref parent, _) => {
+            is_ancestor_or_self(ancestor, &*parent.upgrade().unwrap())
+        }
+    }
+}
+
 /// A somewhat inefficient routine to obtain the name of a module.
 fn module_to_string(module: &Module) -> String {
     let mut names = Vec::new();
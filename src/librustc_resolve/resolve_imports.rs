@@ -277,6 +277,11 @@ impl<'a, 'b:'a, 'tcx:'b> ImportResolver<'a, 'b, 'tcx> {
                                           import_directive.subclass),
                                       help);
                     self.resolver.resolve_error(span, &msg[..]);
+
+                    // A glob import names no item of its own to suggest.
+                    if let SingleImport(_, source) = import_directive.subclass {
+                        self.resolver.report_import_candidates(span, &module, source, None);
+                    }
                 }
                 ResolveResult::Indeterminate => break, // Bail out. We'll come around next time.
                 ResolveResult::Success(()) => () // Good. Continue.
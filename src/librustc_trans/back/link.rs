@@ -14,7 +14,7 @@ use super::rpath;
 use super::rpath::RPathConfig;
 use super::svh::Svh;
 use session::config;
-use session::config::NoDebugInfo;
+use session::config::{NoDebugInfo, PrintRequest};
 use session::config::{OutputFilenames, Input, OutputTypeBitcode, OutputTypeExe, OutputTypeObject};
 use session::search_paths::PathKind;
 use session::Session;
@@ -28,7 +28,7 @@ use util::ppaux;
 use util::sha2::{Digest, Sha256};
 use rustc_back::tempdir::TempDir;
 
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::fs::{self, PathExt};
 use std::io::{self, Read, Write};
 use std::mem;
@@ -39,9 +39,11 @@ use flate;
 use serialize::hex::ToHex;
 use syntax::ast;
 use syntax::ast_map::{PathElem, PathElems, PathName};
+use syntax::attr;
 use syntax::attr::AttrMetaMethods;
 use syntax::codemap::Span;
 use syntax::parse::token;
+use syntax::ptr::P;
 
 // RLIB LLVM-BYTECODE OBJECT LAYOUT
 // Version 1
@@ -123,6 +125,12 @@ pub const RLIB_BYTECODE_OBJECT_V1_DATA_OFFSET: usize =
  *  - Suffix a mangled sym with ::STH@CVERS, so that it is unique in the
  *    name, non-name metadata, and type sense, and versioned in the way
  *    system linkers understand.
+ *
+ *  - Instantiations of generic items additionally get their type parameters
+ *    as a path element of their own, right before the hash, so that they can
+ *    be told apart once demangled. The demangler that prints backtraces is
+ *    in libstd/sys/common/backtrace.rs, and src/etc/rust-demangle.py is a
+ *    standalone filter for other tools' output.
  */
 
 pub fn find_crate_name(sess: Option<&Session>,
@@ -319,6 +327,38 @@ pub fn exported_name(path: PathElems, hash: &str) -> String {
     mangle(path, Some(hash))
 }
 
+// Deeply nested type parameters would make for symbols long enough to slow
+// down linkers and bloat symbol tables, so they're cut short after this many
+// bytes. The hash keeps the symbol unique regardless.
+const MAX_TYPE_PARAMS_LEN: usize = 128;
+
+/// The symbol of an instantiation of a generic item. Its type parameters are
+/// encoded as an extra `<A, B>` element after the item's path, so that the
+/// demangled symbol shows which instantiation it is, e.g.
+/// `foo::bar::<i32, collections::vec::Vec<u8>>::h0123456789abcdef`. Type
+/// parameters longer than `MAX_TYPE_PARAMS_LEN` end in an ellipsis instead.
+pub fn exported_name_with_type_params<'tcx>(tcx: &ty::ctxt<'tcx>,
+                                            path: PathElems,
+                                            tps: &[Ty<'tcx>],
+                                            hash: &str) -> String {
+    if tps.is_empty() {
+        return exported_name(path, hash);
+    }
+
+    let mut tps = tps.iter().map(|&t| ppaux::ty_to_string(tcx, t))
+                     .collect::<Vec<_>>().connect(", ");
+    if tps.len() > MAX_TYPE_PARAMS_LEN {
+        let mut end = MAX_TYPE_PARAMS_LEN;
+        while !tps.is_char_boundary(end) {
+            end -= 1;
+        }
+        tps.truncate(end);
+        tps.push('\u{2026}');
+    }
+    let tps = PathName(token::intern(&format!("<{}>", tps)));
+    mangle(path.chain(Some(tps).into_iter()), Some(hash))
+}
+
 pub fn mangle_exported_name<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, path: PathElems,
                                       t: Ty<'tcx>, id: ast::NodeId) -> String {
     let mut hash = get_symbol_hash(ccx, t);
@@ -365,6 +405,197 @@ pub fn get_cc_prog(sess: &Session) -> String {
     }
 }
 
+fn get_objcopy_prog(sess: &Session) -> String {
+    match sess.opts.cg.objcopy {
+        Some(ref objcopy) => objcopy.to_string(),
+        None => sess.target.target.options.objcopy.clone(),
+    }
+}
+
+/// The ways in which linkers expect their arguments to be spelled.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LinkerFlavor {
+    /// A C compiler driver such as `cc`, which hands `-Wl,` arguments on to the linker.
+    Gcc,
+    /// A GNU-style linker run directly, such as `ld.bfd`, `ld.gold` or `ld.lld`.
+    Ld,
+    /// LLVM's `lld` driver, told to behave like a GNU-style linker with `-flavor gnu`.
+    Lld,
+}
+
+impl LinkerFlavor {
+    pub fn from_str(s: &str) -> Option<LinkerFlavor> {
+        match s {
+            "gcc" => Some(LinkerFlavor::Gcc),
+            "ld" => Some(LinkerFlavor::Ld),
+            "lld" => Some(LinkerFlavor::Lld),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            LinkerFlavor::Gcc => "gcc",
+            LinkerFlavor::Ld => "ld",
+            LinkerFlavor::Lld => "lld",
+        }
+    }
+}
+
+pub fn linker_flavor(sess: &Session) -> LinkerFlavor {
+    let flavor = match sess.opts.cg.linker_flavor {
+        Some(ref flavor) => flavor,
+        None => &sess.target.target.options.linker_flavor,
+    };
+    match LinkerFlavor::from_str(flavor) {
+        Some(flavor) => flavor,
+        None => sess.fatal(&format!("unknown linker flavor: `{}`", flavor)),
+    }
+}
+
+// The command line of the linker, spelled for its flavor. Object files and
+// the arguments that C compiler drivers and linkers agree on, such as `-o`,
+// `-L` and `-l`, are passed with `arg`. Options of the linker itself are
+// passed with `link_arg`, which hides them behind `-Wl,` for a C compiler
+// driver, and anything with a spelling of its own for each flavor has a
+// method of its own.
+struct Linker {
+    cmd: Command,
+    flavor: LinkerFlavor,
+}
+
+impl Linker {
+    fn new(sess: &Session, prog: &str) -> Linker {
+        let flavor = linker_flavor(sess);
+        let mut cmd = Command::new(prog);
+        if flavor == LinkerFlavor::Lld {
+            cmd.arg("-flavor").arg("gnu");
+        }
+        Linker { cmd: cmd, flavor: flavor }
+    }
+
+    fn arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Linker {
+        self.cmd.arg(arg);
+        self
+    }
+
+    fn args<S: AsRef<OsStr>>(&mut self, args: &[S]) -> &mut Linker {
+        self.cmd.args(args);
+        self
+    }
+
+    // Passes one option of the linker, made of the given words, such as
+    // `-force_load <path>`.
+    fn link_args<S: AsRef<OsStr>>(&mut self, args: &[S]) -> &mut Linker {
+        match self.flavor {
+            LinkerFlavor::Gcc => {
+                let mut arg = OsString::from("-Wl");
+                for a in args {
+                    arg.push(",");
+                    arg.push(a);
+                }
+                self.cmd.arg(&arg);
+            }
+            LinkerFlavor::Ld | LinkerFlavor::Lld => {
+                self.cmd.args(args);
+            }
+        }
+        self
+    }
+
+    fn link_arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Linker {
+        self.link_args(&[arg])
+    }
+
+    // Passes an argument spelled for a C compiler driver, such as the
+    // `-Wl,-rpath,...` flags of the rpath module or the `pre_link_args` of a
+    // target. A linker run directly is given the options behind `-Wl,`, but
+    // not those of the driver itself: it takes the output format from the
+    // object files rather than from `-m32` or `-m64`, and links no C runtime
+    // or support library for `-nostdlib` or `-static-libgcc` to leave out.
+    // The `-f` options of the driver have no such counterpart, so dropping
+    // one is warned about.
+    fn cc_arg(&mut self, sess: &Session, arg: &str) -> &mut Linker {
+        if arg.starts_with("-Wl,") {
+            let args = arg[4..].split(',').collect::<Vec<_>>();
+            return self.link_args(&args[..]);
+        }
+        match (self.flavor, arg) {
+            (LinkerFlavor::Gcc, _) => self.arg(arg),
+            (_, "-m32") | (_, "-m64") | (_, "-nostdlib") |
+            (_, "-static-libgcc") | (_, "-shared-libgcc") => self,
+            (flavor, _) if arg.starts_with("-f") => {
+                sess.warn(&format!("ignoring `{}`, an option of C compiler drivers that \
+                                    the `{}` linker flavor doesn't take",
+                                   arg, flavor.name()));
+                self
+            }
+            (_, _) => self.arg(arg),
+        }
+    }
+
+    fn cc_args(&mut self, sess: &Session, args: &[String]) -> &mut Linker {
+        for arg in args {
+            self.cc_arg(sess, arg);
+        }
+        self
+    }
+
+    // Links every object of a static library, not only those that something
+    // refers to.
+    fn whole_archive(&mut self, is_like_osx: bool, path: &Path) -> &mut Linker {
+        if is_like_osx {
+            self.link_args(&[Path::new("-force_load"), path])
+        } else {
+            self.link_arg("--whole-archive").arg(path).link_arg("--no-whole-archive")
+        }
+    }
+
+    // A C compiler driver links the C runtime and its support libraries
+    // unless told not to, a linker run directly only links what it is given.
+    fn no_default_libraries(&mut self) -> &mut Linker {
+        if self.flavor == LinkerFlavor::Gcc {
+            self.cmd.arg("-nodefaultlibs");
+        }
+        self
+    }
+
+    fn export_dynamic(&mut self) -> &mut Linker {
+        match self.flavor {
+            LinkerFlavor::Gcc => self.arg("-rdynamic"),
+            LinkerFlavor::Ld | LinkerFlavor::Lld => self.arg("--export-dynamic"),
+        }
+    }
+
+    fn static_pie(&mut self) -> &mut Linker {
+        match self.flavor {
+            LinkerFlavor::Gcc => self.arg("-static-pie"),
+            LinkerFlavor::Ld | LinkerFlavor::Lld => {
+                self.args(&["-static", "-pie", "--no-dynamic-linker"])
+            }
+        }
+    }
+
+    fn shared(&mut self, is_like_osx: bool) -> &mut Linker {
+        match (self.flavor, is_like_osx) {
+            (LinkerFlavor::Gcc, true) => self.arg("-dynamiclib").link_arg("-dylib"),
+            (_, true) => self.arg("-dylib"),
+            (_, false) => self.arg("-shared"),
+        }
+    }
+}
+
+// Whether something a `#[link]` attribute asks for is linked into this
+// compilation's outputs, given the attribute's `cfg(...)` predicate, if any,
+// and the configuration of the compilation, which `link_binary` builds once.
+fn link_cfg_matches(sess: &Session, crate_cfg: &ast::CrateConfig,
+                    cfg: Option<&P<ast::MetaItem>>) -> bool {
+    match cfg {
+        Some(cfg) => attr::cfg_matches(sess.diagnostic(), crate_cfg, cfg),
+        None => true,
+    }
+}
+
 pub fn remove(sess: &Session, path: &Path) {
     match fs::remove_file(path) {
         Ok(..) => {}
@@ -383,13 +614,14 @@ pub fn link_binary(sess: &Session,
                    outputs: &OutputFilenames,
                    crate_name: &str) -> Vec<PathBuf> {
     let mut out_filenames = Vec::new();
+    let crate_cfg = config::build_configuration(sess);
     for &crate_type in &*sess.crate_types.borrow() {
         if invalid_output_for_target(sess, crate_type) {
             sess.bug(&format!("invalid output type `{:?}` for target os `{}`",
                              crate_type, sess.opts.target_triple));
         }
         let out_file = link_binary_output(sess, trans, crate_type, outputs,
-                                          crate_name);
+                                          crate_name, &crate_cfg);
         out_filenames.push(out_file);
     }
 
@@ -429,6 +661,7 @@ pub fn invalid_output_for_target(sess: &Session,
     match (sess.target.target.options.dynamic_linking,
            sess.target.target.options.executables, crate_type) {
         (false, _, config::CrateTypeDylib) => true,
+        (false, _, config::CrateTypeCdylib) => true,
         (_, false, config::CrateTypeExecutable) => true,
         _ => false
     }
@@ -450,7 +683,7 @@ pub fn filename_for_input(sess: &Session,
         config::CrateTypeRlib => {
             out_filename.with_file_name(&format!("lib{}.rlib", libname))
         }
-        config::CrateTypeDylib => {
+        config::CrateTypeDylib | config::CrateTypeCdylib => {
             let (prefix, suffix) = (&sess.target.target.options.dll_prefix,
                                     &sess.target.target.options.dll_suffix);
             out_filename.with_file_name(&format!("{}{}{}",
@@ -476,7 +709,8 @@ fn link_binary_output(sess: &Session,
                       trans: &CrateTranslation,
                       crate_type: config::CrateType,
                       outputs: &OutputFilenames,
-                      crate_name: &str) -> PathBuf {
+                      crate_name: &str,
+                      crate_cfg: &ast::CrateConfig) -> PathBuf {
     let obj_filename = outputs.temp_path(OutputTypeObject);
     let out_filename = match outputs.single_output_file {
         Some(ref file) => file.clone(),
@@ -505,16 +739,15 @@ fn link_binary_output(sess: &Session,
 
     match crate_type {
         config::CrateTypeRlib => {
-            link_rlib(sess, Some(trans), &obj_filename, &out_filename).build();
+            link_rlib(sess, Some(trans), crate_cfg, &obj_filename, &out_filename).build();
         }
         config::CrateTypeStaticlib => {
-            link_staticlib(sess, &obj_filename, &out_filename);
-        }
-        config::CrateTypeExecutable => {
-            link_natively(sess, trans, false, &obj_filename, &out_filename);
+            link_staticlib(sess, crate_cfg, &obj_filename, &out_filename);
         }
-        config::CrateTypeDylib => {
-            link_natively(sess, trans, true, &obj_filename, &out_filename);
+        config::CrateTypeExecutable |
+        config::CrateTypeDylib |
+        config::CrateTypeCdylib => {
+            link_natively(sess, trans, crate_cfg, crate_type, &obj_filename, &out_filename);
         }
     }
 
@@ -538,6 +771,7 @@ fn archive_search_paths(sess: &Session) -> Vec<PathBuf> {
 // native libraries and inserting all of the contents into this archive.
 fn link_rlib<'a>(sess: &'a Session,
                  trans: Option<&CrateTranslation>, // None == no metadata/bytecode
+                 crate_cfg: &ast::CrateConfig,
                  obj_filename: &Path,
                  out_filename: &Path) -> ArchiveBuilder<'a> {
     let handler = &sess.diagnostic().handler;
@@ -552,10 +786,13 @@ fn link_rlib<'a>(sess: &'a Session,
     let mut ab = ArchiveBuilder::create(config);
     ab.add_file(obj_filename).unwrap();
 
-    for &(ref l, kind) in &*sess.cstore.get_used_libraries().borrow() {
-        match kind {
+    for lib in &*sess.cstore.get_used_libraries().borrow() {
+        if !link_cfg_matches(sess, crate_cfg, lib.cfg.as_ref()) {
+            continue
+        }
+        match lib.kind {
             cstore::NativeStatic => {
-                ab.add_native_library(&l[..]).unwrap();
+                ab.add_native_library(&lib.name[..]).unwrap();
             }
             cstore::NativeFramework | cstore::NativeUnknown => {}
         }
@@ -731,13 +968,14 @@ fn write_rlib_bytecode_object_v1(writer: &mut Write,
 // There's no need to include metadata in a static archive, so ensure to not
 // link in the metadata object file (and also don't prepare the archive with a
 // metadata file).
-fn link_staticlib(sess: &Session, obj_filename: &Path, out_filename: &Path) {
-    let ab = link_rlib(sess, None, obj_filename, out_filename);
+fn link_staticlib(sess: &Session, crate_cfg: &ast::CrateConfig, obj_filename: &Path,
+                  out_filename: &Path) {
+    let ab = link_rlib(sess, None, crate_cfg, obj_filename, out_filename);
     let mut ab = match sess.target.target.options.is_like_osx {
         true => ab.build().extend(),
         false => ab,
     };
-    if sess.target.target.options.morestack {
+    if sess.target.target.options.morestack || sess.target.target.options.stack_probes {
         ab.add_native_library("morestack").unwrap();
     }
     if !sess.target.target.options.no_compiler_rt {
@@ -758,8 +996,12 @@ fn link_staticlib(sess: &Session, obj_filename: &Path, out_filename: &Path) {
         };
         ab.add_rlib(&p, &name[..], sess.lto()).unwrap();
 
+        // Static native libraries were bundled into the rlib, and so are
+        // already part of the archive.
         let native_libs = csearch::get_native_libraries(&sess.cstore, cnum);
-        all_native_libs.extend(native_libs.into_iter());
+        all_native_libs.extend(native_libs.into_iter().filter(|lib| {
+            lib.kind != cstore::NativeStatic && link_cfg_matches(sess, crate_cfg, lib.cfg.as_ref())
+        }));
     }
 
     ab.update_symbols();
@@ -772,53 +1014,107 @@ fn link_staticlib(sess: &Session, obj_filename: &Path, out_filename: &Path) {
                   and so may need to be preserved");
     }
 
-    for &(kind, ref lib) in &all_native_libs {
-        let name = match kind {
+    for lib in &all_native_libs {
+        let name = match lib.kind {
             cstore::NativeStatic => "static library",
             cstore::NativeUnknown => "library",
             cstore::NativeFramework => "framework",
         };
-        sess.note(&format!("{}: {}", name, *lib));
+        sess.note(&format!("{}: {}", name, lib.name));
+    }
+
+    if sess.opts.prints.contains(&PrintRequest::NativeStaticLibs) {
+        print_native_static_libs(&all_native_libs);
     }
 }
 
+// Prints the linker arguments that link the native libraries a static library
+// depends on, for `--print native-static-libs`. Static native libraries are
+// bundled into the static library itself, so they are left out.
+fn print_native_static_libs(all_native_libs: &[cstore::NativeLibrary]) {
+    let args = all_native_libs.iter().filter_map(|lib| {
+        match lib.kind {
+            cstore::NativeStatic => None,
+            cstore::NativeUnknown => Some(format!("-l{}", lib.name)),
+            cstore::NativeFramework => Some(format!("-framework {}", lib.name)),
+        }
+    }).collect::<Vec<_>>();
+    println!("{}", args.connect(" "));
+}
+
 // Create a dynamic library or executable
 //
 // This will invoke the system linker/cc to create the resulting file. This
 // links to all upstream files as well.
-fn link_natively(sess: &Session, trans: &CrateTranslation, dylib: bool,
-                 obj_filename: &Path, out_filename: &Path) {
+fn link_natively(sess: &Session, trans: &CrateTranslation, crate_cfg: &ast::CrateConfig,
+                 crate_type: config::CrateType, obj_filename: &Path, out_filename: &Path) {
     let tmpdir = TempDir::new("rustc").ok().expect("needs a temp dir");
 
     // The invocations of cc share some flags across platforms
     let pname = get_cc_prog(sess);
-    let mut cmd = Command::new(&pname[..]);
+    let mut cmd = Linker::new(sess, &pname[..]);
+
+    // The C runtime objects of targets that ship their own, which only
+    // executables are linked with.
+    let t = &sess.target.target.options;
+    let lib_path = sess.target_filesearch(PathKind::All).get_lib_path();
+    let (pre_link_objects, post_link_objects) = if crate_type != config::CrateTypeExecutable {
+        (&[][..], &[][..])
+    } else if t.static_executables && position_independent_executable(sess, crate_type) {
+        (&t.pre_link_objects_pie[..], &t.post_link_objects[..])
+    } else {
+        (&t.pre_link_objects[..], &t.post_link_objects[..])
+    };
 
-    cmd.args(&sess.target.target.options.pre_link_args);
-    link_args(&mut cmd, sess, dylib, tmpdir.path(),
+    // Only a C compiler driver knows how to start an executable with the C
+    // runtime of the system, and which libraries it needs. A linker run
+    // directly can only link the executables of targets that ship their own.
+    if crate_type == config::CrateTypeExecutable && cmd.flavor != LinkerFlavor::Gcc &&
+       pre_link_objects.is_empty() {
+        sess.err(&format!("the `{}` linker flavor can't link executables for target `{}`, \
+                           which has no `pre-link-objects` to start them with",
+                          cmd.flavor.name(), sess.opts.target_triple));
+        sess.help("link executables with the `gcc` linker flavor, whose C compiler driver \
+                   adds the C runtime startup objects and libraries");
+        sess.abort_if_errors();
+    }
+
+    cmd.cc_args(sess, &t.pre_link_args);
+    for obj in pre_link_objects {
+        cmd.arg(&lib_path.join(obj));
+    }
+    link_args(&mut cmd, sess, crate_cfg, crate_type, tmpdir.path(),
               trans, obj_filename, out_filename);
-    cmd.args(&sess.target.target.options.post_link_args);
+    cmd.cc_args(sess, &t.post_link_args);
+    if sess.opts.cg.coverage || sess.opts.cg.profile_generate.is_some() {
+        // Writes out the counts when the program exits.
+        let arch = if sess.target.target.arch == "x86" { "i386" } else { "x86_64" };
+        cmd.arg(&format!("-lclang_rt.profile-{}", arch));
+    }
     if !sess.target.target.options.no_compiler_rt {
         cmd.arg("-lcompiler-rt");
     }
+    for obj in post_link_objects {
+        cmd.arg(&lib_path.join(obj));
+    }
 
     if sess.opts.debugging_opts.print_link_args {
-        println!("{:?}", &cmd);
+        println!("{:?}", &cmd.cmd);
     }
 
     // May have not found libraries in the right formats.
     sess.abort_if_errors();
 
     // Invoke the system linker
-    debug!("{:?}", &cmd);
-    let prog = time(sess.time_passes(), "running linker", (), |()| cmd.output());
+    debug!("{:?}", &cmd.cmd);
+    let prog = time(sess.time_passes(), "running linker", (), |()| cmd.cmd.output());
     match prog {
         Ok(prog) => {
             if !prog.status.success() {
                 sess.err(&format!("linking with `{}` failed: {}",
                                  pname,
                                  prog.status));
-                sess.note(&format!("{:?}", &cmd));
+                sess.note(&format!("{:?}", &cmd.cmd));
                 let mut output = prog.stderr.clone();
                 output.push_all(&prog.stdout);
                 sess.note(str::from_utf8(&output[..]).unwrap());
@@ -847,15 +1143,68 @@ fn link_natively(sess: &Session, trans: &CrateTranslation, dylib: bool,
             }
         }
     }
+
+    // Everywhere else the debug info ends up in the output itself, so it has
+    // to be moved out explicitly. OSX already keeps it out of the output and
+    // MSVC-like targets write it to a PDB.
+    if sess.opts.cg.split_debuginfo && sess.opts.debuginfo != NoDebugInfo &&
+       !sess.target.target.options.is_like_osx &&
+       !sess.target.target.options.is_like_windows {
+        split_debuginfo(sess, out_filename);
+    }
 }
 
-fn link_args(cmd: &mut Command,
+// Moves the debug info of a linked output into `<output>.debug` and strips it
+// from the output, which gets a `.gnu_debuglink` section naming the `.debug`
+// file instead. Debuggers follow the debuglink to find the debug info, as long
+// as the `.debug` file is kept next to the output (or in the system's global
+// debug directory): whatever installs the output has to install the `.debug`
+// file with it, as `make install` does for rustc's own outputs.
+fn split_debuginfo(sess: &Session, out_filename: &Path) {
+    let mut debug_filename = out_filename.as_os_str().to_os_string();
+    debug_filename.push(".debug");
+    let debug_filename = PathBuf::from(debug_filename);
+
+    let mut debuglink = OsString::from("--add-gnu-debuglink=");
+    debuglink.push(&debug_filename);
+
+    let objcopy = get_objcopy_prog(sess);
+    let mut extract = Command::new(&objcopy);
+    extract.arg("--only-keep-debug").arg(out_filename).arg(&debug_filename);
+    let mut strip = Command::new(&objcopy);
+    strip.arg("--strip-debug").arg(&debuglink).arg(out_filename);
+
+    for cmd in [extract, strip].iter_mut() {
+        debug!("{:?}", cmd);
+        match cmd.output() {
+            Ok(ref prog) if !prog.status.success() => {
+                sess.err(&format!("splitting debug info with `{}` failed: {}",
+                                  objcopy, prog.status));
+                sess.note(&format!("{:?}", cmd));
+                sess.note(str::from_utf8(&prog.stderr[..]).unwrap());
+                sess.abort_if_errors();
+            }
+            Ok(..) => {}
+            Err(e) => {
+                sess.err(&format!("failed to run `{}` to split debug info: {}", objcopy, e));
+                sess.help("another objcopy can be chosen with `-C objcopy` or the \
+                           `objcopy` field of the target specification");
+                sess.abort_if_errors();
+            }
+        }
+    }
+}
+
+fn link_args(cmd: &mut Linker,
              sess: &Session,
-             dylib: bool,
+             crate_cfg: &ast::CrateConfig,
+             crate_type: config::CrateType,
              tmpdir: &Path,
              trans: &CrateTranslation,
              obj_filename: &Path,
              out_filename: &Path) {
+    let dylib = crate_type == config::CrateTypeDylib ||
+                crate_type == config::CrateTypeCdylib;
 
     // The default library location, we need this to find the runtime.
     // The location of crates will be determined as needed.
@@ -885,22 +1234,41 @@ fn link_args(cmd: &mut Command,
     // all contents of this library. This way we're guaranteed that the linker
     // will include the __morestack symbol 100% of the time, always resolving
     // references to it even if the object above didn't use it.
-    if t.options.morestack {
+    //
+    // The same library provides the __rust_probestack function of targets
+    // using stack probes.
+    if t.options.morestack || t.options.stack_probes {
         if t.options.is_like_osx {
             let morestack = lib_path.join("libmorestack.a");
-
-            let mut v = OsString::from("-Wl,-force_load,");
-            v.push(&morestack);
-            cmd.arg(&v);
+            cmd.link_args(&[Path::new("-force_load"), &*morestack]);
         } else {
-            cmd.args(&["-Wl,--whole-archive", "-lmorestack", "-Wl,--no-whole-archive"]);
+            cmd.link_arg("--whole-archive").arg("-lmorestack").link_arg("--no-whole-archive");
+        }
+    }
+
+    // The sanitizer runtimes replace malloc and friends and must see every
+    // symbol of the program, so they are linked whole, and only into
+    // executables: a dynamic library relies on the executable loading it to
+    // provide the runtime.
+    if let Some(sanitizer) = sess.opts.debugging_opts.sanitizer {
+        if !dylib {
+            let runtime = format!("clang_rt.{}-x86_64", sanitizer.runtime());
+            if !lib_path.join(&format!("lib{}.a", runtime)).exists() {
+                sess.err(&format!("the {} sanitizer runtime was not found in {}",
+                                  sanitizer.desc(), lib_path.display()));
+            }
+            cmd.link_arg("--whole-archive")
+               .arg(&format!("-l{}", runtime))
+               .link_arg("--no-whole-archive");
+            cmd.export_dynamic();
         }
     }
 
     // When linking a dynamic library, we put the metadata into a section of the
     // executable. This metadata is in a separate object file from the main
-    // object file, so we link that in here.
-    if dylib {
+    // object file, so we link that in here. A cdylib is not meant to be linked
+    // against by Rust crates, so it has none.
+    if crate_type == config::CrateTypeDylib {
         cmd.arg(&obj_filename.with_extension("metadata.o"));
     }
 
@@ -918,31 +1286,29 @@ fn link_args(cmd: &mut Command,
         //
         // -dead_strip can't be part of the pre_link_args because it's also used for partial
         // linking when using multiple codegen units (-r). So we insert it here.
-        cmd.arg("-Wl,-dead_strip");
+        cmd.link_arg("-dead_strip");
     }
 
     // If we're building a dylib, we don't use --gc-sections because LLVM has
     // already done the best it can do, and we also don't want to eliminate the
     // metadata. If we're building an executable, however, --gc-sections drops
-    // the size of hello world from 1.8MB to 597K, a 67% reduction.
-    if !dylib && !t.options.is_like_osx {
-        cmd.arg("-Wl,--gc-sections");
+    // the size of hello world from 1.8MB to 597K, a 67% reduction. A cdylib
+    // has no metadata, and only exports a handful of symbols, so it benefits
+    // just as much.
+    if crate_type != config::CrateTypeDylib && !t.options.is_like_osx {
+        cmd.link_arg("--gc-sections");
     }
 
     let used_link_args = sess.cstore.get_used_link_args().borrow();
 
-    if t.options.position_independent_executables {
-        let empty_vec = Vec::new();
-        let empty_str = String::new();
-        let args = sess.opts.cg.link_args.as_ref().unwrap_or(&empty_vec);
-        let mut args = args.iter().chain(used_link_args.iter());
-        if !dylib
-            && (t.options.relocation_model == "pic"
-                || *sess.opts.cg.relocation_model.as_ref()
-                   .unwrap_or(&empty_str) == "pic")
-            && !args.any(|x| *x == "-static") {
+    if position_independent_executable(sess, crate_type) {
+        if t.options.static_executables {
+            cmd.static_pie();
+        } else {
             cmd.arg("-pie");
         }
+    } else if !dylib && t.options.static_executables {
+        cmd.arg("-static");
     }
 
     if t.options.linker_is_gnu {
@@ -950,7 +1316,7 @@ fn link_args(cmd: &mut Command,
         // numeric argument, but other linkers do.
         if sess.opts.optimize == config::Default ||
            sess.opts.optimize == config::Aggressive {
-            cmd.arg("-Wl,-O1");
+            cmd.link_arg("-O1");
         }
     }
 
@@ -960,7 +1326,7 @@ fn link_args(cmd: &mut Command,
     // in some large number of libraries and I couldn't quite figure out which
     // subset we wanted.
     if !t.options.is_like_windows {
-        cmd.arg("-nodefaultlibs");
+        cmd.no_default_libraries();
     }
 
     // Mark all dynamic libraries and executables as compatible with ASLR
@@ -1002,27 +1368,28 @@ fn link_args(cmd: &mut Command,
     // this kind of behavior is pretty platform specific and generally not
     // recommended anyway, so I don't think we're shooting ourself in the foot
     // much with that.
-    add_upstream_rust_crates(cmd, sess, dylib, tmpdir, trans);
-    add_local_native_libraries(cmd, sess);
-    add_upstream_native_libraries(cmd, sess);
+    add_upstream_rust_crates(cmd, sess, crate_cfg, crate_type, tmpdir, trans);
+    add_local_native_libraries(cmd, sess, crate_cfg);
+    add_upstream_native_libraries(cmd, sess, crate_cfg);
 
     // # Telling the linker what we're doing
 
     if dylib {
-        // On mac we need to tell the linker to let this library be rpathed
-        if sess.target.target.options.is_like_osx {
-            cmd.args(&["-dynamiclib", "-Wl,-dylib"]);
+        let is_like_osx = sess.target.target.options.is_like_osx;
+        cmd.shared(is_like_osx);
 
-            if sess.opts.cg.rpath {
-                let mut v = OsString::from("-Wl,-install_name,@rpath/");
-                v.push(out_filename.file_name().unwrap());
-                cmd.arg(&v);
-            }
-        } else {
-            cmd.arg("-shared");
+        // On mac we need to tell the linker to let this library be rpathed
+        if is_like_osx && sess.opts.cg.rpath {
+            let mut v = OsString::from("@rpath/");
+            v.push(out_filename.file_name().unwrap());
+            cmd.link_args(&[OsString::from("-install_name"), v]);
         }
     }
 
+    if crate_type == config::CrateTypeCdylib {
+        export_symbols(cmd, sess, tmpdir, trans);
+    }
+
     // FIXME (#2397): At some point we want to rpath our guesses as to
     // where extern libraries might live, based on the
     // addl_lib_search_paths
@@ -1045,7 +1412,24 @@ fn link_args(cmd: &mut Command,
             get_install_prefix_lib_path: &mut get_install_prefix_lib_path,
             realpath: &mut ::util::fs::realpath
         };
-        cmd.args(&rpath::get_rpath_flags(&mut rpath_config));
+        cmd.cc_args(sess, &rpath::get_rpath_flags(&mut rpath_config));
+    }
+
+    // The arguments of #[link(arg = "...")] attributes are options of the
+    // linker itself, so they are spelled for its flavor. Those of an upstream
+    // crate are needed wherever the crate is linked statically, while a
+    // dynamic library was already linked with the arguments of its own crates.
+    let mut linker_args = sess.cstore.get_used_linker_args().borrow().clone();
+    let formats = trans.crate_formats.get(&crate_type).unwrap();
+    for (cnum, _) in sess.cstore.get_used_crates(cstore::RequireStatic) {
+        if let Some(cstore::RequireStatic) = formats[cnum as usize - 1] {
+            linker_args.extend(csearch::get_linker_args(&sess.cstore, cnum).into_iter());
+        }
+    }
+    for &(ref arg, ref cfg) in &linker_args {
+        if link_cfg_matches(sess, crate_cfg, cfg.as_ref()) {
+            cmd.link_arg(arg);
+        }
     }
 
     // Finally add all the linker arguments provided on the command line along
@@ -1055,6 +1439,70 @@ fn link_args(cmd: &mut Command,
     cmd.args(&used_link_args[..]);
 }
 
+// Whether an executable is linked position independent, so that it can be
+// loaded at a random address. This is the case on targets that support it
+// unless the code isn't position independent, or `-static` was asked for.
+fn position_independent_executable(sess: &Session, crate_type: config::CrateType) -> bool {
+    let t = &sess.target.target;
+    if crate_type != config::CrateTypeExecutable || !t.options.position_independent_executables {
+        return false;
+    }
+    let empty_vec = Vec::new();
+    let empty_str = String::new();
+    let used_link_args = sess.cstore.get_used_link_args().borrow();
+    let args = sess.opts.cg.link_args.as_ref().unwrap_or(&empty_vec);
+    let mut args = args.iter().chain(used_link_args.iter());
+    (t.options.relocation_model == "pic"
+        || *sess.opts.cg.relocation_model.as_ref().unwrap_or(&empty_str) == "pic")
+        && !args.any(|x| *x == "-static")
+}
+
+// A cdylib is a shared library for C and other languages to load, so only the
+// `#[no_mangle]` items of the crate are exported from it. Everything else,
+// including the Rust crates linked into it, stays private to it.
+fn export_symbols(cmd: &mut Linker, sess: &Session, tmpdir: &Path,
+                  trans: &CrateTranslation) {
+    let t = &sess.target.target.options;
+    let (path, contents) = if t.is_like_osx {
+        let mut contents = String::new();
+        for sym in &trans.exported_symbols {
+            contents.push_str(&format!("_{}\n", sym));
+        }
+        (tmpdir.join("list"), contents)
+    } else if t.is_like_windows {
+        let mut contents = "EXPORTS\n".to_string();
+        for sym in &trans.exported_symbols {
+            contents.push_str(&format!("    {}\n", sym));
+        }
+        (tmpdir.join("lib.def"), contents)
+    } else {
+        let mut contents = "{\n".to_string();
+        if !trans.exported_symbols.is_empty() {
+            contents.push_str("  global:\n");
+            for sym in &trans.exported_symbols {
+                contents.push_str(&format!("    {};\n", sym));
+            }
+        }
+        contents.push_str("  local:\n    *;\n};\n");
+        (tmpdir.join("list"), contents)
+    };
+
+    match fs::File::create(&path).and_then(|mut f| f.write_all(contents.as_bytes())) {
+        Ok(..) => {}
+        Err(e) => sess.fatal(&format!("failed to write {}: {}", path.display(), e)),
+    }
+
+    if t.is_like_osx {
+        cmd.link_args(&[Path::new("-exported_symbols_list"), &*path]);
+    } else if t.is_like_windows {
+        cmd.arg(&path);
+    } else {
+        let mut arg = OsString::from("--version-script=");
+        arg.push(&path);
+        cmd.link_arg(&arg);
+    }
+}
+
 // # Native library linking
 //
 // User-supplied library search paths (-L on the command line). These are
@@ -1066,7 +1514,7 @@ fn link_args(cmd: &mut Command,
 // Also note that the native libraries linked here are only the ones located
 // in the current crate. Upstream crates with native library dependencies
 // may have their native library pulled in above.
-fn add_local_native_libraries(cmd: &mut Command, sess: &Session) {
+fn add_local_native_libraries(cmd: &mut Linker, sess: &Session, crate_cfg: &ast::CrateConfig) {
     sess.target_filesearch(PathKind::All).for_each_lib_search_path(|path, k| {
         match k {
             PathKind::Framework => { cmd.arg("-F").arg(path); }
@@ -1083,13 +1531,12 @@ fn add_local_native_libraries(cmd: &mut Command, sess: &Session) {
 
     let libs = sess.cstore.get_used_libraries();
     let libs = libs.borrow();
+    let libs = libs.iter().filter(|lib| {
+        link_cfg_matches(sess, crate_cfg, lib.cfg.as_ref())
+    }).collect::<Vec<_>>();
 
-    let staticlibs = libs.iter().filter_map(|&(ref l, kind)| {
-        if kind == cstore::NativeStatic {Some(l)} else {None}
-    });
-    let others = libs.iter().filter(|&&(_, kind)| {
-        kind != cstore::NativeStatic
-    });
+    let staticlibs = libs.iter().filter(|lib| lib.kind == cstore::NativeStatic);
+    let others = libs.iter().filter(|lib| lib.kind != cstore::NativeStatic);
 
     // Platforms that take hints generally also support the --whole-archive
     // flag. We need to pass this flag when linking static native libraries to
@@ -1099,37 +1546,39 @@ fn add_local_native_libraries(cmd: &mut Command, sess: &Session) {
     // away any unused objects in the archive if we don't otherwise explicitly
     // reference them. This can occur for libraries which are just providing
     // bindings, libraries with generic functions, etc.
+    //
+    // This is also why `whole_archive` makes no difference here. It is for
+    // the crates downstream of an rlib, which link the static libraries
+    // bundled into it as part of the rlib.
     if takes_hints {
-        cmd.arg("-Wl,--whole-archive").arg("-Wl,-Bstatic");
+        cmd.link_arg("--whole-archive").link_arg("-Bstatic");
     }
     let search_path = archive_search_paths(sess);
-    for l in staticlibs {
+    for lib in staticlibs {
         if takes_hints {
-            cmd.arg(&format!("-l{}", l));
+            cmd.arg(&format!("-l{}", lib.name));
         } else {
             // -force_load is the OSX equivalent of --whole-archive, but it
             // involves passing the full path to the library to link.
-            let lib = archive::find_library(&l[..],
+            let lib = archive::find_library(&lib.name[..],
                                             &sess.target.target.options.staticlib_prefix,
                                             &sess.target.target.options.staticlib_suffix,
                                             &search_path[..],
                                             &sess.diagnostic().handler);
-            let mut v = OsString::from("-Wl,-force_load,");
-            v.push(&lib);
-            cmd.arg(&v);
+            cmd.whole_archive(true, &lib);
         }
     }
     if takes_hints {
-        cmd.arg("-Wl,--no-whole-archive").arg("-Wl,-Bdynamic");
+        cmd.link_arg("--no-whole-archive").link_arg("-Bdynamic");
     }
 
-    for &(ref l, kind) in others {
-        match kind {
+    for lib in others {
+        match lib.kind {
             cstore::NativeUnknown => {
-                cmd.arg(&format!("-l{}", l));
+                cmd.arg(&format!("-l{}", lib.name));
             }
             cstore::NativeFramework => {
-                cmd.arg("-framework").arg(&l[..]);
+                cmd.arg("-framework").arg(&lib.name[..]);
             }
             cstore::NativeStatic => unreachable!(),
         }
@@ -1141,8 +1590,8 @@ fn add_local_native_libraries(cmd: &mut Command, sess: &Session) {
 // Rust crates are not considered at all when creating an rlib output. All
 // dependencies will be linked when producing the final output (instead of
 // the intermediate rlib version)
-fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
-                            dylib: bool, tmpdir: &Path,
+fn add_upstream_rust_crates(cmd: &mut Linker, sess: &Session, crate_cfg: &ast::CrateConfig,
+                            crate_type: config::CrateType, tmpdir: &Path,
                             trans: &CrateTranslation) {
     // All of the heavy lifting has previously been accomplished by the
     // dependency_format module of the compiler. This is just crawling the
@@ -1152,11 +1601,7 @@ fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
     // will slurp up the object files inside), and linking to a dynamic library
     // involves just passing the right -l flag.
 
-    let data = if dylib {
-        trans.crate_formats.get(&config::CrateTypeDylib).unwrap()
-    } else {
-        trans.crate_formats.get(&config::CrateTypeExecutable).unwrap()
-    };
+    let data = trans.crate_formats.get(&crate_type).unwrap();
 
     // Invoke get_used_crates to ensure that we get a topological sorting of
     // crates.
@@ -1176,7 +1621,7 @@ fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
                 add_dynamic_crate(cmd, sess, &src.dylib.unwrap().0)
             }
             cstore::RequireStatic => {
-                add_static_crate(cmd, sess, tmpdir, &src.rlib.unwrap().0)
+                add_static_crate(cmd, sess, crate_cfg, tmpdir, cnum, &src.rlib.unwrap().0)
             }
         }
 
@@ -1192,8 +1637,8 @@ fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
     }
 
     // Adds the static "rlib" versions of all crates to the command line.
-    fn add_static_crate(cmd: &mut Command, sess: &Session, tmpdir: &Path,
-                        cratepath: &Path) {
+    fn add_static_crate(cmd: &mut Linker, sess: &Session, crate_cfg: &ast::CrateConfig,
+                        tmpdir: &Path, cnum: ast::CrateNum, cratepath: &Path) {
         // When performing LTO on an executable output, all of the
         // bytecode from the upstream libraries has already been
         // included in our object file output. We need to modify all of
@@ -1206,9 +1651,20 @@ fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
         // so if an archive no longer has any object files in it after
         // we remove `lib.o`, then don't link against it at all.
         //
+        // The static native libraries of a crate are bundled into its rlib,
+        // so if one of them that is linked into this output was given
+        // `whole_archive` the whole rlib is linked. The linker then loads
+        // every member of the archive, so the metadata and bytecode, which
+        // aren't object files, are removed.
+        //
         // If we're not doing LTO, then our job is simply to just link
         // against the archive.
-        if sess.lto() {
+        let whole_archive = csearch::get_native_libraries(&sess.cstore, cnum).iter().any(|lib| {
+            lib.kind == cstore::NativeStatic && lib.whole_archive &&
+                link_cfg_matches(sess, crate_cfg, lib.cfg.as_ref())
+        });
+        let is_like_osx = sess.target.target.options.is_like_osx;
+        if sess.lto() || whole_archive {
             let name = cratepath.file_name().unwrap().to_str().unwrap();
             let name = &name[3..name.len() - 5]; // chop off lib/.rlib
             time(sess.time_passes(),
@@ -1251,9 +1707,23 @@ fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
                     maybe_ar_prog: sess.opts.cg.ar.clone()
                 };
                 let mut archive = Archive::open(config);
-                archive.remove_file(&format!("{}.o", name));
+                if sess.lto() {
+                    archive.remove_file(&format!("{}.o", name));
+                }
+                if whole_archive {
+                    for file in archive.files() {
+                        if file == METADATA_FILENAME || file.ends_with(".bytecode.deflate") {
+                            archive.remove_file(&file);
+                        }
+                    }
+                }
                 let files = archive.files();
-                if files.iter().any(|s| s.ends_with(".o")) {
+                if !files.iter().any(|s| s.ends_with(".o")) {
+                    return
+                }
+                if whole_archive {
+                    cmd.whole_archive(is_like_osx, &dst);
+                } else {
                     cmd.arg(&dst);
                 }
             });
@@ -1263,7 +1733,7 @@ fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
     }
 
     // Same thing as above, but for dynamic crates instead of static crates.
-    fn add_dynamic_crate(cmd: &mut Command, sess: &Session, cratepath: &Path) {
+    fn add_dynamic_crate(cmd: &mut Linker, sess: &Session, cratepath: &Path) {
         // If we're performing LTO, then it should have been previously required
         // that all upstream rust dependencies were available in an rlib format.
         assert!(!sess.lto());
@@ -1296,7 +1766,7 @@ fn add_upstream_rust_crates(cmd: &mut Command, sess: &Session,
 // generic function calls a native function, then the generic function must
 // be instantiated in the target crate, meaning that the native symbol must
 // also be resolved in the target crate.
-fn add_upstream_native_libraries(cmd: &mut Command, sess: &Session) {
+fn add_upstream_native_libraries(cmd: &mut Linker, sess: &Session, crate_cfg: &ast::CrateConfig) {
     // Be sure to use a topological sorting of crates because there may be
     // interdependencies between native libraries. When passing -nodefaultlibs,
     // for example, almost all native libraries depend on libc, so we have to
@@ -1309,18 +1779,17 @@ fn add_upstream_native_libraries(cmd: &mut Command, sess: &Session) {
     let crates = sess.cstore.get_used_crates(cstore::RequireStatic);
     for (cnum, _) in crates {
         let libs = csearch::get_native_libraries(&sess.cstore, cnum);
-        for &(kind, ref lib) in &libs {
-            match kind {
+        for lib in libs.iter().filter(|lib| link_cfg_matches(sess, crate_cfg, lib.cfg.as_ref())) {
+            match lib.kind {
                 cstore::NativeUnknown => {
-                    cmd.arg(&format!("-l{}", *lib));
+                    cmd.arg(&format!("-l{}", lib.name));
                 }
                 cstore::NativeFramework => {
                     cmd.arg("-framework");
-                    cmd.arg(&lib[..]);
-                }
-                cstore::NativeStatic => {
-                    sess.bug("statics shouldn't be propagated");
+                    cmd.arg(&lib.name[..]);
                 }
+                // Bundled into the rlib, and linked with it.
+                cstore::NativeStatic => {}
             }
         }
     }
@@ -13,15 +13,19 @@ use super::write;
 use rustc::session::{self, config};
 use llvm;
 use llvm::archive_ro::ArchiveRO;
-use llvm::{ModuleRef, TargetMachineRef, True, False};
+use llvm::{ModuleRef, TargetMachineRef, ValueRef, True, False};
 use rustc::metadata::cstore;
 use rustc::util::common::time;
+use syntax::diagnostic::Handler;
 
 use libc;
 use flate;
 
-use std::ffi::CString;
+use std::cmp;
+use std::collections::{HashMap, HashSet};
+use std::ffi::{CStr, CString};
 use std::mem;
+use std::slice;
 #[allow(deprecated)]
 use std::num::Int;
 
@@ -29,29 +33,244 @@ pub fn run(sess: &session::Session, llmod: ModuleRef,
            tm: TargetMachineRef, reachable: &[String]) {
     if sess.opts.cg.prefer_dynamic {
         sess.err("cannot prefer dynamic linking when performing LTO");
-        sess.note("only 'staticlib' and 'bin' outputs are supported with LTO");
+        sess.note("only 'staticlib', 'cdylib', 'dylib' and 'bin' outputs are supported \
+                   with LTO");
         sess.abort_if_errors();
     }
 
     // Make sure we actually can run LTO
     for crate_type in &*sess.crate_types.borrow() {
         match *crate_type {
-            config::CrateTypeExecutable | config::CrateTypeStaticlib => {}
+            config::CrateTypeExecutable |
+            config::CrateTypeStaticlib |
+            config::CrateTypeCdylib |
+            config::CrateTypeDylib => {}
             _ => {
-                sess.fatal("lto can only be run for executables and \
-                            static library outputs");
+                sess.fatal("lto can only be run for executables, dylibs, cdylibs \
+                            and static library outputs");
             }
         }
     }
 
+    // A dylib keeps exporting the upstream crates linked into it, as crates
+    // linking to the dylib may use them through it. So only the symbols which
+    // are defined by this crate itself and aren't reachable can be internalized.
+    let exports_upstream = sess.crate_types.borrow().contains(&config::CrateTypeDylib);
+    let local_definitions = if exports_upstream {
+        unsafe { external_definitions(llmod) }
+    } else {
+        HashSet::new()
+    };
+
     // For each of our upstream dependencies, find the corresponding rlib and
     // load the bitcode from the archive. Then merge it into the current LLVM
     // module that we've got.
+    each_upstream_bitcode(sess, true, |name, i, bc_decoded| {
+        let ptr = bc_decoded.as_ptr();
+        debug!("linking {}, part {}", name, i);
+        time(sess.time_passes(),
+             &format!("ll link {}.{}", name, i),
+             (),
+             |()| unsafe {
+            if !llvm::LLVMRustLinkInExternalBitcode(llmod,
+                                                    ptr as *const libc::c_char,
+                                                    bc_decoded.len() as libc::size_t) {
+                write::llvm_err(sess.diagnostic().handler(),
+                                format!("failed to load bc of `{}`",
+                                        &name[..]));
+            }
+        });
+    });
+
+    let mut symbols_to_keep = reachable.to_vec();
+    if exports_upstream {
+        let upstream_definitions = unsafe { external_definitions(llmod) };
+        symbols_to_keep.extend(upstream_definitions.into_iter().filter(|name| {
+            !local_definitions.contains(name)
+        }));
+    }
+
+    // Internalize everything but the symbols we need to keep
+    let cstrs: Vec<CString> = symbols_to_keep.iter().map(|s| {
+        CString::new(s.clone()).unwrap()
+    }).collect();
+    let arr: Vec<*const libc::c_char> = cstrs.iter().map(|c| c.as_ptr()).collect();
+    let ptr = arr.as_ptr();
+    unsafe {
+        llvm::LLVMRustRunRestrictionPass(llmod,
+                                         ptr as *const *const libc::c_char,
+                                         arr.len() as libc::size_t);
+    }
+
+    if sess.no_landing_pads() {
+        unsafe {
+            llvm::LLVMRustMarkAllFunctionsNounwind(llmod);
+        }
+    }
+
+    // Now we have one massive module inside of llmod. Time to run the
+    // LTO-specific optimization passes that LLVM provides.
+    //
+    // This code is based off the code found in llvm's LTO code generator:
+    //      tools/lto/LTOCodeGenerator.cpp
+    debug!("running the pass manager");
+    unsafe {
+        let pm = llvm::LLVMCreatePassManager();
+        llvm::LLVMRustAddAnalysisPasses(tm, pm, llmod);
+        llvm::LLVMRustAddPass(pm, "verify\0".as_ptr() as *const _);
+
+        let opt = match sess.opts.optimize {
+            config::No => 0,
+            config::Less => 1,
+            config::Default => 2,
+            config::Aggressive => 3,
+        };
+
+        let builder = llvm::LLVMPassManagerBuilderCreate();
+        llvm::LLVMPassManagerBuilderSetOptLevel(builder, opt);
+        llvm::LLVMPassManagerBuilderPopulateLTOPassManager(builder, pm,
+            /* Internalize = */ False,
+            /* RunInliner = */ True);
+        llvm::LLVMPassManagerBuilderDispose(builder);
+
+        llvm::LLVMRustAddPass(pm, "verify\0".as_ptr() as *const _);
+
+        time(sess.time_passes(), "LTO passes", (), |()|
+             llvm::LLVMRunPassManager(pm, llmod));
+
+        llvm::LLVMDisposePassManager(pm);
+    }
+    debug!("lto done");
+}
+
+/// How many instructions an upstream function may have for summary LTO to
+/// import it into a codegen unit, for each time the codegen unit calls it.
+/// Functions called from many places are worth importing at a bigger size.
+const IMPORT_SIZE_PER_CALL: u32 = 25;
+
+/// The most instructions an upstream function may have for summary LTO to
+/// import it, however often it is called.
+const IMPORT_SIZE_LIMIT: u32 = 200;
+
+/// What summary LTO may import from one object file of an upstream crate:
+/// the functions small enough to be worth inlining, and a module holding
+/// just their bodies, built once and shared by all codegen units.
+pub struct ImportSummary {
+    name: String,
+    // The size in instructions of each candidate function, by symbol name.
+    candidates: HashMap<String, u32>,
+    bitcode: Vec<u8>,
+}
+
+/// Builds the import summary of each object file of the upstream crates.
+/// Each of them is loaded only here, once, and only the bodies of the
+/// candidate functions are kept. Crates which are not available as an rlib
+/// are skipped: they are linked dynamically and can't be imported from.
+pub fn build_import_summaries(sess: &session::Session) -> Vec<ImportSummary> {
+    extern "C" fn candidate(data: *mut libc::c_void, name: *const libc::c_char,
+                            len: libc::size_t, size: libc::c_uint) {
+        let candidates = unsafe { &mut *(data as *mut HashMap<String, u32>) };
+        let name = unsafe { slice::from_raw_parts(name as *const u8, len as usize) };
+        candidates.insert(String::from_utf8_lossy(name).into_owned(), size as u32);
+    }
+
+    let mut summaries = Vec::new();
+    each_upstream_bitcode(sess, false, |name, i, data| {
+        let name = format!("{}.{}", name, i);
+        let mut candidates = HashMap::new();
+        let mut ok = false;
+        let bitcode = llvm::build_bytes(|out| unsafe {
+            ok = llvm::LLVMRustBuildImportSummary(data.as_ptr() as *const libc::c_char,
+                                                  data.len() as libc::size_t,
+                                                  IMPORT_SIZE_LIMIT as libc::c_uint,
+                                                  candidate,
+                                                  &mut candidates as *mut _ as *mut libc::c_void,
+                                                  out);
+        });
+        if !ok {
+            write::llvm_err(sess.diagnostic().handler(),
+                            format!("failed to summarize bc of `{}`", name));
+        }
+        debug!("{} functions of {} may be imported", candidates.len(), name);
+        if !candidates.is_empty() {
+            summaries.push(ImportSummary {
+                name: name,
+                candidates: candidates,
+                bitcode: bitcode,
+            });
+        }
+    });
+    summaries
+}
+
+/// Summary-based LTO of a single codegen unit: the upstream functions which
+/// `llmod` calls and which are small enough to be worth inlining, given how
+/// often they are called, are copied into it as `available_externally`
+/// definitions. Unlike full LTO, this keeps the codegen units separate, so
+/// they can still be optimized in parallel, and the upstream crates are still
+/// linked in as usual.
+pub fn run_summary(handler: &Handler, llmod: ModuleRef, summaries: &[ImportSummary]) {
+    extern "C" fn count(data: *mut libc::c_void, name: *const libc::c_char,
+                        len: libc::size_t, calls: libc::c_uint) {
+        let counts = unsafe { &mut *(data as *mut HashMap<String, u32>) };
+        let name = unsafe { slice::from_raw_parts(name as *const u8, len as usize) };
+        counts.insert(String::from_utf8_lossy(name).into_owned(), calls as u32);
+    }
+
+    let mut calls = HashMap::new();
+    unsafe {
+        llvm::LLVMRustCountExternalCalls(llmod, count,
+                                         &mut calls as *mut _ as *mut libc::c_void);
+    }
+
+    for summary in summaries {
+        let mut names: Vec<&str> = calls.iter().filter(|&(name, &count)| {
+            match summary.candidates.get(name) {
+                Some(&size) => {
+                    let limit = count.checked_mul(IMPORT_SIZE_PER_CALL)
+                                     .unwrap_or(IMPORT_SIZE_LIMIT);
+                    size <= cmp::min(limit, IMPORT_SIZE_LIMIT)
+                }
+                None => false,
+            }
+        }).map(|(name, _)| &name[..]).collect();
+        if names.is_empty() {
+            continue;
+        }
+        // Import in the same order every time, to keep the output stable.
+        names.sort();
+
+        debug!("importing {} functions from {}", names.len(), summary.name);
+        let cstrs: Vec<CString> = names.iter().map(|name| {
+            CString::new(*name).unwrap()
+        }).collect();
+        let arr: Vec<*const libc::c_char> = cstrs.iter().map(|c| c.as_ptr()).collect();
+        unsafe {
+            if !llvm::LLVMRustImportFunctions(llmod,
+                                              summary.bitcode.as_ptr() as *const libc::c_char,
+                                              summary.bitcode.len() as libc::size_t,
+                                              arr.as_ptr(),
+                                              arr.len() as libc::size_t) {
+                write::llvm_err(handler, format!("failed to import from bc of `{}`",
+                                                 summary.name));
+            }
+        }
+    }
+}
+
+/// Calls `f` with the crate name, part number and decompressed bitcode of each
+/// object file of the upstream crates linked in statically. Upstream crates
+/// without an rlib are an error if `require_rlibs` is set and skipped
+/// otherwise.
+fn each_upstream_bitcode<F>(sess: &session::Session, require_rlibs: bool, mut f: F)
+    where F: FnMut(&str, usize, &[u8])
+{
     let crates = sess.cstore.get_used_crates(cstore::RequireStatic);
     for (cnum, path) in crates {
         let name = sess.cstore.get_crate_data(cnum).name.clone();
         let path = match path {
             Some(p) => p,
+            None if !require_rlibs => continue,
             None => {
                 sess.fatal(&format!("could not find rlib for: `{}`",
                                    name));
@@ -121,74 +340,35 @@ pub fn run(sess: &session::Session, llmod: ModuleRef,
                 })
             };
 
-            let ptr = bc_decoded.as_ptr();
-            debug!("linking {}, part {}", name, i);
-            time(sess.time_passes(),
-                 &format!("ll link {}.{}", name, i),
-                 (),
-                 |()| unsafe {
-                if !llvm::LLVMRustLinkInExternalBitcode(llmod,
-                                                        ptr as *const libc::c_char,
-                                                        bc_decoded.len() as libc::size_t) {
-                    write::llvm_err(sess.diagnostic().handler(),
-                                    format!("failed to load bc of `{}`",
-                                            &name[..]));
-                }
-            });
+            f(&name, i, &bc_decoded);
         }
     }
+}
 
-    // Internalize everything but the reachable symbols of the current module
-    let cstrs: Vec<CString> = reachable.iter().map(|s| {
-        CString::new(s.clone()).unwrap()
-    }).collect();
-    let arr: Vec<*const libc::c_char> = cstrs.iter().map(|c| c.as_ptr()).collect();
-    let ptr = arr.as_ptr();
-    unsafe {
-        llvm::LLVMRustRunRestrictionPass(llmod,
-                                         ptr as *const *const libc::c_char,
-                                         arr.len() as libc::size_t);
-    }
-
-    if sess.no_landing_pads() {
-        unsafe {
-            llvm::LLVMRustMarkAllFunctionsNounwind(llmod);
+/// The names of the functions and global variables that `llmod` defines and
+/// that are visible outside of it.
+unsafe fn external_definitions(llmod: ModuleRef) -> HashSet<String> {
+    let mut names = HashSet::new();
+    let mut insert = |val: ValueRef| {
+        if llvm::LLVMIsDeclaration(val) == 0 &&
+           llvm::LLVMGetLinkage(val) != llvm::InternalLinkage as libc::c_uint &&
+           llvm::LLVMGetLinkage(val) != llvm::PrivateLinkage as libc::c_uint {
+            let name = CStr::from_ptr(llvm::LLVMGetValueName(val));
+            names.insert(String::from_utf8_lossy(name.to_bytes()).into_owned());
         }
-    }
-
-    // Now we have one massive module inside of llmod. Time to run the
-    // LTO-specific optimization passes that LLVM provides.
-    //
-    // This code is based off the code found in llvm's LTO code generator:
-    //      tools/lto/LTOCodeGenerator.cpp
-    debug!("running the pass manager");
-    unsafe {
-        let pm = llvm::LLVMCreatePassManager();
-        llvm::LLVMRustAddAnalysisPasses(tm, pm, llmod);
-        llvm::LLVMRustAddPass(pm, "verify\0".as_ptr() as *const _);
-
-        let opt = match sess.opts.optimize {
-            config::No => 0,
-            config::Less => 1,
-            config::Default => 2,
-            config::Aggressive => 3,
-        };
-
-        let builder = llvm::LLVMPassManagerBuilderCreate();
-        llvm::LLVMPassManagerBuilderSetOptLevel(builder, opt);
-        llvm::LLVMPassManagerBuilderPopulateLTOPassManager(builder, pm,
-            /* Internalize = */ False,
-            /* RunInliner = */ True);
-        llvm::LLVMPassManagerBuilderDispose(builder);
-
-        llvm::LLVMRustAddPass(pm, "verify\0".as_ptr() as *const _);
-
-        time(sess.time_passes(), "LTO passes", (), |()|
-             llvm::LLVMRunPassManager(pm, llmod));
+    };
 
-        llvm::LLVMDisposePassManager(pm);
+    let mut val = llvm::LLVMGetFirstFunction(llmod);
+    while !val.is_null() {
+        insert(val);
+        val = llvm::LLVMGetNextFunction(val);
     }
-    debug!("lto done");
+    let mut val = llvm::LLVMGetFirstGlobal(llmod);
+    while !val.is_null() {
+        insert(val);
+        val = llvm::LLVMGetNextGlobal(val);
+    }
+    names
 }
 
 fn is_versioned_bytecode_format(bc: &[u8]) -> bool {
@@ -10,7 +10,7 @@
 
 use back::lto;
 use back::link::{get_cc_prog, remove};
-use session::config::{OutputFilenames, NoDebugInfo, Passes, SomePasses, AllPasses};
+use session::config::{OutputFilenames, NoDebugInfo, Passes, SomePasses, AllPasses, Sanitizer};
 use session::Session;
 use session::config;
 use llvm;
@@ -23,11 +23,12 @@ use syntax::codemap;
 use syntax::diagnostic;
 use syntax::diagnostic::{Emitter, Handler, Level, mk_handler};
 
+use std::env;
 use std::ffi::{CStr, CString};
 use std::fs;
 use std::iter::Unfold;
 use std::mem;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::ptr;
 use std::str;
@@ -279,6 +280,12 @@ struct ModuleConfig {
     no_prepopulate_passes: bool,
     no_builtins: bool,
     time_passes: bool,
+    sanitizer: Option<Sanitizer>,
+    coverage: bool,
+    // Where an instrumented program writes its profile.
+    profile_output: Option<PathBuf>,
+    // What the upstream crates have to import under `-C lto=summary`.
+    lto_imports: Option<Arc<Vec<lto::ImportSummary>>>,
 }
 
 unsafe impl Send for ModuleConfig { }
@@ -301,6 +308,10 @@ impl ModuleConfig {
             no_prepopulate_passes: false,
             no_builtins: false,
             time_passes: false,
+            sanitizer: None,
+            coverage: false,
+            profile_output: None,
+            lto_imports: None,
         }
     }
 
@@ -309,6 +320,13 @@ impl ModuleConfig {
         self.no_prepopulate_passes = sess.opts.cg.no_prepopulate_passes;
         self.no_builtins = trans.no_builtins;
         self.time_passes = sess.time_passes();
+        self.sanitizer = sess.opts.debugging_opts.sanitizer;
+        self.coverage = sess.opts.cg.coverage;
+        // This is the file the profiling runtime writes by default, so it
+        // doesn't matter which instrumented crate's constructor runs last.
+        self.profile_output = sess.opts.cg.profile_generate.as_ref().map(|dir| {
+            Path::new(dir).join("default.profraw")
+        });
     }
 }
 
@@ -407,6 +425,126 @@ unsafe extern "C" fn diagnostic_handler(info: DiagnosticInfoRef, user: *mut c_vo
     }
 }
 
+/// Tells LLVM's gcov pass where to put the notes (`.gcno`) and counts
+/// (`.gcda`) of the module: next to the crate's output, named after the
+/// codegen unit. Returns false if the module has no debuginfo, and so no
+/// line tables to map the counters back to the source with.
+///
+/// The pass writes one pair of files per compile unit. The module's own
+/// compile unit comes first and gets the plain name; any other compile
+/// units, linked in from upstream crates by summary LTO, get their index
+/// appended so that their files don't overwrite each other.
+unsafe fn add_gcov_metadata(llcx: ContextRef,
+                            llmod: ModuleRef,
+                            output_names: &OutputFilenames,
+                            name_extra: &str) -> bool {
+    let dbg_cu = "llvm.dbg.cu\0".as_ptr() as *const _;
+    let num_units = llvm::LLVMGetNamedMetadataNumOperands(llmod, dbg_cu);
+    if num_units == 0 {
+        return false;
+    }
+    // A module has several compile units once summary LTO has imported
+    // functions from upstream crates into it.
+    let mut compile_units = vec![ptr::null_mut(); num_units as usize];
+    llvm::LLVMGetNamedMetadataOperands(llmod, dbg_cu, compile_units.as_mut_ptr());
+
+    for (i, &compile_unit) in compile_units.iter().enumerate() {
+        let ext = if i == 0 {
+            format!("{}.gcda", name_extra)
+        } else {
+            format!("{}.{}.gcda", name_extra, i)
+        };
+        // The counts are written by the instrumented program, which may well
+        // run in another directory.
+        let path = output_names.with_extension(&ext);
+        let path = env::current_dir().unwrap().join(&path);
+        let path = path.to_string_lossy();
+        let md_path = llvm::LLVMMDStringInContext(llcx,
+                                                  path.as_ptr() as *const _,
+                                                  path.len() as c_uint);
+        let node = llvm::LLVMMDNodeInContext(llcx, [md_path, compile_unit].as_ptr(), 2);
+        llvm::LLVMAddNamedMetadataOperand(llmod, "llvm.gcov\0".as_ptr() as *const _, node);
+    }
+    true
+}
+
+/// The LLVM passes that instrument code for a sanitizer. The leak sanitizer
+/// needs no instrumentation, only its runtime.
+fn sanitizer_passes(sanitizer: Option<Sanitizer>) -> &'static [&'static str] {
+    match sanitizer {
+        Some(Sanitizer::Address) => &["asan", "asan-module"],
+        Some(Sanitizer::Thread) => &["tsan"],
+        Some(Sanitizer::Memory) => &["msan"],
+        Some(Sanitizer::Leak) | None => &[],
+    }
+}
+
+// The size of a page, and so of the smallest guard page. A stack frame no
+// larger than this can't skip over the guard page.
+const STACK_PROBE_INTERVAL: u64 = 4096;
+
+// Room for what `LLVMRustFrameSizeEstimate` leaves out of a frame: the return
+// address, the callee-saved registers and the alignment of the frame.
+const STACK_FRAME_OVERHEAD: u64 = 128;
+
+/// Probes the stack frames of the functions trans marked for it (see
+/// `attributes::probe_stack`) that may be larger than a page, so that an
+/// overflowing function hits the guard page at the end of the stack before
+/// it writes to whatever lies beyond it.
+///
+/// The frame size isn't known until code generation lays the frame out, so
+/// it is estimated instead, once the module is fully optimized, by
+/// `LLVMRustFrameSizeEstimate`, which errs on the large side by counting two
+/// stack slots for every value the function computes. This is not a bound:
+/// a frame that code generation makes larger than its estimate by more than
+/// a page can still skip over the guard page. The body of a function with a
+/// large frame is moved to a function of its own, and the original function
+/// probes the estimated size below its own small frame before calling it. So
+/// nothing is written to the large frame before it has been probed, whatever
+/// LLVM puts in the body's prologue. Probing is done by `__rust_probestack`,
+/// which is jumped to from inline assembly.
+unsafe fn probe_large_frames(llmod: ModuleRef) {
+    let attr = "rust-probestack\0".as_ptr() as *const _;
+    let mut large_frames = Vec::new();
+    let mut llfn = llvm::LLVMGetFirstFunction(llmod);
+    while !llfn.is_null() {
+        if llvm::LLVMIsDeclaration(llfn) == llvm::False &&
+           llvm::LLVMGetLinkage(llfn) != llvm::AvailableExternallyLinkage as c_uint &&
+           llvm::LLVMRustHasFunctionAttrString(llfn, attr) {
+            let size = llvm::LLVMRustFrameSizeEstimate(llfn) + STACK_FRAME_OVERHEAD;
+            if size > STACK_PROBE_INTERVAL {
+                large_frames.push((llfn, size));
+            }
+        }
+        llfn = llvm::LLVMGetNextFunction(llfn);
+    }
+    if large_frames.is_empty() {
+        return;
+    }
+
+    let llcx = llvm::LLVMGetModuleContext(llmod);
+    let builder = llvm::LLVMCreateBuilderInContext(llcx);
+    let asm_ty = llvm::LLVMFunctionType(llvm::LLVMVoidTypeInContext(llcx),
+                                        ptr::null(), 0, llvm::False);
+    let constraints = "~{rax},~{r10},~{r11},~{memory},~{dirflag},~{fpsr},~{flags}\0";
+    for (llfn, size) in large_frames {
+        let name = CStr::from_ptr(llvm::LLVMGetValueName(llfn)).to_string_lossy();
+        let body = CString::new(format!("{}.body", name)).unwrap();
+        let call = llvm::LLVMRustOutlineFunctionBody(llfn, body.as_ptr());
+
+        let asm = CString::new(format!("leaq 1f(%rip), %r11\n\
+                                        movq $${}, %rax\n\
+                                        jmp __rust_probestack\n\
+                                        1:", size)).unwrap();
+        let asm = llvm::LLVMConstInlineAsm(asm_ty, asm.as_ptr(),
+                                           constraints.as_ptr() as *const _,
+                                           llvm::True, llvm::False);
+        llvm::LLVMPositionBuilderBefore(builder, call);
+        llvm::LLVMBuildCall(builder, asm, ptr::null(), 0, "\0".as_ptr() as *const _);
+    }
+    llvm::LLVMDisposeBuilder(builder);
+}
+
 // Unsafe due to LLVM calls.
 unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
                                mtrans: ModuleTranslation,
@@ -435,6 +573,13 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
 
     match config.opt_level {
         Some(opt_level) => {
+            // Import upstream functions first, so that the regular passes
+            // below can inline them.
+            if let Some(ref summaries) = config.lto_imports {
+                time(config.time_passes, "summary lto import", (), |()|
+                     lto::run_summary(cgcx.handler, llmod, summaries));
+            }
+
             // Create the two optimizing pass managers. These mirror what clang
             // does, and are by populated by LLVM's default PassManagerBuilder.
             // Each manager has a different set of passes, but they also share
@@ -450,6 +595,23 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
             };
             if !config.no_verify { assert!(addpass("verify")); }
 
+            // Insert the counters before anything is optimized away, so they
+            // match the lines of the source.
+            if config.coverage && add_gcov_metadata(llcx, llmod, &output_names, &name_extra) {
+                let pass = CString::new("insert-gcov-profiling").unwrap();
+                if !llvm::LLVMRustAddPass(mpm, pass.as_ptr()) {
+                    cgcx.handler.err("-C coverage requires LLVM's gcov pass, which this \
+                                      build of LLVM does not include");
+                }
+            }
+
+            // The counters that `-C profile-generate` placed are lowered
+            // before they can be optimized, as clang does.
+            if let Some(ref output) = config.profile_output {
+                let output = path2cstr(output);
+                llvm::LLVMRustAddInstrProfilingPass(mpm, output.as_ptr());
+            }
+
             if !config.no_prepopulate_passes {
                 llvm::LLVMRustAddAnalysisPasses(tm, fpm, llmod);
                 llvm::LLVMRustAddAnalysisPasses(tm, mpm, llmod);
@@ -464,6 +626,14 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
                 }
             }
 
+            for pass in sanitizer_passes(config.sanitizer) {
+                let pass = CString::new(*pass).unwrap();
+                if !llvm::LLVMRustAddPass(mpm, pass.as_ptr()) {
+                    cgcx.handler.err(&format!("LLVM does not recognize the sanitizer \
+                                               pass {:?}", pass));
+                }
+            }
+
             for pass in &cgcx.plugin_passes {
                 let pass = CString::new(pass.clone()).unwrap();
                 if !llvm::LLVMRustAddPass(mpm, pass.as_ptr()) {
@@ -530,6 +700,10 @@ unsafe fn optimize_and_codegen(cgcx: &CodegenContext,
         llvm::LLVMWriteBitcodeToFile(llmod, out.as_ptr());
     }
 
+    // After the bitcode is written, so that LTO sees the functions as trans
+    // left them and probes them after it has optimized them.
+    probe_large_frames(llmod);
+
     time(config.time_passes, "codegen passes", (), |()| {
         if config.emit_ir {
             let ext = format!("{}.ll", name_extra);
@@ -593,6 +767,12 @@ pub fn run_passes(sess: &Session,
 
     modules_config.opt_level = Some(get_llvm_opt_level(sess.opts.optimize));
 
+    if sess.opts.cg.lto == config::Lto::Summary {
+        let summaries = time(sess.time_passes(), "summarizing upstream bitcode", (), |()|
+                             lto::build_import_summaries(sess));
+        modules_config.lto_imports = Some(Arc::new(summaries));
+    }
+
     // Save all versions of the bytecode if we're saving our temporaries.
     if sess.opts.cg.save_temps {
         modules_config.emit_no_opt_bc = true;
@@ -20,6 +20,10 @@
 //! enum, so structs/tuples/etc. have one pseudo-variant with
 //! discriminant 0; i.e., as if they were a univariant enum.
 //!
+//! The fields of a struct without a `#[repr]` attribute are not
+//! necessarily laid out in declaration order, so field indices must be
+//! mapped through `Struct::memory_index` before being used in a GEP.
+//!
 //! Having everything in one place will enable improvements to data
 //! structure representation; possibilities include:
 //!
@@ -48,8 +52,10 @@ pub use self::Repr::*;
 #[allow(deprecated)]
 use std::num::Int;
 use std::rc::Rc;
+use std::{char, u8, u32};
 
-use llvm::{ValueRef, True, IntEQ, IntNE};
+use libc::c_uint;
+use llvm::{self, ValueRef, True, IntEQ, IntNE};
 use back::abi::FAT_PTR_ADDR;
 use middle::subst;
 use middle::ty::{self, Ty, ClosureTyper};
@@ -104,23 +110,51 @@ pub enum Repr<'tcx> {
         nnty: Ty<'tcx>,
         nullfields: Vec<Ty<'tcx>>
     },
-    /// Two cases distinguished by a nullable pointer: the case with discriminant
-    /// `nndiscr` is represented by the struct `nonnull`, where the `discrfield`th
-    /// field is known to be nonnull due to its type; if that field is null, then
-    /// it represents the other case, which is inhabited by at most one value
-    /// (and all other fields are undefined/unused).
+    /// Two cases distinguished by a field with invalid values: the case with
+    /// discriminant `nndiscr` is represented by the struct `nonnull`, where
+    /// the `discrfield`th field can never hold the value `niche.value` due to
+    /// its type; if that field holds it, then it represents the other case,
+    /// which is inhabited by at most one value (and all other fields are
+    /// undefined/unused).
     ///
     /// For example, `std::option::Option` instantiated at a safe pointer type
     /// is represented such that `None` is a null pointer and `Some` is the
-    /// identity function.
+    /// identity function, and `Option<bool>` is a byte where `None` is `2`.
     StructWrappedNullablePointer {
         nonnull: Struct<'tcx>,
         nndiscr: Disr,
         discrfield: DiscrField,
+        niche: Niche,
         nullfields: Vec<Ty<'tcx>>,
     }
 }
 
+/// An invalid value of the scalar at the end of a `DiscrField`, used to
+/// encode the empty case of a `StructWrappedNullablePointer`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct Niche {
+    /// The value that encodes the empty case; `0` for pointers.
+    pub value: u64,
+    /// The largest value the scalar can be made to hold. The invalid values
+    /// above `value` are still free, so an enum wrapping this one can use
+    /// `value + 1`.
+    pub limit: u64,
+}
+
+impl Niche {
+    fn null() -> Niche {
+        Niche { value: 0, limit: 0 }
+    }
+
+    fn next(&self) -> Option<Niche> {
+        if self.value < self.limit {
+            Some(Niche { value: self.value + 1, limit: self.limit })
+        } else {
+            None
+        }
+    }
+}
+
 /// For structs, and struct-like parts of anything fancier.
 #[derive(Eq, PartialEq, Debug)]
 pub struct Struct<'tcx> {
@@ -130,7 +164,27 @@ pub struct Struct<'tcx> {
     pub align: u32,
     pub sized: bool,
     pub packed: bool,
-    pub fields: Vec<Ty<'tcx>>
+    pub fields: Vec<Ty<'tcx>>,
+    // For each field, in declaration order, its index in the LLVM struct.
+    // Fields of structs without a `#[repr]` are laid out by decreasing
+    // alignment to minimize padding.
+    pub memory_index: Vec<usize>,
+}
+
+impl<'tcx> Struct<'tcx> {
+    /// The indices of the fields, in the order they are laid out in memory.
+    pub fn memory_order(&self) -> Vec<usize> {
+        let mut order = vec![0; self.memory_index.len()];
+        for (i, &m) in self.memory_index.iter().enumerate() {
+            order[m] = i;
+        }
+        order
+    }
+
+    /// The field types, in the order they are laid out in memory.
+    pub fn fields_in_memory_order(&self) -> Vec<Ty<'tcx>> {
+        self.memory_order().iter().map(|&i| self.fields[i]).collect()
+    }
 }
 
 /// Convenience for `represent_type`.  There should probably be more or
@@ -220,7 +274,28 @@ fn represent_type_uncached<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
             let dtor = ty::ty_dtor(cx.tcx(), def_id).has_drop_flag();
             if dtor { ftys.push(cx.tcx().dtor_type()); }
 
-            Univariant(mk_struct(cx, &ftys[..], packed, t), dtor_to_init_u8(dtor))
+            // Only reorder fields when nothing pins down the layout: not
+            // for `#[repr(C)]` or SIMD structs. An unsized last field must
+            // stay last. So must the last field of a generic struct, as it
+            // may be unsized in some other instantiation, which must agree
+            // on the offsets of the others.
+            let reorder = !packed &&
+                ty::lookup_repr_hints(cx.tcx(), def_id).is_empty() &&
+                !ty::type_is_simd(cx.tcx(), t);
+            let st = if reorder {
+                let mut fixed = if dtor { 1 } else { 0 };
+                if !fields.is_empty() {
+                    let last = ftys[fields.len() - 1];
+                    if !substs.types.is_empty() || !type_is_sized(cx.tcx(), last) {
+                        fixed += 1;
+                    }
+                }
+                mk_reordered_struct(cx, &ftys[..], fixed, t)
+            } else {
+                mk_struct(cx, &ftys[..], packed, t)
+            };
+
+            Univariant(st, dtor_to_init_u8(dtor))
         }
         ty::ty_closure(def_id, substs) => {
             let typer = NormalizingClosureTyper::new(cx.tcx());
@@ -277,27 +352,31 @@ fn represent_type_uncached<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
             }
 
             if !dtor && cases.len() == 2 && hint == attr::ReprAny {
-                // Nullable pointer optimization
+                // Nullable pointer optimization, generalized to any field
+                // with an invalid value to spare (a niche).
                 let mut discr = 0;
                 while discr < 2 {
                     if cases[1 - discr].is_zerolen(cx, t) {
                         let st = mk_struct(cx, &cases[discr].tys,
                                            false, t);
-                        match cases[discr].find_ptr(cx) {
-                            Some(ref df) if df.len() == 1 && st.fields.len() == 1 => {
+                        match cases[discr].find_niche(cx) {
+                            Some((ref df, niche))
+                                if df.len() == 1 && st.fields.len() == 1 &&
+                                   niche == Niche::null() => {
                                 return RawNullablePointer {
                                     nndiscr: discr as Disr,
                                     nnty: st.fields[0],
                                     nullfields: cases[1 - discr].tys.clone()
                                 };
                             }
-                            Some(mut discrfield) => {
+                            Some((mut discrfield, niche)) => {
                                 discrfield.push(0);
                                 discrfield.reverse();
                                 return StructWrappedNullablePointer {
                                     nndiscr: discr as Disr,
                                     nonnull: st,
                                     discrfield: discrfield,
+                                    niche: niche,
                                     nullfields: cases[1 - discr].tys.clone()
                                 };
                             }
@@ -394,21 +473,54 @@ struct Case<'tcx> {
 /// This represents the (GEP) indices to follow to get to the discriminant field
 pub type DiscrField = Vec<usize>;
 
-fn find_discr_field_candidate<'tcx>(tcx: &ty::ctxt<'tcx>,
-                                    ty: Ty<'tcx>,
-                                    mut path: DiscrField) -> Option<DiscrField> {
+/// Looks for a scalar inside a value of type `ty` that has an invalid value
+/// to spare, returning the (reversed) path to it and the value to use.
+fn find_discr_field_candidate<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
+                                        ty: Ty<'tcx>,
+                                        mut path: DiscrField) -> Option<(DiscrField, Niche)> {
+    let tcx = cx.tcx();
     match ty.sty {
         // Fat &T/&mut T/Box<T> i.e. T is [T], str, or Trait
         ty::ty_rptr(_, ty::mt { ty, .. }) | ty::ty_uniq(ty) if !type_is_sized(tcx, ty) => {
             path.push(FAT_PTR_ADDR);
-            Some(path)
+            Some((path, Niche::null()))
         },
 
         // Regular thin pointer: &T/&mut T/Box<T>
-        ty::ty_rptr(..) | ty::ty_uniq(..) => Some(path),
+        ty::ty_rptr(..) | ty::ty_uniq(..) => Some((path, Niche::null())),
 
         // Functions are just pointers
-        ty::ty_bare_fn(..) => Some(path),
+        ty::ty_bare_fn(..) => Some((path, Niche::null())),
+
+        // A `bool` is a byte that is only ever 0 or 1.
+        ty::ty_bool => Some((path, Niche { value: 2, limit: u8::MAX as u64 })),
+
+        // A `char` is never above `char::MAX`.
+        ty::ty_char => {
+            Some((path, Niche { value: char::MAX as u64 + 1, limit: u32::MAX as u64 }))
+        }
+
+        // Values of an enum that are not any of its discriminants.
+        ty::ty_enum(..) => {
+            match *represent_type(cx, ty) {
+                CEnum(ity, min, max) => {
+                    discr_niche(cx, ity, min, max).map(|niche| (path, niche))
+                }
+                General(ity, ref cases, _) => {
+                    discr_niche(cx, ity, 0, (cases.len() - 1) as Disr).map(|niche| {
+                        path.push(0);
+                        (path, niche)
+                    })
+                }
+                StructWrappedNullablePointer { ref discrfield, niche, .. } => {
+                    niche.next().map(|niche| {
+                        path.extend(discrfield[1..].iter().rev().cloned());
+                        (path, niche)
+                    })
+                }
+                Univariant(..) | RawNullablePointer { .. } => None,
+            }
+        }
 
         // Is this the NonZero lang item wrapping a pointer or integer type?
         ty::ty_struct(did, substs) if Some(did) == tcx.lang_items.non_zero() => {
@@ -418,21 +530,28 @@ fn find_discr_field_candidate<'tcx>(tcx: &ty::ctxt<'tcx>,
             match nonzero_field.sty {
                 ty::ty_ptr(..) | ty::ty_int(..) | ty::ty_uint(..) => {
                     path.push(0);
-                    Some(path)
+                    Some((path, Niche::null()))
                 },
                 _ => None
             }
         },
 
-        // Perhaps one of the fields of this struct is non-zero
+        // Perhaps one of the fields of this struct has a niche
         // let's recurse and find out
         ty::ty_struct(def_id, substs) => {
             let fields = ty::lookup_struct_fields(tcx, def_id);
             for (j, field) in fields.iter().enumerate() {
                 let field_ty = ty::lookup_field_type(tcx, def_id, field.id, substs);
-                if let Some(mut fpath) = find_discr_field_candidate(tcx, field_ty, path.clone()) {
-                    fpath.push(j);
-                    return Some(fpath);
+                let field_ty = monomorphize::normalize_associated_type(tcx, &field_ty);
+                if let Some((mut fpath, niche)) =
+                        find_discr_field_candidate(cx, field_ty, path.clone()) {
+                    // The path is made of LLVM indices, which may differ
+                    // from the declaration order.
+                    match *represent_type(cx, ty) {
+                        Univariant(ref st, _) => fpath.push(st.memory_index[j]),
+                        _ => fpath.push(j),
+                    }
+                    return Some((fpath, niche));
                 }
             }
             None
@@ -441,20 +560,21 @@ fn find_discr_field_candidate<'tcx>(tcx: &ty::ctxt<'tcx>,
         // Can we use one of the fields in this tuple?
         ty::ty_tup(ref tys) => {
             for (j, &ty) in tys.iter().enumerate() {
-                if let Some(mut fpath) = find_discr_field_candidate(tcx, ty, path.clone()) {
+                if let Some((mut fpath, niche)) =
+                        find_discr_field_candidate(cx, ty, path.clone()) {
                     fpath.push(j);
-                    return Some(fpath);
+                    return Some((fpath, niche));
                 }
             }
             None
         },
 
-        // Is this a fixed-size array of something non-zero
+        // Is this a fixed-size array of something with a niche
         // with at least one element?
         ty::ty_vec(ety, Some(d)) if d > 0 => {
-            if let Some(mut vpath) = find_discr_field_candidate(tcx, ety, path) {
+            if let Some((mut vpath, niche)) = find_discr_field_candidate(cx, ety, path) {
                 vpath.push(0);
-                Some(vpath)
+                Some((vpath, niche))
             } else {
                 None
             }
@@ -470,17 +590,35 @@ impl<'tcx> Case<'tcx> {
         mk_struct(cx, &self.tys, false, scapegoat).size == 0
     }
 
-    fn find_ptr<'a>(&self, cx: &CrateContext<'a, 'tcx>) -> Option<DiscrField> {
+    fn find_niche<'a>(&self, cx: &CrateContext<'a, 'tcx>) -> Option<(DiscrField, Niche)> {
         for (i, &ty) in self.tys.iter().enumerate() {
-            if let Some(mut path) = find_discr_field_candidate(cx.tcx(), ty, vec![]) {
+            if let Some((mut path, niche)) = find_discr_field_candidate(cx, ty, vec![]) {
                 path.push(i);
-                return Some(path);
+                return Some((path, niche));
             }
         }
         None
     }
 }
 
+/// The first value after the discriminants `min..max` of an enum stored as
+/// `ity`, if there is one to spare.
+fn discr_niche(cx: &CrateContext, ity: IntType, min: Disr, max: Disr) -> Option<Niche> {
+    let bits = machine::llbitsize_of_real(cx, ll_inttype(cx, ity));
+    let limit = match ity {
+        // Negative discriminants would wrap around to the top of the
+        // range; don't bother with those.
+        attr::SignedInt(_) if (min as i64) < 0 => return None,
+        attr::SignedInt(_) => (1 << (bits - 1)) - 1,
+        attr::UnsignedInt(_) => !0u64 >> (64 - bits),
+    };
+    if max < limit {
+        Some(Niche { value: max + 1, limit: limit })
+    } else {
+        None
+    }
+}
+
 fn get_cases<'tcx>(tcx: &ty::ctxt<'tcx>,
                    def_id: ast::DefId,
                    substs: &subst::Substs<'tcx>)
@@ -497,15 +635,47 @@ fn mk_struct<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                        tys: &[Ty<'tcx>], packed: bool,
                        scapegoat: Ty<'tcx>)
                        -> Struct<'tcx> {
+    mk_struct_in_order(cx, tys, packed, (0..tys.len()).collect(), scapegoat)
+}
+
+/// Like `mk_struct`, but lays the fields out by decreasing alignment, which
+/// leaves no padding between them. The last `fixed` fields (the drop flag,
+/// a possibly unsized field) stay at the end, in order.
+fn mk_reordered_struct<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
+                                 tys: &[Ty<'tcx>], fixed: usize,
+                                 scapegoat: Ty<'tcx>)
+                                 -> Struct<'tcx> {
+    let reorderable = tys.len() - fixed;
+    let mut order = (0..reorderable).collect::<Vec<_>>();
+    // The sort is stable, so fields with the same alignment keep their
+    // relative order.
+    order.sort_by(|&a, &b| {
+        type_of::align_of(cx, tys[b]).cmp(&type_of::align_of(cx, tys[a]))
+    });
+    order.extend(reorderable..tys.len());
+    mk_struct_in_order(cx, tys, false, order, scapegoat)
+}
+
+/// Builds a struct whose `m`th field in memory is `tys[order[m]]`.
+fn mk_struct_in_order<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
+                                tys: &[Ty<'tcx>], packed: bool,
+                                order: Vec<usize>,
+                                scapegoat: Ty<'tcx>)
+                                -> Struct<'tcx> {
     let sized = tys.iter().all(|&ty| type_is_sized(cx.tcx(), ty));
     let lltys : Vec<Type> = if sized {
-        tys.iter()
-           .map(|&ty| type_of::sizing_type_of(cx, ty)).collect()
+        order.iter()
+             .map(|&i| type_of::sizing_type_of(cx, tys[i])).collect()
     } else {
-        tys.iter().filter(|&ty| type_is_sized(cx.tcx(), *ty))
-           .map(|&ty| type_of::sizing_type_of(cx, ty)).collect()
+        order.iter().filter(|&&i| type_is_sized(cx.tcx(), tys[i]))
+             .map(|&i| type_of::sizing_type_of(cx, tys[i])).collect()
     };
 
+    let mut memory_index = vec![0; tys.len()];
+    for (m, &i) in order.iter().enumerate() {
+        memory_index[i] = m;
+    }
+
     ensure_struct_fits_in_address_space(cx, &lltys[..], packed, scapegoat);
 
     let llty_rec = Type::struct_(cx, &lltys[..], packed);
@@ -515,6 +685,7 @@ fn mk_struct<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         sized: sized,
         packed: packed,
         fields: tys.to_vec(),
+        memory_index: memory_index,
     }
 }
 
@@ -748,11 +919,12 @@ fn generic_type_of<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
 
 fn struct_llfields<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>, st: &Struct<'tcx>,
                              sizing: bool, dst: bool) -> Vec<Type> {
+    let fields = st.fields_in_memory_order();
     if sizing {
-        st.fields.iter().filter(|&ty| !dst || type_is_sized(cx.tcx(), *ty))
+        fields.iter().filter(|&ty| !dst || type_is_sized(cx.tcx(), *ty))
             .map(|&ty| type_of::sizing_type_of(cx, ty)).collect()
     } else {
-        st.fields.iter().map(|&ty| type_of::in_memory_type_of(cx, ty)).collect()
+        fields.iter().map(|&ty| type_of::in_memory_type_of(cx, ty)).collect()
     }
 }
 
@@ -803,8 +975,8 @@ pub fn trans_get_discr<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, r: &Repr<'tcx>,
             val = ICmp(bcx, cmp, Load(bcx, scrutinee), C_null(llptrty), DebugLoc::None);
             signed = false;
         }
-        StructWrappedNullablePointer { nndiscr, ref discrfield, .. } => {
-            val = struct_wrapped_nullable_bitdiscr(bcx, nndiscr, discrfield, scrutinee);
+        StructWrappedNullablePointer { nndiscr, ref discrfield, niche, .. } => {
+            val = struct_wrapped_nullable_bitdiscr(bcx, nndiscr, discrfield, niche, scrutinee);
             signed = false;
         }
     }
@@ -815,11 +987,20 @@ pub fn trans_get_discr<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, r: &Repr<'tcx>,
 }
 
 fn struct_wrapped_nullable_bitdiscr(bcx: Block, nndiscr: Disr, discrfield: &DiscrField,
-                                    scrutinee: ValueRef) -> ValueRef {
+                                    niche: Niche, scrutinee: ValueRef) -> ValueRef {
     let llptrptr = GEPi(bcx, scrutinee, &discrfield[..]);
     let llptr = Load(bcx, llptrptr);
     let cmp = if nndiscr == 0 { IntEQ } else { IntNE };
-    ICmp(bcx, cmp, llptr, C_null(val_ty(llptr)), DebugLoc::None)
+    ICmp(bcx, cmp, llptr, C_niche(val_ty(llptr), niche), DebugLoc::None)
+}
+
+/// The constant stored in the discriminant field of the empty case.
+fn C_niche(llty: Type, niche: Niche) -> ValueRef {
+    if niche == Niche::null() {
+        C_null(llty)
+    } else {
+        C_integral(llty, niche.value, false)
+    }
 }
 
 /// Helper for cases where the discriminant is simply loaded.
@@ -904,11 +1085,11 @@ pub fn trans_set_discr<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, r: &Repr<'tcx>,
                 Store(bcx, C_null(llptrty), val)
             }
         }
-        StructWrappedNullablePointer { nndiscr, ref discrfield, .. } => {
+        StructWrappedNullablePointer { nndiscr, ref discrfield, niche, .. } => {
             if discr != nndiscr {
                 let llptrptr = GEPi(bcx, val, &discrfield[..]);
                 let llptrty = val_ty(llptrptr).element_type();
-                Store(bcx, C_null(llptrty), llptrptr)
+                Store(bcx, C_niche(llptrty, niche), llptrptr)
             }
         }
     }
@@ -987,14 +1168,16 @@ pub fn struct_field_ptr<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, st: &Struct<'tcx>, v
                                     ix: usize, needs_cast: bool) -> ValueRef {
     let val = if needs_cast {
         let ccx = bcx.ccx();
-        let fields = st.fields.iter().map(|&ty| type_of::type_of(ccx, ty)).collect::<Vec<_>>();
+        let fields = st.fields_in_memory_order().iter().map(|&ty| {
+            type_of::type_of(ccx, ty)
+        }).collect::<Vec<_>>();
         let real_ty = Type::struct_(ccx, &fields[..], st.packed);
         PointerCast(bcx, val, real_ty.ptr_to())
     } else {
         val
     };
 
-    GEPi(bcx, val, &[0, ix])
+    GEPi(bcx, val, &[0, st.memory_index[ix]])
 }
 
 pub fn fold_variants<'blk, 'tcx, F>(bcx: Block<'blk, 'tcx>,
@@ -1025,7 +1208,7 @@ pub fn fold_variants<'blk, 'tcx, F>(bcx: Block<'blk, 'tcx>,
                 let rhs_val = C_integral(ll_inttype(ccx, ity), discr as u64, true);
                 AddCase(llswitch, rhs_val, variant_cx.llbb);
 
-                let fields = case.fields.iter().map(|&ty|
+                let fields = case.fields_in_memory_order().iter().map(|&ty|
                     type_of::type_of(bcx.ccx(), ty)).collect::<Vec<_>>();
                 let real_ty = Type::struct_(ccx, &fields[..], case.packed);
                 let variant_value = PointerCast(variant_cx, value, real_ty.ptr_to());
@@ -1121,7 +1304,7 @@ pub fn trans_const<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, r: &Repr<'tcx>, discr
                 C_null(type_of::sizing_type_of(ccx, nnty))
             }
         }
-        StructWrappedNullablePointer { ref nonnull, nndiscr, .. } => {
+        StructWrappedNullablePointer { ref nonnull, nndiscr, ref discrfield, niche, .. } => {
             if discr == nndiscr {
                 C_struct(ccx, &build_const_struct(ccx,
                                                  nonnull,
@@ -1133,10 +1316,25 @@ pub fn trans_const<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>, r: &Repr<'tcx>, discr
                     // field; see #8506.
                     C_null(type_of::sizing_type_of(ccx, ty))
                 }).collect::<Vec<ValueRef>>();
-                C_struct(ccx, &build_const_struct(ccx,
-                                                 nonnull,
-                                                 &vals[..]),
-                         false)
+                let llval = C_struct(ccx, &build_const_struct(ccx,
+                                                              nonnull,
+                                                              &vals[..]),
+                                     false);
+                if niche == Niche::null() {
+                    llval
+                } else {
+                    // All fields are null, so no padding was inserted and
+                    // the GEP indices of `discrfield` address the constant.
+                    let path = discrfield[1..].iter().map(|&i| i as c_uint)
+                                              .collect::<Vec<_>>();
+                    let llfield = const_get_elt(ccx, llval, &path);
+                    unsafe {
+                        llvm::LLVMConstInsertValue(llval,
+                                                   C_niche(val_ty(llfield), niche),
+                                                   path.as_ptr(),
+                                                   path.len() as c_uint)
+                    }
+                }
             }
         }
     }
@@ -1148,7 +1346,7 @@ fn compute_struct_field_offsets<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
     let mut offsets = vec!();
 
     let mut offset = 0;
-    for &ty in &st.fields {
+    for &ty in &st.fields_in_memory_order() {
         let llty = type_of::sizing_type_of(ccx, ty);
         if !st.packed {
             let type_align = type_of::align_of(ccx, ty);
@@ -1174,6 +1372,7 @@ fn build_const_struct<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                                 -> Vec<ValueRef> {
     assert_eq!(vals.len(), st.fields.len());
 
+    let vals = st.memory_order().iter().map(|&i| vals[i]).collect::<Vec<_>>();
     let target_offsets = compute_struct_field_offsets(ccx, st);
 
     // offset of current value
@@ -1237,16 +1436,20 @@ pub fn const_get_discrim(ccx: &CrateContext, r: &Repr, val: ValueRef) -> Disr {
 /// (Not to be confused with `common::const_get_elt`, which operates on
 /// raw LLVM-level structs and arrays.)
 pub fn const_get_field(ccx: &CrateContext, r: &Repr, val: ValueRef,
-                       _discr: Disr, ix: usize) -> ValueRef {
+                       discr: Disr, ix: usize) -> ValueRef {
     match *r {
         CEnum(..) => ccx.sess().bug("element access in C-like enum const"),
-        Univariant(..) => const_struct_field(ccx, val, ix),
-        General(..) => const_struct_field(ccx, val, ix + 1),
+        Univariant(ref st, _) => const_struct_field(ccx, val, st.memory_index[ix]),
+        General(_, ref cases, _) => {
+            const_struct_field(ccx, val, cases[discr as usize].memory_index[ix + 1])
+        }
         RawNullablePointer { .. } => {
             assert_eq!(ix, 0);
             val
         },
-        StructWrappedNullablePointer{ .. } => const_struct_field(ccx, val, ix)
+        StructWrappedNullablePointer{ ref nonnull, .. } => {
+            const_struct_field(ccx, val, nonnull.memory_index[ix])
+        }
     }
 }
 
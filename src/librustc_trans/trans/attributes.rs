@@ -12,6 +12,7 @@
 use libc::{c_uint, c_ulonglong};
 use llvm::{self, ValueRef, AttrHelper};
 use middle::ty::{self, ClosureTyper};
+use session::config::Sanitizer;
 use syntax::abi;
 use syntax::ast;
 pub use syntax::attr::InlineAttr;
@@ -34,6 +35,20 @@ pub fn split_stack(val: ValueRef, set: bool) {
     }
 }
 
+/// Mark LLVM function to have its stack frame probed if it may be larger than a page, see
+/// `back::write::probe_large_frames`.
+#[inline]
+pub fn probe_stack(val: ValueRef, set: bool) {
+    unsafe {
+        let attr = "rust-probestack\0".as_ptr() as *const _;
+        if set {
+            llvm::LLVMAddFunctionAttrString(val, llvm::FunctionIndex as c_uint, attr);
+        } else {
+            llvm::LLVMRemoveFunctionAttrString(val, llvm::FunctionIndex as c_uint, attr);
+        }
+    }
+}
+
 /// Mark LLVM function to use provided inline heuristic.
 #[inline]
 pub fn inline(val: ValueRef, inline: InlineAttr) {
@@ -91,6 +106,19 @@ pub fn set_optimize_for_size(val: ValueRef, optimize: bool) {
     }
 }
 
+/// Mark LLVM function to be instrumented by the given sanitizer.
+#[inline]
+pub fn sanitize(val: ValueRef, sanitizer: Sanitizer) {
+    let attr = match sanitizer {
+        Sanitizer::Address => llvm::SanitizeAddressAttribute,
+        Sanitizer::Thread => llvm::SanitizeThreadAttribute,
+        Sanitizer::Memory => llvm::SanitizeMemoryAttribute,
+        // The leak sanitizer only intercepts allocations at runtime.
+        Sanitizer::Leak => return,
+    };
+    attr.apply_llfn(llvm::FunctionIndex as c_uint, val);
+}
+
 /// Composite function which sets LLVM attributes for function depending on its AST (#[attribute])
 /// attributes.
 pub fn from_fn_attrs(ccx: &CrateContext, attrs: &[ast::Attribute], llfn: ValueRef) {
@@ -100,6 +128,7 @@ pub fn from_fn_attrs(ccx: &CrateContext, attrs: &[ast::Attribute], llfn: ValueRe
     for attr in attrs {
         if attr.check_name("no_stack_check") {
             split_stack(llfn, false);
+            probe_stack(llfn, false);
         } else if attr.check_name("cold") {
             unsafe {
                 llvm::LLVMAddFunctionAttribute(llfn,
@@ -52,8 +52,8 @@ use trans::callee;
 use trans::cleanup::CleanupMethods;
 use trans::cleanup;
 use trans::closure;
-use trans::common::{Block, C_bool, C_bytes_in_context, C_i32, C_int, C_integral};
-use trans::common::{C_null, C_struct_in_context, C_u64, C_u8, C_undef};
+use trans::common::{Block, C_array, C_bool, C_bytes_in_context, C_i32, C_int, C_integral};
+use trans::common::{C_null, C_struct, C_struct_in_context, C_u64, C_u8, C_undef};
 use trans::common::{CrateContext, FunctionContext};
 use trans::common::{Result, NodeIdAndSpan};
 use trans::common::{node_id_type, return_type_is_void};
@@ -73,10 +73,12 @@ use trans::machine;
 use trans::machine::{llsize_of, llsize_of_real};
 use trans::meth;
 use trans::monomorphize;
+use trans::pgo;
 use trans::tvec;
 use trans::type_::Type;
 use trans::type_of;
 use trans::type_of::*;
+use trans::type_sizes;
 use trans::value::Value;
 use util::common::indenter;
 use util::ppaux::{Repr, ty_to_string};
@@ -89,6 +91,7 @@ use std::ffi::{CStr, CString};
 use std::cell::{Cell, RefCell};
 use std::collections::HashSet;
 use std::mem;
+use std::ptr;
 use std::str;
 use std::{i8, i16, i32, i64};
 use syntax::abi::{Rust, RustCall, RustIntrinsic, Abi};
@@ -1203,7 +1206,8 @@ pub fn new_fn_ctxt<'a, 'tcx>(ccx: &'a CrateContext<'a, 'tcx>,
           ccx: ccx,
           debug_context: debug_context,
           scopes: RefCell::new(Vec::new()),
-          cfg: cfg
+          cfg: cfg,
+          pgo: RefCell::new(None),
     };
 
     if has_env {
@@ -1502,6 +1506,7 @@ pub fn trans_closure<'a, 'b, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
                       Some(body.span),
                       &arena);
     let mut bcx = init_function(&fcx, false, output_type);
+    pgo::start_function(bcx, body);
 
     // cleanup scope for the incoming arguments
     let fn_cleanup_debug_loc =
@@ -2122,6 +2127,55 @@ fn register_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
     llfn
 }
 
+/// Registers a global constructor that tells the runtime to abort on panic.
+///
+/// Code compiled with `-C panic=abort` has no landing pads, so a panic must
+/// never unwind through it. Not every crate type has an entry point to set
+/// this from (staticlibs, dylibs and cdylibs are entered through arbitrary
+/// exported functions), so every codegen unit of such a crate carries the
+/// constructor: whichever of its objects ends up linked runs it when the
+/// image is loaded, before any of its code can panic.
+fn register_abort_on_panic_ctor(ccx: &CrateContext) {
+    let abort_def_id = match ccx.tcx().lang_items.abort_on_panic_fn() {
+        Some(def_id) => def_id,
+        None => return,
+    };
+
+    let llfty = Type::func(&[], &Type::void(ccx));
+    let llfn = declare::define_cfn(ccx, "rust_abort_on_panic_ctor", llfty,
+                                   ty::mk_nil(ccx.tcx())).unwrap_or_else(|| {
+        ccx.sess().bug("symbol `rust_abort_on_panic_ctor` is already defined")
+    });
+    llvm::SetLinkage(llfn, llvm::InternalLinkage);
+
+    let bld = ccx.raw_builder();
+    unsafe {
+        let llbb = llvm::LLVMAppendBasicBlockInContext(ccx.llcx(), llfn,
+                                                       "top\0".as_ptr() as *const _);
+        llvm::LLVMPositionBuilderAtEnd(bld, llbb);
+
+        let abort_fn = if abort_def_id.krate == ast::LOCAL_CRATE {
+            get_item_val(ccx, abort_def_id.node)
+        } else {
+            let abort_fn_type = csearch::get_type(ccx.tcx(), abort_def_id).ty;
+            trans_external_path(ccx, abort_def_id, abort_fn_type)
+        };
+        llvm::LLVMBuildCall(bld, abort_fn, ptr::null(), 0, noname());
+        llvm::LLVMBuildRetVoid(bld);
+
+        // `llvm.global_ctors` is an appending array of `{ priority, fn }`;
+        // 65535 is the default priority.
+        let ctor = C_struct(ccx, &[C_i32(ccx, 65535), llfn], false);
+        let ctors = C_array(val_ty(ctor), &[ctor]);
+        let global = declare::define_global(ccx, "llvm.global_ctors",
+                                            val_ty(ctors)).unwrap_or_else(|| {
+            ccx.sess().bug("symbol `llvm.global_ctors` is already defined")
+        });
+        llvm::LLVMSetInitializer(global, ctors);
+        llvm::SetLinkage(global, llvm::AppendingLinkage);
+    }
+}
+
 pub fn is_entry_fn(sess: &Session, node_id: ast::NodeId) -> bool {
     match *sess.entry_fn.borrow() {
         Some((entry_id, _)) => node_id == entry_id,
@@ -2494,8 +2548,9 @@ pub fn crate_ctxt_to_encode_parms<'a, 'tcx>(cx: &'a SharedCrateContext<'tcx>,
 pub fn write_metadata(cx: &SharedCrateContext, krate: &ast::Crate) -> Vec<u8> {
     use flate;
 
+    // Only the outputs that other Rust crates can link against need metadata.
     let any_library = cx.sess().crate_types.borrow().iter().any(|ty| {
-        *ty != config::CrateTypeExecutable
+        *ty != config::CrateTypeExecutable && *ty != config::CrateTypeCdylib
     });
     if !any_library {
         return Vec::new()
@@ -2527,6 +2582,34 @@ pub fn write_metadata(cx: &SharedCrateContext, krate: &ast::Crate) -> Vec<u8> {
     return metadata;
 }
 
+/// The symbols of the crate's reachable `#[no_mangle]` or `#[export_name]`
+/// statics and extern functions, which are the only ones a cdylib exports.
+fn exported_symbols(cx: &SharedCrateContext) -> Vec<String> {
+    let mut symbols = Vec::new();
+    for id in cx.reachable() {
+        let item = match cx.tcx().map.find(*id) {
+            Some(ast_map::NodeItem(item)) => item,
+            _ => continue,
+        };
+        let exported = match item.node {
+            ast::ItemFn(_, _, abi, ref generics, _) => {
+                abi != Rust && !generics.is_type_parameterized()
+            }
+            ast::ItemStatic(..) => true,
+            _ => false,
+        };
+        let named = attr::contains_name(&item.attrs, "no_mangle") ||
+                    attr::contains_name(&item.attrs, "export_name");
+        if exported && named {
+            if let Some(sym) = cx.item_symbols().borrow().get(id) {
+                symbols.push(sym.clone());
+            }
+        }
+    }
+    symbols.sort();
+    symbols
+}
+
 /// Find any symbols that are defined in one compilation unit, but not declared
 /// in any other compilation unit.  Give these symbols internal linkage.
 fn internalize_symbols(cx: &SharedCrateContext, reachable: &HashSet<String>) {
@@ -2676,6 +2759,9 @@ pub fn trans_crate<'tcx>(analysis: ty::CrateAnalysis<'tcx>)
         if ccx.sess().opts.debuginfo != NoDebugInfo {
             debuginfo::finalize(&ccx);
         }
+        if ccx.sess().opts.cg.panic == config::PanicStrategy::Abort {
+            register_abort_on_panic_ctor(&ccx);
+        }
     }
 
     // Translate the metadata.
@@ -2704,6 +2790,9 @@ pub fn trans_crate<'tcx>(analysis: ty::CrateAnalysis<'tcx>)
             }
         }
     }
+    if shared_ccx.sess().opts.debugging_opts.print_type_sizes {
+        type_sizes::print_type_sizes(&shared_ccx);
+    }
     if shared_ccx.sess().count_llvm_insns() {
         for (k, v) in &*shared_ccx.stats().llvm_insns.borrow() {
             println!("{:7} {}", *v, *k);
@@ -2752,6 +2841,7 @@ pub fn trans_crate<'tcx>(analysis: ty::CrateAnalysis<'tcx>)
     };
     let formats = shared_ccx.tcx().dependency_formats.borrow().clone();
     let no_builtins = attr::contains_name(&krate.attrs, "no_builtins");
+    let exported_symbols = exported_symbols(&shared_ccx);
 
     let translation = CrateTranslation {
         modules: modules,
@@ -2759,6 +2849,7 @@ pub fn trans_crate<'tcx>(analysis: ty::CrateAnalysis<'tcx>)
         link: link_meta,
         metadata: metadata,
         reachable: reachable,
+        exported_symbols: exported_symbols,
         crate_formats: formats,
         no_builtins: no_builtins,
     };
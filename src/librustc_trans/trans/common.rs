@@ -34,6 +34,7 @@ use trans::debuginfo::{self, DebugLoc};
 use trans::declare;
 use trans::machine;
 use trans::monomorphize;
+use trans::pgo;
 use trans::type_::Type;
 use trans::type_of;
 use middle::traits;
@@ -429,6 +430,9 @@ pub struct FunctionContext<'a, 'tcx: 'a> {
     pub scopes: RefCell<Vec<cleanup::CleanupScope<'a, 'tcx>>>,
 
     pub cfg: Option<cfg::CFG>,
+
+    // Used and maintained by the pgo module.
+    pub pgo: RefCell<Option<pgo::FunctionCounters>>,
 }
 
 impl<'a, 'tcx> FunctionContext<'a, 'tcx> {
@@ -22,6 +22,7 @@ use trans::common::{ExternMap,BuilderRef_res};
 use trans::debuginfo;
 use trans::declare;
 use trans::monomorphize::MonoId;
+use trans::pgo;
 use trans::type_::{Type, TypeNames};
 use middle::subst::Substs;
 use middle::ty::{self, Ty};
@@ -33,6 +34,7 @@ use util::nodemap::{NodeMap, NodeSet, DefIdMap, FnvHashMap, FnvHashSet};
 
 use std::ffi::CString;
 use std::cell::{Cell, RefCell};
+use std::path::Path;
 use std::ptr;
 use std::rc::Rc;
 use syntax::ast;
@@ -71,6 +73,7 @@ pub struct SharedCrateContext<'tcx> {
     stats: Stats,
     check_overflow: bool,
     check_drop_flag_for_sanity: bool,
+    profile: Option<pgo::Profile>,
 
     available_monomorphizations: RefCell<FnvHashSet<String>>,
     available_drop_glues: RefCell<FnvHashMap<Ty<'tcx>, String>>,
@@ -250,6 +253,10 @@ impl<'tcx> SharedCrateContext<'tcx> {
             create_context_and_module(&tcx.sess, "metadata")
         };
 
+        let profile = tcx.sess.opts.cg.profile_use.as_ref().map(|path| {
+            pgo::Profile::open(&tcx.sess, Path::new(path))
+        });
+
         let mut shared_ccx = SharedCrateContext {
             local_ccxs: Vec::with_capacity(local_count),
             metadata_llmod: metadata_llmod,
@@ -274,6 +281,7 @@ impl<'tcx> SharedCrateContext<'tcx> {
             },
             check_overflow: check_overflow,
             check_drop_flag_for_sanity: check_drop_flag_for_sanity,
+            profile: profile,
             available_monomorphizations: RefCell::new(FnvHashSet()),
             available_drop_glues: RefCell::new(FnvHashMap()),
         };
@@ -732,6 +740,10 @@ impl<'b, 'tcx> CrateContext<'b, 'tcx> {
         // values.
         self.shared.check_drop_flag_for_sanity
     }
+
+    pub fn profile(&self) -> Option<&'b pgo::Profile> {
+        self.shared.profile.as_ref()
+    }
 }
 
 fn declare_intrinsic(ccx: &CrateContext, key: & &'static str) -> Option<ValueRef> {
@@ -870,6 +882,8 @@ fn declare_intrinsic(ccx: &CrateContext, key: & &'static str) -> Option<ValueRef
     ifn!("llvm.lifetime.start", fn(t_i64,i8p) -> void);
     ifn!("llvm.lifetime.end", fn(t_i64, i8p) -> void);
 
+    ifn!("llvm.instrprof.increment", fn(i8p, t_i64, t_i32, t_i32) -> void);
+
     ifn!("llvm.expect.i1", fn(i1, i1) -> i1);
     ifn!("llvm.assume", fn(i1) -> void);
 
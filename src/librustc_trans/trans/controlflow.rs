@@ -22,6 +22,7 @@ use trans::consts;
 use trans::debuginfo;
 use trans::debuginfo::{DebugLoc, ToDebugLoc};
 use trans::expr;
+use trans::pgo;
 use trans;
 use middle::ty;
 use util::ppaux::Repr;
@@ -205,22 +206,28 @@ pub fn trans_if<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     let cond_source_loc = cond.debug_loc();
 
     let next_bcx;
+    let else_llbb;
     match els {
         Some(elexpr) => {
             let else_bcx_in = bcx.fcx.new_id_block("else-block", elexpr.id);
             let else_bcx_out = expr::trans_into(else_bcx_in, &*elexpr, dest);
             next_bcx = bcx.fcx.join_blocks(if_id,
                                            &[then_bcx_out, else_bcx_out]);
-            CondBr(bcx, cond_val, then_bcx_in.llbb, else_bcx_in.llbb, cond_source_loc);
+            else_llbb = else_bcx_in.llbb;
         }
 
         None => {
             next_bcx = bcx.fcx.new_id_block("next-block", if_id);
             Br(then_bcx_out, next_bcx.llbb, DebugLoc::None);
-            CondBr(bcx, cond_val, then_bcx_in.llbb, next_bcx.llbb, cond_source_loc);
+            else_llbb = next_bcx.llbb;
         }
     }
 
+    let then_llbb = pgo::counted_edge(bcx.fcx, if_id, true, then_bcx_in.llbb);
+    let else_llbb = pgo::counted_edge(bcx.fcx, if_id, false, else_llbb);
+    CondBr(bcx, cond_val, then_llbb, else_llbb, cond_source_loc);
+    pgo::set_branch_weights(bcx, if_id);
+
     // Clear the source location because it is still set to whatever has been translated
     // right before.
     trans::debuginfo::clear_source_location(next_bcx.fcx);
@@ -267,7 +274,10 @@ pub fn trans_while<'blk, 'tcx>(bcx: Block<'blk, 'tcx>,
     let Result {bcx: cond_bcx_out, val: cond_val} =
         expr::trans(cond_bcx_in, cond).to_llbool();
 
-    CondBr(cond_bcx_out, cond_val, body_bcx_in.llbb, cleanup_llbb, cond.debug_loc());
+    let body_llbb = pgo::counted_edge(fcx, loop_expr.id, true, body_bcx_in.llbb);
+    let exit_llbb = pgo::counted_edge(fcx, loop_expr.id, false, cleanup_llbb);
+    CondBr(cond_bcx_out, cond_val, body_llbb, exit_llbb, cond.debug_loc());
+    pgo::set_branch_weights(cond_bcx_out, loop_expr.id);
 
     // loop body:
     let body_bcx_out = trans_block(body_bcx_in, body, expr::Ignore);
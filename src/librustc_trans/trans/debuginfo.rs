@@ -475,10 +475,9 @@ impl<'tcx> TypeMap<'tcx> {
                 }
             },
             ty::ty_closure(def_id, substs) => {
-                let typer = NormalizingClosureTyper::new(cx.tcx());
-                let closure_ty = typer.closure_type(def_id, substs);
                 self.get_unique_type_id_of_closure_type(cx,
-                                                        closure_ty,
+                                                        def_id,
+                                                        substs,
                                                         &mut unique_type_id);
             },
             _ => {
@@ -549,46 +548,31 @@ impl<'tcx> TypeMap<'tcx> {
         }
     }
 
+    // Closures are described by the values they capture, so two closures with
+    // the same signature still need distinct ids. The closure's def-id together
+    // with all the type parameters in scope identifies its set of upvars.
     fn get_unique_type_id_of_closure_type<'a>(&mut self,
                                               cx: &CrateContext<'a, 'tcx>,
-                                              closure_ty: ty::ClosureTy<'tcx>,
+                                              def_id: ast::DefId,
+                                              substs: &subst::Substs<'tcx>,
                                               unique_type_id: &mut String) {
-        let ty::ClosureTy { unsafety,
-                            ref sig,
-                            abi: _ } = closure_ty;
-
-        if unsafety == ast::Unsafety::Unsafe {
-            unique_type_id.push_str("unsafe ");
-        }
-
-        unique_type_id.push_str("|");
-
-        let sig = ty::erase_late_bound_regions(cx.tcx(), sig);
-
-        for &parameter_type in &sig.inputs {
-            let parameter_type_id =
-                self.get_unique_type_id_of_type(cx, parameter_type);
-            let parameter_type_id =
-                self.get_unique_type_id_as_string(parameter_type_id);
-            unique_type_id.push_str(&parameter_type_id[..]);
-            unique_type_id.push(',');
-        }
-
-        if sig.variadic {
-            unique_type_id.push_str("...");
-        }
-
-        unique_type_id.push_str("|->");
-
-        match sig.output {
-            ty::FnConverging(ret_ty) => {
-                let return_type_id = self.get_unique_type_id_of_type(cx, ret_ty);
-                let return_type_id = self.get_unique_type_id_as_string(return_type_id);
-                unique_type_id.push_str(&return_type_id[..]);
-            }
-            ty::FnDiverging => {
-                unique_type_id.push_str("!");
+        unique_type_id.push_str("closure ");
+        unique_type_id.push_str(cx.link_meta().crate_hash.as_str());
+        unique_type_id.push_str(&format!("/{:x}", def_id.node));
+
+        if !substs.types.is_empty() {
+            unique_type_id.push('<');
+
+            for &type_parameter in substs.types.iter() {
+                let param_type_id =
+                    self.get_unique_type_id_of_type(cx, type_parameter);
+                let param_type_id =
+                    self.get_unique_type_id_as_string(param_type_id);
+                unique_type_id.push_str(&param_type_id[..]);
+                unique_type_id.push(',');
             }
+
+            unique_type_id.push('>');
         }
     }
 
@@ -828,6 +812,62 @@ pub fn create_global_var_metadata(cx: &CrateContext,
     }
 }
 
+/// Creates debug information for the given vtable, which is for the
+/// given type.
+///
+/// The vtable is described as a global variable of an empty struct type whose
+/// `DW_AT_containing_type` is the concrete type the vtable was created for.
+/// This lets a debugger find the dynamic type behind a trait object by looking
+/// up the vtable pointer, and so downcast it.
+pub fn create_vtable_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
+                                        ty: Ty<'tcx>,
+                                        vtable: ValueRef) {
+    if cx.dbg_cx().is_none() {
+        return;
+    }
+
+    let type_metadata = type_metadata(cx, ty, codemap::DUMMY_SP);
+
+    let unique_type_id_str = {
+        let mut type_map = debug_context(cx).type_map.borrow_mut();
+        let unique_type_id = type_map.get_unique_type_id_of_type(cx, ty);
+        format!("vtable {}", &type_map.get_unique_type_id_as_string(unique_type_id)[..])
+    };
+
+    let name = CString::new("vtable").unwrap();
+    let linkage_name = CString::new("").unwrap();
+    let unique_type_id = CString::new(unique_type_id_str).unwrap();
+    unsafe {
+        let empty_array = create_DIArray(DIB(cx), &[]);
+
+        let vtable_type = llvm::LLVMDIBuilderCreateStructType(
+            DIB(cx),
+            UNKNOWN_SCOPE_METADATA,
+            name.as_ptr(),
+            UNKNOWN_FILE_METADATA,
+            UNKNOWN_LINE_NUMBER,
+            0,
+            0,
+            FLAGS_NONE,
+            ptr::null_mut(),
+            empty_array,
+            0,
+            type_metadata,
+            unique_type_id.as_ptr());
+
+        llvm::LLVMDIBuilderCreateStaticVariable(DIB(cx),
+                                                UNKNOWN_SCOPE_METADATA,
+                                                name.as_ptr(),
+                                                linkage_name.as_ptr(),
+                                                UNKNOWN_FILE_METADATA,
+                                                UNKNOWN_LINE_NUMBER,
+                                                vtable_type,
+                                                true,
+                                                vtable,
+                                                ptr::null_mut());
+    }
+}
+
 /// Creates debug information for the given local variable.
 ///
 /// This function assumes that there's a datum for each pattern component of the
@@ -2010,6 +2050,10 @@ impl<'tcx> RecursiveTypeDescription<'tcx> {
 // Creates MemberDescriptions for the fields of a struct
 struct StructMemberDescriptionFactory<'tcx> {
     fields: Vec<ty::field<'tcx>>,
+    // The LLVM type of the struct and, for each field, its index in it;
+    // fields may be laid out in a different order than they are declared.
+    llvm_type: Type,
+    memory_index: Vec<usize>,
     is_simd: bool,
     span: Span,
 }
@@ -2038,7 +2082,8 @@ impl<'tcx> StructMemberDescriptionFactory<'tcx> {
                 assert!(field_size != 0xdeadbeef);
                 FixedMemberOffset { bytes: i * field_size }
             } else {
-                ComputedMemberOffset
+                let offset = machine::llelement_offset(cx, self.llvm_type, self.memory_index[i]);
+                FixedMemberOffset { bytes: offset as usize }
             };
 
             MemberDescription {
@@ -2079,6 +2124,11 @@ fn prepare_struct_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         field.mt.ty = monomorphize::normalize_associated_type(cx.tcx(), &field.mt.ty);
     }
 
+    let memory_index = match *adt::represent_type(cx, struct_type) {
+        adt::Univariant(ref st, _) => st.memory_index.clone(),
+        _ => cx.sess().span_bug(span, "struct not represented as a univariant"),
+    };
+
     create_and_register_recursive_type_forward_declaration(
         cx,
         struct_type,
@@ -2087,6 +2137,8 @@ fn prepare_struct_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         struct_llvm_type,
         StructMDF(StructMemberDescriptionFactory {
             fields: fields,
+            llvm_type: struct_llvm_type,
+            memory_index: memory_index,
             is_simd: ty::type_is_simd(cx.tcx(), struct_type),
             span: span,
         })
@@ -2094,6 +2146,76 @@ fn prepare_struct_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
 }
 
 
+//=-----------------------------------------------------------------------------
+// Closures
+//=-----------------------------------------------------------------------------
+
+// Closures are described as structs with one member per captured variable,
+// named after that variable. By-reference captures show up as reference
+// members, so the debugger can follow them to the captured value.
+fn prepare_closure_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
+                                      closure_type: Ty<'tcx>,
+                                      def_id: ast::DefId,
+                                      substs: &subst::Substs<'tcx>,
+                                      unique_type_id: UniqueTypeId,
+                                      span: Span)
+                                      -> RecursiveTypeDescription<'tcx> {
+    let closure_name = compute_debuginfo_type_name(cx, closure_type, false);
+    let closure_llvm_type = type_of::type_of(cx, closure_type);
+
+    let closure_metadata_stub = create_struct_stub(cx,
+                                                   closure_llvm_type,
+                                                   &closure_name[..],
+                                                   unique_type_id,
+                                                   UNKNOWN_SCOPE_METADATA);
+
+    let typer = NormalizingClosureTyper::new(cx.tcx());
+    let upvars = typer.closure_upvars(def_id, substs).unwrap();
+
+    let fields = upvars.iter().map(|upvar| {
+        let var_id = upvar.def.def_id().node;
+        ty::field {
+            name: upvar_name(cx, var_id),
+            mt: ty::mt { ty: upvar.ty, mutbl: ast::MutImmutable },
+        }
+    }).collect();
+
+    let memory_index = match *adt::represent_type(cx, closure_type) {
+        adt::Univariant(ref st, _) => st.memory_index.clone(),
+        _ => cx.sess().span_bug(span, "closure not represented as a univariant"),
+    };
+
+    return create_and_register_recursive_type_forward_declaration(
+        cx,
+        closure_type,
+        unique_type_id,
+        closure_metadata_stub,
+        closure_llvm_type,
+        StructMDF(StructMemberDescriptionFactory {
+            fields: fields,
+            llvm_type: closure_llvm_type,
+            memory_index: memory_index,
+            is_simd: false,
+            span: span,
+        })
+    );
+
+    // Upvars always refer to a binding in an enclosing function, which is
+    // either a local or a function argument.
+    fn upvar_name(cx: &CrateContext, var_id: ast::NodeId) -> ast::Name {
+        match cx.tcx().map.find(var_id) {
+            Some(ast_map::NodeLocal(pat)) | Some(ast_map::NodeArg(pat)) => {
+                match pat.node {
+                    ast::PatIdent(_, ref path1, _) => path1.node.name,
+                    _ => special_idents::unnamed_field.name,
+                }
+            }
+            _ => special_idents::unnamed_field.name,
+        }
+    }
+}
+
+
 //=-----------------------------------------------------------------------------
 // Tuples
 //=-----------------------------------------------------------------------------
@@ -2158,6 +2280,15 @@ fn prepare_tuple_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
 // the members of this union; so for every variant of the given enum, this factory
 // will produce one MemberDescription (all with no name and a fixed offset of
 // zero bytes).
+//
+// Enums with a discriminant of their own also get a last union member named
+// `discriminant`, whose type enumerates the variant names, so that a debugger
+// without the pretty printers in src/etc can still tell which variant a value
+// is. DWARF can describe the variants themselves natively, with a
+// DW_TAG_variant_part whose DW_TAG_variant children carry their discriminant
+// values, but the DIBuilder of the LLVM version used here has no way to emit
+// those. Enums with a null pointer optimized representation have no
+// discriminant to describe, and keep their `RUST$ENCODED$ENUM` member names.
 struct EnumMemberDescriptionFactory<'tcx> {
     enum_type: Ty<'tcx>,
     type_rep: Rc<adt::Repr<'tcx>>,
@@ -2172,11 +2303,11 @@ impl<'tcx> EnumMemberDescriptionFactory<'tcx> {
     fn create_member_descriptions<'a>(&self, cx: &CrateContext<'a, 'tcx>)
                                       -> Vec<MemberDescription> {
         match *self.type_rep {
-            adt::General(_, ref struct_defs, _) => {
-                let discriminant_info = RegularDiscriminant(self.discriminant_type_metadata
-                    .expect(""));
+            adt::General(inttype, ref struct_defs, _) => {
+                let discriminant_type_metadata = self.discriminant_type_metadata.expect("");
+                let discriminant_info = RegularDiscriminant(discriminant_type_metadata);
 
-                struct_defs
+                let mut member_descriptions: Vec<_> = struct_defs
                     .iter()
                     .enumerate()
                     .map(|(i, struct_def)| {
@@ -2205,7 +2336,17 @@ impl<'tcx> EnumMemberDescriptionFactory<'tcx> {
                             offset: FixedMemberOffset { bytes: 0 },
                             flags: FLAGS_NONE
                         }
-                    }).collect()
+                    }).collect();
+
+                // The discriminant is the first field of every variant.
+                member_descriptions.push(MemberDescription {
+                    name: "discriminant".to_string(),
+                    llvm_type: adt::ll_inttype(cx, inttype),
+                    type_metadata: discriminant_type_metadata,
+                    offset: FixedMemberOffset { bytes: 0 },
+                    flags: FLAGS_NONE
+                });
+                member_descriptions
             },
             adt::Univariant(ref struct_def, _) => {
                 assert!(self.variants.len() <= 1);
@@ -2313,7 +2454,8 @@ impl<'tcx> EnumMemberDescriptionFactory<'tcx> {
             },
             adt::StructWrappedNullablePointer { nonnull: ref struct_def,
                                                 nndiscr,
-                                                ref discrfield, ..} => {
+                                                ref discrfield,
+                                                niche, ..} => {
                 // Create a description of the non-null variant
                 let (variant_type_metadata, variant_llvm_type, member_description_factory) =
                     describe_enum_variant(cx,
@@ -2336,13 +2478,20 @@ impl<'tcx> EnumMemberDescriptionFactory<'tcx> {
                 // member's name.
                 let null_variant_index = (1 - nndiscr) as usize;
                 let null_variant_name = token::get_name((*self.variants)[null_variant_index].name);
-                let discrfield = discrfield.iter()
-                                           .skip(1)
+                let discrfield = debuginfo_discr_path(cx, &struct_def.fields, &discrfield[1..])
+                                           .iter()
                                            .map(|x| x.to_string())
                                            .collect::<Vec<_>>().connect("$");
-                let union_member_name = format!("RUST$ENCODED$ENUM${}${}",
-                                                discrfield,
-                                                null_variant_name);
+                // A null niche keeps the old encoding, which debuggers know;
+                // any other value is spelled out.
+                let union_member_name = if niche.value == 0 {
+                    format!("RUST$ENCODED$ENUM${}${}", discrfield, null_variant_name)
+                } else {
+                    format!("RUST$ENCODED$NICHE${}${}${}",
+                            niche.value,
+                            discrfield,
+                            null_variant_name)
+                };
 
                 // Create the (singleton) list of descriptions of union members.
                 vec![
@@ -2392,6 +2541,58 @@ enum EnumDiscriminantInfo {
     NoDiscriminant
 }
 
+// Translates the LLVM indices of a `discrfield` path into indices of the
+// members the debugger sees, starting from a struct with the given fields.
+// The two differ when struct fields are reordered, and for nested enums,
+// which debuggers see as unions of their variants.
+fn debuginfo_discr_path<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
+                                  fields: &[Ty<'tcx>],
+                                  discrfield: &[usize])
+                                  -> Vec<usize> {
+    if discrfield.is_empty() {
+        return Vec::new();
+    }
+    let mut path = vec![discrfield[0]];
+    let mut ty = fields[discrfield[0]];
+    for (i, &ix) in discrfield.iter().enumerate().skip(1) {
+        match ty.sty {
+            ty::ty_struct(def_id, substs) => {
+                let source_ix = match *adt::represent_type(cx, ty) {
+                    adt::Univariant(ref st, _) => st.memory_order()[ix],
+                    _ => ix,
+                };
+                let field = &ty::lookup_struct_fields(cx.tcx(), def_id)[source_ix];
+                let field_ty = ty::lookup_field_type(cx.tcx(), def_id, field.id, substs);
+                ty = monomorphize::normalize_associated_type(cx.tcx(), &field_ty);
+                path.push(source_ix);
+            }
+            ty::ty_tup(ref tys) => {
+                ty = tys[ix];
+                path.push(ix);
+            }
+            ty::ty_vec(ety, _) => {
+                ty = ety;
+                path.push(ix);
+            }
+            ty::ty_enum(..) => {
+                match *adt::represent_type(cx, ty) {
+                    // The discriminant is the first member of every variant.
+                    adt::General(..) => path.extend([0, 0].iter().cloned()),
+                    adt::StructWrappedNullablePointer { ref nonnull, .. } => {
+                        path.push(0);
+                        path.extend(debuginfo_discr_path(cx, &nonnull.fields, &discrfield[i..]));
+                    }
+                    _ => cx.sess().bug("unexpected enum in discriminant path"),
+                }
+                return path;
+            }
+            // Fat pointers
+            _ => path.push(ix),
+        }
+    }
+    path
+}
+
 // Returns a tuple of (1) type_metadata_stub of the variant, (2) the llvm_type
 // of the variant, and (3) a MemberDescriptionFactory for producing the
 // descriptions of the fields of the variant. This is a rudimentary version of a
@@ -2405,7 +2606,7 @@ fn describe_enum_variant<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                                    span: Span)
                                    -> (DICompositeType, Type, MemberDescriptionFactory<'tcx>) {
     let variant_llvm_type =
-        Type::struct_(cx, &struct_def.fields
+        Type::struct_(cx, &struct_def.fields_in_memory_order()
                                     .iter()
                                     .map(|&t| type_of::type_of(cx, t))
                                     .collect::<Vec<_>>()
@@ -2899,9 +3100,12 @@ fn trait_pointer_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                                     trait_object_type: Option<Ty<'tcx>>,
                                     unique_type_id: UniqueTypeId)
                                     -> DIType {
-    // The implementation provided here is a stub. It makes sure that the trait
-    // type is assigned the correct name, size, namespace, and source location.
-    // But it does not describe the trait's methods.
+    // The implementation provided here makes sure that the trait type is
+    // assigned the correct name, size, namespace, and source location, and
+    // that actual trait pointers expose their data and vtable pointers. It
+    // does not describe the trait's methods. The concrete type behind a trait
+    // object can be found through the debuginfo attached to its vtable, see
+    // `create_vtable_metadata()`.
 
     let def_id = match trait_type.sty {
         ty::ty_trait(ref data) => data.principal_def_id(),
@@ -2913,6 +3117,30 @@ fn trait_pointer_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
         }
     };
 
+    let member_descriptions = if trait_object_type.is_some() {
+        let data_ptr_type = ty::mk_mut_ptr(cx.tcx(), cx.tcx().types.u8);
+        let vtable_type = ty::mk_imm_ptr(cx.tcx(), cx.tcx().types.usize);
+
+        vec![
+            MemberDescription {
+                name: "pointer".to_string(),
+                llvm_type: Type::i8p(cx),
+                type_metadata: type_metadata(cx, data_ptr_type, codemap::DUMMY_SP),
+                offset: ComputedMemberOffset,
+                flags: FLAGS_NONE,
+            },
+            MemberDescription {
+                name: "vtable".to_string(),
+                llvm_type: Type::vtable_ptr(cx),
+                type_metadata: type_metadata(cx, vtable_type, codemap::DUMMY_SP),
+                offset: ComputedMemberOffset,
+                flags: FLAGS_NONE,
+            },
+        ]
+    } else {
+        Vec::new()
+    };
+
     let trait_object_type = trait_object_type.unwrap_or(trait_type);
     let trait_type_name =
         compute_debuginfo_type_name(cx, trait_object_type, false);
@@ -2925,7 +3153,7 @@ fn trait_pointer_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
                             trait_llvm_type,
                             &trait_type_name[..],
                             unique_type_id,
-                            &[],
+                            &member_descriptions[..],
                             containing_scope,
                             UNKNOWN_FILE_METADATA,
                             codemap::DUMMY_SP)
@@ -3028,9 +3256,12 @@ fn type_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
             subroutine_type_metadata(cx, unique_type_id, &barefnty.sig, usage_site_span)
         }
         ty::ty_closure(def_id, substs) => {
-            let typer = NormalizingClosureTyper::new(cx.tcx());
-            let sig = typer.closure_type(def_id, substs).sig;
-            subroutine_type_metadata(cx, unique_type_id, &sig, usage_site_span)
+            prepare_closure_metadata(cx,
+                                     t,
+                                     def_id,
+                                     substs,
+                                     unique_type_id,
+                                     usage_site_span).finalize(cx)
         }
         ty::ty_struct(def_id, substs) => {
             prepare_struct_metadata(cx,
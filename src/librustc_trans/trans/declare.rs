@@ -82,6 +82,14 @@ pub fn declare_fn(ccx: &CrateContext, name: &str, callconv: llvm::CallConv, ty:
     if ccx.is_split_stack_supported() && !ccx.sess().opts.cg.no_stack_check {
         attributes::split_stack(llfn, true);
     }
+
+    if ccx.sess().target.target.options.stack_probes && !ccx.sess().opts.cg.no_stack_check {
+        attributes::probe_stack(llfn, true);
+    }
+
+    if let Some(sanitizer) = ccx.sess().opts.debugging_opts.sanitizer {
+        attributes::sanitize(llfn, sanitizer);
+    }
     llfn
 }
 
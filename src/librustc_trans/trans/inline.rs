@@ -18,6 +18,7 @@ use middle::ty;
 
 use syntax::ast;
 use syntax::ast_util::local_def;
+use syntax::attr;
 
 fn instantiate_inline(ccx: &CrateContext, fn_id: ast::DefId)
     -> Option<ast::DefId> {
@@ -60,6 +61,12 @@ fn instantiate_inline(ccx: &CrateContext, fn_id: ast::DefId)
                         // Generics have no symbol, so they can't be given any
                         // linkage.
                         None
+                    } else if !attr::requests_inline(&item.attrs) {
+                        // The body was exported only because it is small
+                        // (see `reachable::is_auto_inlinable`), so call the
+                        // symbol of the original crate where LLVM doesn't
+                        // inline it, rather than duplicating the code.
+                        Some(AvailableExternallyLinkage)
                     } else {
                         if ccx.sess().opts.cg.codegen_units == 1 {
                             // We could use AvailableExternallyLinkage here,
@@ -159,8 +166,14 @@ fn instantiate_inline(ccx: &CrateContext, fn_id: ast::DefId)
                              empty_substs,
                              impl_item.id,
                              &[]);
-                    // Use InternalLinkage so LLVM can optimize more aggressively.
-                    SetLinkage(llfn, InternalLinkage);
+                    if attr::requests_inline(&impl_item.attrs) {
+                        // Use InternalLinkage so LLVM can optimize more aggressively.
+                        SetLinkage(llfn, InternalLinkage);
+                    } else {
+                        // As for functions, a method exported only because
+                        // it is small keeps using the original symbol.
+                        SetLinkage(llfn, AvailableExternallyLinkage);
+                    }
                 }
             }
 
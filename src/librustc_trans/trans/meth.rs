@@ -27,7 +27,7 @@ use trans::closure;
 use trans::common::*;
 use trans::consts;
 use trans::datum::*;
-use trans::debuginfo::DebugLoc;
+use trans::debuginfo::{self, DebugLoc};
 use trans::declare;
 use trans::expr::SaveIn;
 use trans::expr;
@@ -758,6 +758,7 @@ pub fn get_vtable<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
     ].into_iter().chain(methods).collect();
 
     let vtable = consts::addr_of(ccx, C_struct(ccx, &components, false), "vtable");
+    debuginfo::create_vtable_metadata(ccx, trait_ref.self_ty(), vtable);
 
     ccx.vtables().borrow_mut().insert(trait_ref, vtable);
     vtable
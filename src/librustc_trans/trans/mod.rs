@@ -54,9 +54,11 @@ mod machine;
 mod _match;
 mod meth;
 mod monomorphize;
+mod pgo;
 mod tvec;
 mod type_;
 mod type_of;
+mod type_sizes;
 mod value;
 
 #[derive(Copy, Clone)]
@@ -74,6 +76,8 @@ pub struct CrateTranslation {
     pub link: LinkMeta,
     pub metadata: Vec<u8>,
     pub reachable: Vec<String>,
+    /// The symbols exported from a cdylib.
+    pub exported_symbols: Vec<String>,
     pub crate_formats: dependency_format::Dependencies,
     pub no_builtins: bool,
 }
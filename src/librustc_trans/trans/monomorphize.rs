@@ -8,7 +8,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use back::link::exported_name;
+use back::link::exported_name_with_type_params;
 use session;
 use llvm::ValueRef;
 use llvm;
@@ -130,9 +130,10 @@ pub fn monomorphic_fn<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
         hash_id.hash(&mut state);
         mono_ty.hash(&mut state);
 
-        hash = format!("h{}", state.finish());
+        hash = format!("h{:016x}", state.finish());
         ccx.tcx().map.with_path(fn_id.node, |path| {
-            exported_name(path, &hash[..])
+            exported_name_with_type_params(ccx.tcx(), path, psubsts.types.as_slice(),
+                                           &hash[..])
         })
     };
 
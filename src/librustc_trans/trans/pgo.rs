@@ -0,0 +1,261 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+//! Profile-guided optimization, for `-C profile-generate` and
+//! `-C profile-use`.
+//!
+//! The LLVM we build against cannot instrument IR by itself, so, as in
+//! clang, the counters are placed here, with calls to
+//! `llvm.instrprof.increment`, and lowered by LLVM's `instrprof` pass in
+//! `back::write`. Every function has a counter for its entry, and two for
+//! each `if` and `while` in its body: one on the edge into the `then` block
+//! or the loop body, and one on the edge past it. `match` arms are not
+//! counted.
+//!
+//! The counters of a function are numbered by walking its body in order,
+//! before it is translated, so they come out the same whether the crate is
+//! built to record a profile or to use one. Closures and nested items are
+//! not walked; they have counters of their own. The hash that LLVM keeps
+//! with the counters of each function is just how many there are, which
+//! catches most edits to a function between the two builds. A profile whose
+//! counters don't match a function is ignored.
+//!
+//! With `-C profile-use`, a function that the profile says never ran is
+//! marked cold, and each counted branch gets `!prof` branch weights.
+
+use llvm::{self, BasicBlockRef, ProfileReaderRef, ValueRef};
+use back::write;
+use session::Session;
+use trans::build::*;
+use trans::common::*;
+use trans::consts;
+use trans::debuginfo::DebugLoc;
+use trans::declare;
+use trans::type_::Type;
+use util::common::path2cstr;
+use util::nodemap::NodeMap;
+
+use libc::{c_char, c_uint, size_t};
+use std::cmp;
+use std::ffi::{CStr, CString};
+use std::path::Path;
+use std::u32;
+use syntax::ast;
+use syntax::visit::{self, Visitor};
+
+/// A profile merged by `llvm-profdata`, read for `-C profile-use`.
+pub struct Profile {
+    reader: ProfileReaderRef,
+}
+
+impl Profile {
+    pub fn open(sess: &Session, path: &Path) -> Profile {
+        let reader = unsafe { llvm::LLVMRustOpenProfile(path2cstr(path).as_ptr()) };
+        if reader.is_null() {
+            write::llvm_err(sess.diagnostic().handler(),
+                            format!("failed to read profile {}", path.display()));
+        }
+        Profile { reader: reader }
+    }
+
+    /// The counts recorded for the function with the given symbol, if the
+    /// profile has exactly `len` of them.
+    fn counts(&self, name: &str, len: usize) -> Option<Vec<u64>> {
+        let name = CString::new(name).unwrap();
+        let mut counts = vec![0; len];
+        let found = unsafe {
+            llvm::LLVMRustGetProfileCounts(self.reader, name.as_ptr(), len as u64,
+                                           counts.as_mut_ptr(), len as size_t)
+        };
+        if found == len as i64 { Some(counts) } else { None }
+    }
+}
+
+impl Drop for Profile {
+    fn drop(&mut self) {
+        unsafe { llvm::LLVMRustFreeProfile(self.reader); }
+    }
+}
+
+/// The counters of the function being translated.
+pub struct FunctionCounters {
+    // `__llvm_profile_name_<symbol>`, when recording a profile.
+    llname: Option<ValueRef>,
+    num_counters: u32,
+    // The first of the two counters of each `if` and `while`.
+    regions: NodeMap<u32>,
+    // The counts recorded for the function, when using a profile.
+    counts: Option<Vec<u64>>,
+}
+
+struct RegionNumberer {
+    next: u32,
+    regions: NodeMap<u32>,
+}
+
+impl<'v> Visitor<'v> for RegionNumberer {
+    fn visit_expr(&mut self, e: &'v ast::Expr) {
+        match e.node {
+            ast::ExprIf(..) | ast::ExprWhile(..) => {
+                self.regions.insert(e.id, self.next);
+                self.next += 2;
+            }
+            ast::ExprClosure(..) => return,
+            _ => {}
+        }
+        visit::walk_expr(self, e);
+    }
+
+    fn visit_item(&mut self, _: &'v ast::Item) {}
+}
+
+/// Numbers the counters of a function about to be translated and, when
+/// recording a profile, counts its entry. Called once the entry block of
+/// the function exists.
+pub fn start_function<'blk, 'tcx>(bcx: Block<'blk, 'tcx>, body: &ast::Block) {
+    let fcx = bcx.fcx;
+    let ccx = fcx.ccx;
+    let generate = ccx.sess().opts.cg.profile_generate.is_some();
+    if !generate && ccx.profile().is_none() {
+        return;
+    }
+
+    let mut numberer = RegionNumberer { next: 1, regions: NodeMap() };
+    numberer.visit_block(body);
+
+    let name = unsafe {
+        let name = CStr::from_ptr(llvm::LLVMGetValueName(fcx.llfn)).to_bytes();
+        String::from_utf8_lossy(name).into_owned()
+    };
+
+    let mut counters = FunctionCounters {
+        llname: None,
+        num_counters: numberer.next,
+        regions: numberer.regions,
+        counts: None,
+    };
+
+    if generate {
+        counters.llname = Some(define_name(ccx, &name));
+    } else if let Some(profile) = ccx.profile() {
+        counters.counts = profile.counts(&name, counters.num_counters as usize);
+        if counters.counts.as_ref().map_or(false, |counts| counts[0] == 0) {
+            unsafe {
+                llvm::LLVMAddFunctionAttribute(fcx.llfn,
+                                               llvm::FunctionIndex as c_uint,
+                                               llvm::ColdAttribute as u64)
+            }
+        }
+    }
+
+    increment(bcx, &counters, 0);
+    *fcx.pgo.borrow_mut() = Some(counters);
+}
+
+/// The private global holding the name that the profile records the
+/// counters of the function under.
+fn define_name(ccx: &CrateContext, name: &str) -> ValueRef {
+    let sym = format!("__llvm_profile_name_{}", name);
+    unsafe {
+        let llname = llvm::LLVMConstStringInContext(ccx.llcx(),
+                                                    name.as_ptr() as *const c_char,
+                                                    name.len() as c_uint,
+                                                    llvm::True);
+        let g = declare::define_global(ccx, &sym[..], val_ty(llname)).unwrap_or_else(|| {
+            ccx.sess().bug(&format!("symbol `{}` is already defined", sym));
+        });
+        llvm::LLVMSetInitializer(g, llname);
+        llvm::LLVMSetGlobalConstant(g, llvm::True);
+        llvm::SetLinkage(g, llvm::PrivateLinkage);
+        g
+    }
+}
+
+fn increment(bcx: Block, counters: &FunctionCounters, index: u32) {
+    let llname = match counters.llname {
+        Some(llname) => llname,
+        None => return,
+    };
+    let ccx = bcx.ccx();
+    let increment = ccx.get_intrinsic(&"llvm.instrprof.increment");
+    Call(bcx, increment,
+         &[consts::ptrcast(llname, Type::i8p(ccx)),
+           C_u64(ccx, counters.num_counters as u64),
+           C_u32(ccx, counters.num_counters),
+           C_u32(ccx, index)],
+         None, DebugLoc::None);
+}
+
+/// The block to branch to in order to reach `target` along one edge of the
+/// `if` or `while` expression `id`: the edge into its `then` block or body
+/// if `taken`, the edge past it otherwise. When recording a profile, this
+/// is a new block that counts the edge.
+pub fn counted_edge<'blk, 'tcx>(fcx: &'blk FunctionContext<'blk, 'tcx>,
+                                id: ast::NodeId,
+                                taken: bool,
+                                target: BasicBlockRef)
+                                -> BasicBlockRef {
+    let pgo = fcx.pgo.borrow();
+    let counters = match *pgo {
+        Some(ref counters) if counters.llname.is_some() => counters,
+        _ => return target,
+    };
+    let index = match counters.regions.get(&id) {
+        Some(&first) => if taken { first } else { first + 1 },
+        None => return target,
+    };
+    let bcx = fcx.new_temp_block(if taken { "pgo-taken" } else { "pgo-not-taken" });
+    increment(bcx, counters, index);
+    Br(bcx, target, DebugLoc::None);
+    bcx.llbb
+}
+
+/// Gives the conditional branch that ends `bcx`, for the `if` or `while`
+/// expression `id`, the weights of its two edges recorded in the profile.
+pub fn set_branch_weights(bcx: Block, id: ast::NodeId) {
+    if bcx.unreachable.get() {
+        return;
+    }
+    let pgo = bcx.fcx.pgo.borrow();
+    let (first, counts) = match *pgo {
+        Some(FunctionCounters { counts: Some(ref counts), ref regions, .. }) => {
+            match regions.get(&id) {
+                Some(&first) => (first as usize, counts),
+                None => return,
+            }
+        }
+        _ => return,
+    };
+
+    let ccx = bcx.ccx();
+    // The weights are 32 bits, and must not be zero. Scale them all
+    // down together so that the larger one fits.
+    let max = cmp::max(counts[first], counts[first + 1]);
+    let scale = max / (u32::MAX as u64 - 1) + 1;
+    let weight = |count: u64| C_u32(ccx, (count / scale + 1) as u32);
+    unsafe {
+        let key = "prof";
+        let kind = llvm::LLVMGetMDKindIDInContext(ccx.llcx(),
+                                                  key.as_ptr() as *const c_char,
+                                                  key.len() as c_uint);
+        let name = "branch_weights";
+        let vals = [llvm::LLVMMDStringInContext(ccx.llcx(),
+                                                name.as_ptr() as *const c_char,
+                                                name.len() as c_uint),
+                    weight(counts[first]),
+                    weight(counts[first + 1])];
+        let node = llvm::LLVMMDNodeInContext(ccx.llcx(), vals.as_ptr(), vals.len() as c_uint);
+        let br = llvm::LLVMGetBasicBlockTerminator(bcx.llbb);
+        if !br.is_null() {
+            llvm::LLVMSetMetadata(br, kind, node);
+        }
+    }
+}
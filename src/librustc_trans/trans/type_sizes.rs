@@ -0,0 +1,254 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Support for `-Z print-type-sizes`, which reports the layout chosen for
+//! every monomorphized struct, enum and closure type: its size and
+//! alignment, the size of the discriminant, the offset and size of each
+//! field, and how many bytes are lost to padding.
+//!
+//! The layouts are taken from the `adt::Repr`s built while translating the
+//! crate, so only types that trans actually needed are reported.
+
+use middle::ty::{self, Ty};
+use trans::adt;
+use trans::common::*;
+use trans::context::SharedCrateContext;
+use trans::machine;
+use trans::type_::Type;
+use trans::type_of;
+use util::nodemap::FnvHashMap;
+use util::ppaux::ty_to_string;
+
+use serialize::json;
+use std::cmp::Ordering;
+use syntax::parse::token::{self, special_idents};
+
+#[derive(RustcEncodable)]
+struct FieldLayout {
+    name: String,
+    offset: u64,
+    size: u64,
+}
+
+#[derive(RustcEncodable)]
+struct VariantLayout {
+    // `None` for structs, closures and enums laid out like a struct.
+    name: Option<String>,
+    fields: Vec<FieldLayout>,
+}
+
+#[derive(RustcEncodable)]
+struct TypeLayout {
+    name: String,
+    kind: &'static str,
+    size: u64,
+    align: u64,
+    discriminant_size: u64,
+    padding: u64,
+    variants: Vec<VariantLayout>,
+}
+
+/// Prints the layout of every type represented in any of the crate's
+/// codegen units, largest first. With `-Z print-type-sizes-json`, each
+/// type is printed as a JSON object on a line of its own.
+pub fn print_type_sizes(shared_ccx: &SharedCrateContext) {
+    let mut layouts = FnvHashMap();
+    for ccx in shared_ccx.iter() {
+        for (&t, repr) in &*ccx.adt_reprs().borrow() {
+            if layouts.contains_key(&t) {
+                continue;
+            }
+            if let Some(layout) = type_layout(&ccx, t, repr) {
+                layouts.insert(t, layout);
+            }
+        }
+    }
+
+    let mut layouts = layouts.into_iter().map(|(_, layout)| layout).collect::<Vec<_>>();
+    layouts.sort_by(|a, b| {
+        match b.size.cmp(&a.size) {
+            Ordering::Equal => a.name.cmp(&b.name),
+            ord => ord,
+        }
+    });
+
+    let as_json = shared_ccx.sess().opts.debugging_opts.print_type_sizes_json;
+    for layout in &layouts {
+        if as_json {
+            println!("{}", json::as_json(layout));
+        } else {
+            print_layout(layout);
+        }
+    }
+}
+
+fn print_layout(layout: &TypeLayout) {
+    println!("{} `{}`: {} bytes, alignment: {} bytes, padding: {} bytes",
+             layout.kind, layout.name, layout.size, layout.align, layout.padding);
+    if layout.discriminant_size != 0 {
+        println!("    discriminant: {} bytes", layout.discriminant_size);
+    }
+    for variant in &layout.variants {
+        let indent = match variant.name {
+            Some(ref name) => {
+                println!("    variant `{}`", name);
+                "        "
+            }
+            None => "    ",
+        };
+        for field in &variant.fields {
+            println!("{}field `{}`: {} bytes, offset: {} bytes",
+                     indent, field.name, field.size, field.offset);
+        }
+    }
+}
+
+fn type_layout<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
+                         t: Ty<'tcx>,
+                         repr: &adt::Repr<'tcx>)
+                         -> Option<TypeLayout> {
+    let kind = match t.sty {
+        ty::ty_struct(..) => "struct",
+        ty::ty_enum(..) => "enum",
+        ty::ty_closure(..) => "closure",
+        _ => return None,
+    };
+    if ty::type_has_params(t) || !type_is_sized(ccx.tcx(), t) {
+        return None;
+    }
+
+    let llty = type_of::sizing_type_of(ccx, t);
+    let size = machine::llsize_of_alloc(ccx, llty);
+    let align = machine::llalign_of_min(ccx, llty) as u64;
+    let names = field_names(ccx.tcx(), t);
+    let variant_name = |discr: ty::Disr| {
+        match names.get(discr as usize) {
+            Some(&(Some(ref name), _)) => Some(name.clone()),
+            _ => None,
+        }
+    };
+
+    let (discriminant_size, variants) = match *repr {
+        adt::CEnum(ity, _, _) => {
+            (machine::llsize_of_alloc(ccx, adt::ll_inttype(ccx, ity)), Vec::new())
+        }
+        adt::Univariant(ref st, dtor) => {
+            let fields = struct_fields(ccx, st, 0, variant_fields(&names, 0), dtor != 0);
+            (0, vec![VariantLayout { name: variant_name(0), fields: fields }])
+        }
+        adt::General(_, ref sts, dtor) => {
+            let discr_ty = type_of::sizing_type_of(ccx, sts[0].fields[0]);
+            let variants = sts.iter().enumerate().map(|(i, st)| {
+                VariantLayout {
+                    name: variant_name(i as ty::Disr),
+                    fields: struct_fields(ccx, st, 1, variant_fields(&names, i), dtor != 0),
+                }
+            }).collect();
+            (machine::llsize_of_alloc(ccx, discr_ty), variants)
+        }
+        adt::RawNullablePointer { nndiscr, nnty, .. } => {
+            let nnllty = type_of::sizing_type_of(ccx, nnty);
+            let field = FieldLayout {
+                name: field_name(variant_fields(&names, nndiscr as usize), 0),
+                offset: 0,
+                size: machine::llsize_of_alloc(ccx, nnllty),
+            };
+            (0, vec![VariantLayout { name: variant_name(nndiscr), fields: vec![field] }])
+        }
+        adt::StructWrappedNullablePointer { ref nonnull, nndiscr, .. } => {
+            let fields = struct_fields(ccx, nonnull, 0,
+                                       variant_fields(&names, nndiscr as usize), false);
+            (0, vec![VariantLayout { name: variant_name(nndiscr), fields: fields }])
+        }
+    };
+
+    // The bytes that hold data in the largest variant; everything else in
+    // the type is padding.
+    let used = variants.iter().map(|v| {
+        v.fields.iter().fold(0, |sum, f| sum + f.size)
+    }).max().unwrap_or(0) + discriminant_size;
+
+    Some(TypeLayout {
+        name: ty_to_string(ccx.tcx(), t),
+        kind: kind,
+        size: size,
+        align: align,
+        discriminant_size: discriminant_size,
+        padding: size - used,
+        variants: variants,
+    })
+}
+
+/// Lays out the fields of `st` from index `skip` on, the first `skip`
+/// fields being the discriminant. Fields are listed in memory order.
+fn struct_fields<'a, 'tcx>(ccx: &CrateContext<'a, 'tcx>,
+                           st: &adt::Struct<'tcx>,
+                           skip: usize,
+                           names: &[String],
+                           has_drop_flag: bool)
+                           -> Vec<FieldLayout> {
+    let llfields = st.fields_in_memory_order().iter().map(|&ty| {
+        type_of::sizing_type_of(ccx, ty)
+    }).collect::<Vec<_>>();
+    let llty = Type::struct_(ccx, &llfields, st.packed);
+    st.memory_order().into_iter().filter(|&i| i >= skip).map(|i| {
+        let name = if has_drop_flag && i == st.fields.len() - 1 {
+            "(drop flag)".to_string()
+        } else {
+            field_name(names, i - skip)
+        };
+        let m = st.memory_index[i];
+        FieldLayout {
+            name: name,
+            offset: machine::llelement_offset(ccx, llty, m),
+            size: machine::llsize_of_alloc(ccx, llfields[m]),
+        }
+    }).collect()
+}
+
+fn variant_fields(names: &[(Option<String>, Vec<String>)], discr: usize) -> &[String] {
+    match names.get(discr) {
+        Some(&(_, ref fields)) => fields,
+        None => &[],
+    }
+}
+
+fn field_name(names: &[String], i: usize) -> String {
+    names.get(i).cloned().unwrap_or_else(|| i.to_string())
+}
+
+/// Returns the name of each variant of `t`, if it is an enum, and the
+/// names of its fields. Positional fields are named by their index.
+fn field_names<'tcx>(tcx: &ty::ctxt<'tcx>, t: Ty<'tcx>) -> Vec<(Option<String>, Vec<String>)> {
+    match t.sty {
+        ty::ty_struct(def_id, _) => {
+            let names = ty::lookup_struct_fields(tcx, def_id).iter().enumerate().map(|(i, f)| {
+                if f.name == special_idents::unnamed_field.name {
+                    i.to_string()
+                } else {
+                    token::get_name(f.name).to_string()
+                }
+            }).collect();
+            vec![(None, names)]
+        }
+        ty::ty_enum(def_id, _) => {
+            ty::enum_variants(tcx, def_id).iter().map(|v| {
+                let names = match v.arg_names {
+                    Some(ref names) => {
+                        names.iter().map(|&n| token::get_name(n).to_string()).collect()
+                    }
+                    None => (0..v.args.len()).map(|i| i.to_string()).collect(),
+                };
+                (Some(token::get_name(v.name).to_string()), names)
+            }).collect()
+        }
+        _ => vec![(None, Vec::new())],
+    }
+}
@@ -9,12 +9,12 @@
 // except according to those terms.
 
 use check::FnCtxt;
-use middle::traits::{self, ObjectSafetyViolation, MethodViolationCode};
+use middle::traits;
 use middle::traits::{Obligation, ObligationCause};
 use middle::traits::report_fulfillment_errors;
 use middle::ty::{self, Ty, AsPredicate};
 use syntax::codemap::Span;
-use util::ppaux::{Repr, UserString};
+use util::ppaux::Repr;
 
 
 // Check that a trait is 'object-safe'. This should be checked whenever a trait object
@@ -36,45 +36,7 @@ pub fn check_object_safety<'tcx>(tcx: &ty::ctxt<'tcx>,
               "cannot convert to a trait object because trait `{}` is not object-safe",
               ty::item_path_str(tcx, trait_def_id));
 
-    let violations = traits::object_safety_violations(tcx, trait_def_id);
-    for violation in violations {
-        match violation {
-            ObjectSafetyViolation::SizedSelf => {
-                tcx.sess.span_note(
-                    span,
-                    "the trait cannot require that `Self : Sized`");
-            }
-
-            ObjectSafetyViolation::SupertraitSelf => {
-                tcx.sess.span_note(
-                    span,
-                    "the trait cannot use `Self` as a type parameter \
-                     in the supertrait listing");
-            }
-
-            ObjectSafetyViolation::Method(method, MethodViolationCode::StaticMethod) => {
-                tcx.sess.span_note(
-                    span,
-                    &format!("method `{}` has no receiver",
-                             method.name.user_string(tcx)));
-            }
-
-            ObjectSafetyViolation::Method(method, MethodViolationCode::ReferencesSelf) => {
-                tcx.sess.span_note(
-                    span,
-                    &format!("method `{}` references the `Self` type \
-                              in its arguments or return type",
-                             method.name.user_string(tcx)));
-            }
-
-            ObjectSafetyViolation::Method(method, MethodViolationCode::Generic) => {
-                tcx.sess.span_note(
-                    span,
-                    &format!("method `{}` has generic type parameters",
-                             method.name.user_string(tcx)));
-            }
-        }
-    }
+    traits::note_object_safety_violations(tcx, span, trait_def_id);
 }
 
 pub fn register_object_cast_obligations<'a, 'tcx>(fcx: &FnCtxt<'a, 'tcx>,
@@ -170,6 +170,8 @@ impl<'cx, 'tcx,'v> visit::Visitor<'v> for OverlapChecker<'cx, 'tcx> {
                             span_err!(self.tcx.sess, self_ty.span, E0372,
                                       "the trait `{}` cannot be made into an object",
                                       ty::item_path_str(self.tcx, data.principal_def_id()));
+                            traits::note_object_safety_violations(self.tcx, self_ty.span,
+                                                                  data.principal_def_id());
                         } else {
                             let mut supertrait_def_ids =
                                 traits::supertrait_def_ids(self.tcx, data.principal_def_id());
@@ -121,6 +121,7 @@ fn try_inline_def(cx: &DocContext, tcx: &ty::ctxt,
         inner: inner,
         visibility: Some(ast::Public),
         stability: stability::lookup(tcx, did).clean(cx),
+        deprecation: stability::lookup_deprecation(tcx, did).clean(cx),
         def_id: did,
     });
     Some(ret)
@@ -195,6 +196,7 @@ fn build_struct(cx: &DocContext, tcx: &ty::ctxt, did: ast::DefId) -> clean::Stru
         generics: (&t.generics, &predicates, subst::TypeSpace).clean(cx),
         fields: fields.clean(cx),
         fields_stripped: false,
+        properties: clean::type_properties(cx, did),
     }
 }
 
@@ -207,6 +209,7 @@ fn build_type(cx: &DocContext, tcx: &ty::ctxt, did: ast::DefId) -> clean::ItemEn
                 generics: (&t.generics, &predicates, subst::TypeSpace).clean(cx),
                 variants_stripped: false,
                 variants: ty::enum_variants(tcx, edid).clean(cx),
+                properties: clean::type_properties(cx, did),
             })
         }
         _ => {}
@@ -301,6 +304,7 @@ pub fn build_impl(cx: &DocContext,
             attrs: attrs,
             visibility: Some(ast::Inherited),
             stability: stability::lookup(tcx, did).clean(cx),
+            deprecation: stability::lookup_deprecation(tcx, did).clean(cx),
             def_id: did,
         });
     }
@@ -351,6 +355,7 @@ pub fn build_impl(cx: &DocContext,
                     attrs: vec![],
                     visibility: None,
                     stability: stability::lookup(tcx, did).clean(cx),
+                    deprecation: stability::lookup_deprecation(tcx, did).clean(cx),
                     def_id: did
                 })
             }
@@ -384,6 +389,7 @@ pub fn build_impl(cx: &DocContext,
         attrs: attrs,
         visibility: Some(ast::Inherited),
         stability: stability::lookup(tcx, did).clean(cx),
+        deprecation: stability::lookup_deprecation(tcx, did).clean(cx),
         def_id: did,
     });
 
@@ -43,6 +43,7 @@ use rustc::middle::def;
 use rustc::middle::subst::{self, ParamSpace, VecPerParamSpace};
 use rustc::middle::ty;
 use rustc::middle::stability;
+use rustc::middle::type_properties;
 
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -65,6 +66,12 @@ fn get_stability(cx: &DocContext, def_id: ast::DefId) -> Option<Stability> {
     cx.tcx_opt().and_then(|tcx| stability::lookup(tcx, def_id)).clean(cx)
 }
 
+// extract the `#[deprecated]` attribute of a node in a crate without
+// staged_api from tcx, if possible
+fn get_deprecation(cx: &DocContext, def_id: ast::DefId) -> Option<Deprecation> {
+    cx.tcx_opt().and_then(|tcx| stability::lookup_deprecation(tcx, def_id)).clean(cx)
+}
+
 pub trait Clean<T> {
     fn clean(&self, cx: &DocContext) -> T;
 }
@@ -186,6 +193,7 @@ impl<'a, 'tcx> Clean<Crate> for visit_ast::RustdocVisitor<'a, 'tcx> {
                     attrs: child.attrs.clone(),
                     visibility: Some(ast::Public),
                     stability: None,
+                    deprecation: None,
                     def_id: ast_util::local_def(prim.to_node_id()),
                     inner: PrimitiveItem(prim),
                 });
@@ -254,6 +262,7 @@ pub struct Item {
     pub visibility: Option<Visibility>,
     pub def_id: ast::DefId,
     pub stability: Option<Stability>,
+    pub deprecation: Option<Deprecation>,
 }
 
 impl Item {
@@ -330,7 +339,8 @@ impl Item {
                 }
                 base
             }
-            _ => String::new(),
+            None if self.deprecation.is_some() => "deprecated".to_string(),
+            None => String::new(),
         }
     }
 }
@@ -416,6 +426,7 @@ impl Clean<Item> for doctree::Module {
             source: whence.clean(cx),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             def_id: ast_util::local_def(self.id),
             inner: ModuleItem(Module {
                is_crate: self.is_crate,
@@ -1085,6 +1096,7 @@ impl Clean<Item> for doctree::Function {
             source: self.whence.clean(cx),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             def_id: ast_util::local_def(self.id),
             inner: FunctionItem(Function {
                 decl: self.decl.clean(cx),
@@ -1207,6 +1219,7 @@ impl Clean<Item> for doctree::Trait {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: TraitItem(Trait {
                 unsafety: self.unsafety,
                 items: self.items.clean(cx),
@@ -1252,6 +1265,7 @@ impl Clean<Item> for ast::TraitItem {
             def_id: ast_util::local_def(self.id),
             visibility: None,
             stability: get_stability(cx, ast_util::local_def(self.id)),
+            deprecation: get_deprecation(cx, ast_util::local_def(self.id)),
             inner: inner
         }
     }
@@ -1285,6 +1299,7 @@ impl Clean<Item> for ast::ImplItem {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: get_stability(cx, ast_util::local_def(self.id)),
+            deprecation: get_deprecation(cx, ast_util::local_def(self.id)),
             inner: inner
         }
     }
@@ -1352,6 +1367,7 @@ impl<'tcx> Clean<Item> for ty::Method<'tcx> {
             name: Some(self.name.clean(cx)),
             visibility: Some(ast::Inherited),
             stability: get_stability(cx, self.def_id),
+            deprecation: get_deprecation(cx, self.def_id),
             def_id: self.def_id,
             attrs: inline::load_attrs(cx, cx.tcx(), self.def_id),
             source: Span::empty(),
@@ -1703,6 +1719,7 @@ impl Clean<Item> for ast::StructField {
             source: self.span.clean(cx),
             visibility: Some(vis),
             stability: get_stability(cx, ast_util::local_def(self.node.id)),
+            deprecation: get_deprecation(cx, ast_util::local_def(self.node.id)),
             def_id: ast_util::local_def(self.node.id),
             inner: StructFieldItem(TypedStructField(self.node.ty.clean(cx))),
         }
@@ -1730,6 +1747,7 @@ impl Clean<Item> for ty::field_ty {
             source: Span::empty(),
             visibility: Some(self.vis),
             stability: get_stability(cx, self.id),
+            deprecation: get_deprecation(cx, self.id),
             def_id: self.id,
             inner: StructFieldItem(TypedStructField(ty.ty.clean(cx))),
         }
@@ -1750,6 +1768,7 @@ pub struct Struct {
     pub generics: Generics,
     pub fields: Vec<Item>,
     pub fields_stripped: bool,
+    pub properties: Option<TypeProperties>,
 }
 
 impl Clean<Item> for doctree::Struct {
@@ -1761,16 +1780,55 @@ impl Clean<Item> for doctree::Struct {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: StructItem(Struct {
                 struct_type: self.struct_type,
                 generics: self.generics.clean(cx),
                 fields: self.fields.clean(cx),
                 fields_stripped: false,
+                properties: type_properties(cx, ast_util::local_def(self.id)),
             }),
         }
     }
 }
 
+/// The inferred variance of the parameters of a generic struct or enum, and
+/// whether it is `Send` and `Sync`.
+#[derive(Clone, RustcEncodable, RustcDecodable, Debug)]
+pub struct TypeProperties {
+    /// Each lifetime and type parameter, with its variance.
+    pub variances: Vec<(String, String)>,
+    /// Whether the type is `Send` and `Sync` when its type parameters are.
+    /// This is only known for types of the crate being documented.
+    pub auto_traits: Option<(bool, bool)>,
+}
+
+pub fn type_properties(cx: &DocContext, def_id: ast::DefId) -> Option<TypeProperties> {
+    let tcx = match cx.tcx_opt() {
+        Some(tcx) => tcx,
+        None => return None,
+    };
+
+    let variances = type_properties::param_variances(tcx, def_id);
+    if variances.is_empty() {
+        return None;
+    }
+
+    let auto_traits = if ast_util::is_local(def_id) {
+        let auto_traits = type_properties::auto_traits(tcx, def_id.node);
+        Some((auto_traits.send, auto_traits.sync))
+    } else {
+        None
+    };
+
+    Some(TypeProperties {
+        variances: variances.into_iter().map(|(name, variance)| {
+            (name, type_properties::variance_name(variance).to_string())
+        }).collect(),
+        auto_traits: auto_traits,
+    })
+}
+
 /// This is a more limited form of the standard Struct, different in that
 /// it lacks the things most items have (name, id, parameterization). Found
 /// only as a variant in an enum.
@@ -1796,6 +1854,7 @@ pub struct Enum {
     pub variants: Vec<Item>,
     pub generics: Generics,
     pub variants_stripped: bool,
+    pub properties: Option<TypeProperties>,
 }
 
 impl Clean<Item> for doctree::Enum {
@@ -1807,10 +1866,12 @@ impl Clean<Item> for doctree::Enum {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: EnumItem(Enum {
                 variants: self.variants.clean(cx),
                 generics: self.generics.clean(cx),
                 variants_stripped: false,
+                properties: type_properties(cx, ast_util::local_def(self.id)),
             }),
         }
     }
@@ -1829,6 +1890,7 @@ impl Clean<Item> for doctree::Variant {
             source: self.whence.clean(cx),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             def_id: ast_util::local_def(self.id),
             inner: VariantItem(Variant {
                 kind: self.kind.clean(cx),
@@ -1864,6 +1926,7 @@ impl<'tcx> Clean<Item> for ty::VariantInfo<'tcx> {
                             //        at the needed information here.
                             def_id: self.id,
                             stability: get_stability(cx, self.id),
+                            deprecation: get_deprecation(cx, self.id),
                             inner: StructFieldItem(
                                 TypedStructField(ty.clean(cx))
                             )
@@ -1880,6 +1943,7 @@ impl<'tcx> Clean<Item> for ty::VariantInfo<'tcx> {
             def_id: self.id,
             inner: VariantItem(Variant { kind: kind }),
             stability: get_stability(cx, self.id),
+            deprecation: get_deprecation(cx, self.id),
         }
     }
 }
@@ -2062,6 +2126,7 @@ impl Clean<Item> for doctree::Typedef {
             def_id: ast_util::local_def(self.id.clone()),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: TypedefItem(Typedef {
                 type_: self.ty.clean(cx),
                 generics: self.gen.clean(cx),
@@ -2113,6 +2178,7 @@ impl Clean<Item> for doctree::Static {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: StaticItem(Static {
                 type_: self.type_.clean(cx),
                 mutability: self.mutability.clean(cx),
@@ -2137,6 +2203,7 @@ impl Clean<Item> for doctree::Constant {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: ConstantItem(Constant {
                 type_: self.type_.clean(cx),
                 expr: self.expr.span.to_src(cx),
@@ -2211,6 +2278,7 @@ impl Clean<Vec<Item>> for doctree::Impl {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             inner: ImplItem(Impl {
                 unsafety: self.unsafety,
                 generics: self.generics.clean(cx),
@@ -2293,6 +2361,7 @@ impl Clean<Item> for doctree::DefaultImpl {
             def_id: ast_util::local_def(self.id),
             visibility: Some(ast::Public),
             stability: None,
+            deprecation: None,
             inner: DefaultImplItem(DefaultImpl {
                 unsafety: self.unsafety,
                 trait_: self.trait_.clean(cx),
@@ -2310,6 +2379,7 @@ impl Clean<Item> for doctree::ExternCrate {
             def_id: ast_util::local_def(0),
             visibility: self.vis.clean(cx),
             stability: None,
+            deprecation: None,
             inner: ExternCrateItem(self.name.clean(cx), self.path.clone())
         }
     }
@@ -2375,6 +2445,7 @@ impl Clean<Vec<Item>> for doctree::Import {
             def_id: ast_util::local_def(0),
             visibility: self.vis.clean(cx),
             stability: None,
+            deprecation: None,
             inner: ImportItem(inner)
         });
         ret
@@ -2457,6 +2528,7 @@ impl Clean<Item> for ast::ForeignItem {
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: get_stability(cx, ast_util::local_def(self.id)),
+            deprecation: get_deprecation(cx, ast_util::local_def(self.id)),
             inner: inner,
         }
     }
@@ -2639,6 +2711,7 @@ impl Clean<Item> for doctree::Macro {
             source: self.whence.clean(cx),
             visibility: ast::Public.clean(cx),
             stability: self.stab.clean(cx),
+            deprecation: self.depr.clean(cx),
             def_id: ast_util::local_def(self.id),
             inner: MacroItem(Macro {
                 source: self.whence.to_src(cx),
@@ -2672,6 +2745,23 @@ impl Clean<Stability> for attr::Stability {
     }
 }
 
+#[derive(Clone, RustcEncodable, RustcDecodable, Debug)]
+pub struct Deprecation {
+    pub since: String,
+    pub note: String,
+    pub suggestion: String,
+}
+
+impl Clean<Deprecation> for attr::Deprecation {
+    fn clean(&self, _: &DocContext) -> Deprecation {
+        Deprecation {
+            since: self.since.to_string(),
+            note: self.note.as_ref().map_or("".to_string(), |s| s.to_string()),
+            suggestion: self.suggestion.as_ref().map_or("".to_string(), |s| s.to_string()),
+        }
+    }
+}
+
 impl Clean<Item> for ty::AssociatedType {
     fn clean(&self, cx: &DocContext) -> Item {
         // When loading a cross-crate associated type, the bounds for this type
@@ -2727,7 +2817,8 @@ impl Clean<Item> for ty::AssociatedType {
             inner: AssociatedTypeItem(bounds, None),
             visibility: self.vis.clean(cx),
             def_id: self.def_id,
-            stability: stability::lookup(cx.tcx(), self.def_id).clean(cx),
+            stability: get_stability(cx, self.def_id),
+            deprecation: get_deprecation(cx, self.def_id),
         }
     }
 }
@@ -1689,6 +1689,23 @@ fn item_module(w: &mut fmt::Formatter, cx: &Context,
 }
 
 fn short_stability(item: &clean::Item, show_reason: bool) -> Option<String> {
+    if let Some(ref depr) = item.deprecation {
+        let mut reason = depr.note.clone();
+        if !depr.suggestion.is_empty() {
+            if !reason.is_empty() {
+                reason.push(' ');
+            }
+            reason.push_str(&format!("Use `{}` instead.", depr.suggestion));
+        }
+        let text = if show_reason {
+            let reason = if reason.is_empty() { reason } else { format!(": {}", reason) };
+            format!("Deprecated since {}{}", Escape(&depr.since), Markdown(&reason))
+        } else {
+            "Deprecated".to_string()
+        };
+        return Some(format!("<em class='stab {}'>{}</em>", item.stability_class(), text))
+    }
+
     item.stability.as_ref().and_then(|stab| {
         let reason = if show_reason && !stab.reason.is_empty() {
             format!(": {}", stab.reason)
@@ -2001,6 +2018,7 @@ fn item_struct(w: &mut fmt::Formatter, it: &clean::Item,
             try!(write!(w, "</table>"));
         }
     }
+    try!(render_type_properties(w, &s.properties));
     render_methods(w, it.def_id, MethodRender::All)
 }
 
@@ -2100,10 +2118,35 @@ fn item_enum(w: &mut fmt::Formatter, it: &clean::Item,
         try!(write!(w, "</table>"));
 
     }
+    try!(render_type_properties(w, &e.properties));
     try!(render_methods(w, it.def_id, MethodRender::All));
     Ok(())
 }
 
+fn render_type_properties(w: &mut fmt::Formatter,
+                          properties: &Option<clean::TypeProperties>) -> fmt::Result {
+    let properties = match *properties {
+        Some(ref properties) => properties,
+        None => return Ok(()),
+    };
+
+    try!(write!(w, "<h2 class='variance'>Parameters</h2>\n<table>"));
+    for &(ref name, ref variance) in &properties.variances {
+        try!(write!(w, "<tr><td><code>{}</code></td><td>{}</td></tr>",
+                    name, variance));
+    }
+    try!(write!(w, "</table>"));
+
+    if let Some((send, sync)) = properties.auto_traits {
+        let status = |implemented| if implemented { "is" } else { "is not" };
+        try!(write!(w, "<p class='auto-traits'>When its type parameters are \
+                        <code>Send</code> and <code>Sync</code>, this type \
+                        {} <code>Send</code> and {} <code>Sync</code>.</p>",
+                    status(send), status(sync)));
+    }
+    Ok(())
+}
+
 fn render_attributes(w: &mut fmt::Formatter, it: &clean::Item) -> fmt::Result {
     for attr in &it.attrs {
         match *attr {
@@ -14,6 +14,7 @@ use prelude::v1::*;
 use io::prelude::*;
 
 use any::Any;
+use intrinsics;
 use cell::RefCell;
 use rt::{backtrace, unwind};
 use sys::stdio::Stderr;
@@ -66,4 +67,9 @@ pub fn on_panic(obj: &(Any+Send), file: &'static str, line: u32) {
     if unwind::panicking() && !backtrace::log_enabled() {
         let _ = backtrace::write(&mut err);
     }
+
+    // Code compiled with `-C panic=abort` cannot be unwound through.
+    if unwind::abort_on_panic() {
+        unsafe { intrinsics::abort() }
+    }
 }
@@ -69,6 +69,39 @@ mod test {
         t!("_ZN12test$BP$test4foobE", "test*test::foob");
     }
 
+    #[test]
+    fn demangle_unicode() {
+        t!("_ZN9$u7e$test4foobE", "~test::foob");
+        t!("_ZN9test$u3f$4foobE", "test?::foob");
+        t!("_ZN10test$u3bb$4foobE", "test\u{3bb}::foob");
+    }
+
+    #[test]
+    fn demangle_hash() {
+        t!("_ZN3foo3bar17h0123456789abcdefE", "foo::bar");
+        t!("_ZN3foo3bar20h0123456789abcdefxYzE", "foo::bar");
+        t!("_ZN3foo17h0123456789abcdefE", "foo");
+        t!("_ZN17h0123456789abcdefE", "h0123456789abcdef");
+        t!("_ZN3foo17g0123456789abcdefE", "foo::g0123456789abcdef");
+        t!("_ZN3foo18h0123456789abcde\u{3bb}E", "foo::h0123456789abcde\u{3bb}");
+    }
+
+    #[test]
+    fn demangle_type_params() {
+        t!("_ZN3foo3bar12_$LT$i32$GT$17h0123456789abcdefE", "foo::bar::<i32>");
+        t!("_ZN3foo3bar56_$LT$i32$C$$u20$collections..vec..Vec$LT$$RF$str$GT$$GT$\
+            17h0123456789abcdefE",
+           "foo::bar::<i32, collections::vec::Vec<&str>>");
+        t!("_ZN3foo4_barE", "foo::_bar");
+    }
+
+    #[test]
+    fn demangle_invalid() {
+        t!("_ZN5testE", "_ZN5testE");
+        t!("_ZN4test0E", "_ZN4test0E");
+        t!("_ZN3\u{3bb}E", "_ZN3\u{3bb}E");
+    }
+
     #[test]
     fn demangle_windows() {
         t!("ZN4testE", "test");
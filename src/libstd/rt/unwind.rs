@@ -73,6 +73,9 @@ use sys_common::mutex::{Mutex, MUTEX_INIT};
 
 use rt::libunwind as uw;
 
+// `uwe` must stay at offset 0: the unwinder hands us back a pointer to it,
+// which is cast to a pointer to the whole `Exception`.
+#[repr(C)]
 struct Exception {
     uwe: uw::_Unwind_Exception,
     cause: Option<Box<Any + Send + 'static>>,
@@ -97,6 +100,10 @@ static CALLBACK_CNT: atomic::AtomicUsize = atomic::ATOMIC_USIZE_INIT;
 
 thread_local! { static PANICKING: Cell<bool> = Cell::new(false) }
 
+// Set on load by a global constructor in every crate compiled with
+// `-C panic=abort`, whose code has no landing pads to unwind through.
+static ABORT_ON_PANIC: atomic::AtomicBool = atomic::ATOMIC_BOOL_INIT;
+
 /// Invoke a closure, capturing the cause of panic if one occurs.
 ///
 /// This function will return `Ok(())` if the closure did not panic, and will
@@ -158,6 +165,20 @@ pub fn panicking() -> bool {
     PANICKING.with(|s| s.get())
 }
 
+/// Makes every later panic abort the process once its message has been
+/// printed. The compiler calls this from a global constructor emitted into
+/// every crate compiled with `-C panic=abort`, whatever its crate type.
+#[cfg(not(test))]
+#[lang = "abort_on_panic"]
+pub fn set_abort_on_panic() {
+    ABORT_ON_PANIC.store(true, Ordering::SeqCst);
+}
+
+/// Determines whether panics abort the process rather than unwind.
+pub fn abort_on_panic() -> bool {
+    ABORT_ON_PANIC.load(Ordering::SeqCst)
+}
+
 // An uninlined, unmangled function upon which to slap yer breakpoints
 #[inline(never)]
 #[no_mangle]
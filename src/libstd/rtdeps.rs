@@ -23,8 +23,8 @@ extern {}
 // libm. Hence, we must explicitly link to it.
 //
 // On Linux, librt and libdl are indirect dependencies via std,
-// and binutils 2.22+ won't add them automatically
-#[cfg(target_os = "linux")]
+// and binutils 2.22+ won't add them automatically. musl has them in its libc.
+#[cfg(all(target_os = "linux", not(target_env = "musl")))]
 #[link(name = "dl")]
 #[link(name = "pthread")]
 extern {}
@@ -11,6 +11,7 @@
 use prelude::v1::*;
 use io::prelude::*;
 
+use char;
 use io;
 
 #[cfg(target_pointer_width = "64")]
@@ -29,13 +30,19 @@ pub const HEX_WIDTH: usize = 10;
 //
 // For example, "_ZN4testE" => "test" and "_ZN3foo3bar" => "foo::bar".
 //
+// Characters which can't appear in symbols are escaped within an element, see
+// `sanitize` in src/librustc_trans/back/link.rs, which also prefixes elements
+// that don't start like an identifier with "_". Instantiations of generic
+// functions have their type parameters as an element of their own, and most
+// symbols end with a hash element to keep them unique, so
+// "_ZN3foo3bar12_$LT$i32$GT$17h0123456789abcdefE" => "foo::bar::<i32>".
+//
 // We're the ones printing our backtraces, so we can't rely on anything else to
 // demangle our symbols. It's *much* nicer to look at demangled symbols, so
 // this function is implemented to give us nice pretty output.
 //
-// Note that this demangler isn't quite as fancy as it could be. We have lots
-// of other information in our symbols like hashes, version, type information,
-// etc. Additionally, this doesn't handle glue symbols at all.
+// Note that this demangler isn't quite as fancy as it could be: the hash is
+// simply left out, and glue symbols aren't handled specially at all.
 pub fn demangle(writer: &mut Write, s: &str) -> io::Result<()> {
     // First validate the symbol. If it doesn't look like anything we're
     // expecting, we just print it literally. Note that we must handle non-rust
@@ -51,90 +58,106 @@ pub fn demangle(writer: &mut Write, s: &str) -> io::Result<()> {
         valid = false;
     }
 
+    let mut elements = Vec::new();
     if valid {
-        let mut chars = inner.chars();
-        while valid {
-            let mut i = 0;
-            for c in chars.by_ref() {
-                if c.is_numeric() {
-                    i = i * 10 + c as usize - '0' as usize;
-                } else {
-                    break
-                }
-            }
-            if i == 0 {
-                valid = chars.next().is_none();
-                break
-            } else if chars.by_ref().take(i - 1).count() != i - 1 {
-                valid = false;
-            }
+        let mut rest = inner;
+        while !rest.is_empty() {
+            let digits = rest.chars().take_while(|c| c.is_digit(10)).count();
+            let i: usize = match rest[..digits].parse() {
+                Ok(i) if i > 0 && i <= rest.len() - digits &&
+                         rest.is_char_boundary(digits + i) => i,
+                _ => { valid = false; break }
+            };
+            elements.push(&rest[digits .. digits + i]);
+            rest = &rest[digits + i ..];
         }
     }
 
     // Alright, let's do this.
     if !valid {
-        try!(writer.write_all(s.as_bytes()));
-    } else {
-        let mut first = true;
-        while !inner.is_empty() {
-            if !first {
-                try!(writer.write_all(b"::"));
-            } else {
-                first = false;
-            }
-            let mut rest = inner;
-            while rest.char_at(0).is_numeric() {
-                rest = &rest[1..];
-            }
-            let i: usize = inner[.. (inner.len() - rest.len())].parse().unwrap();
-            inner = &rest[i..];
-            rest = &rest[..i];
-            while !rest.is_empty() {
-                if rest.starts_with("$") {
-                    macro_rules! demangle {
-                        ($($pat:expr, => $demangled:expr),*) => ({
-                            $(if rest.starts_with($pat) {
-                                try!(writer.write_all($demangled));
-                                rest = &rest[$pat.len()..];
-                              } else)*
-                            {
-                                try!(writer.write_all(rest.as_bytes()));
-                                break;
-                            }
+        return writer.write_all(s.as_bytes());
+    }
 
-                        })
-                    }
+    if elements.len() > 1 && is_hash(elements[elements.len() - 1]) {
+        elements.pop();
+    }
 
-                    // see src/librustc/back/link.rs for these mappings
-                    demangle! (
-                        "$SP$", => b"@",
-                        "$BP$", => b"*",
-                        "$RF$", => b"&",
-                        "$LT$", => b"<",
-                        "$GT$", => b">",
-                        "$LP$", => b"(",
-                        "$RP$", => b")",
-                        "$C$", => b",",
+    for (i, element) in elements.into_iter().enumerate() {
+        if i > 0 {
+            try!(writer.write_all(b"::"));
+        }
+        let mut rest = element;
+        // Drop the "_" that `sanitize` put in front of an escape.
+        if rest.starts_with("_$") {
+            rest = &rest[1..];
+        }
+        while !rest.is_empty() {
+            if rest.starts_with("$") {
+                macro_rules! demangle {
+                    ($($pat:expr, => $demangled:expr),*) => ({
+                        $(if rest.starts_with($pat) {
+                            try!(writer.write_all($demangled));
+                            rest = &rest[$pat.len()..];
+                          } else)*
+                        if let Some((c, len)) = unicode_escape(rest) {
+                            try!(write!(writer, "{}", c));
+                            rest = &rest[len..];
+                        } else {
+                            try!(writer.write_all(rest.as_bytes()));
+                            break;
+                        }
 
-                        // in theory we can demangle any Unicode code point, but
-                        // for simplicity we just catch the common ones.
-                        "$u7e$", => b"~",
-                        "$u20$", => b" ",
-                        "$u27$", => b"'",
-                        "$u5b$", => b"[",
-                        "$u5d$", => b"]"
-                    )
-                } else {
-                    let idx = match rest.find('$') {
-                        None => rest.len(),
-                        Some(i) => i,
-                    };
-                    try!(writer.write_all(rest[..idx].as_bytes()));
-                    rest = &rest[idx..];
+                    })
                 }
+
+                // see src/librustc_trans/back/link.rs for these mappings
+                demangle! (
+                    "$SP$", => b"@",
+                    "$BP$", => b"*",
+                    "$RF$", => b"&",
+                    "$LT$", => b"<",
+                    "$GT$", => b">",
+                    "$LP$", => b"(",
+                    "$RP$", => b")",
+                    "$C$", => b","
+                )
+            } else if rest.starts_with("..") {
+                // Paths within an element, such as those of type parameters.
+                try!(writer.write_all(b"::"));
+                rest = &rest[2..];
+            } else {
+                let idx = rest.char_indices().skip(1)
+                              .find(|&(_, c)| c == '$' || c == '.')
+                              .map(|(i, _)| i)
+                              .unwrap_or(rest.len());
+                try!(writer.write_all(rest[..idx].as_bytes()));
+                rest = &rest[idx..];
             }
         }
     }
 
     Ok(())
 }
+
+// Any other character is escaped as its code point, e.g. "$u20$" for a space.
+// Returns that character and the length of its escape, if `s` starts with one.
+fn unicode_escape(s: &str) -> Option<(char, usize)> {
+    if !s.starts_with("$u") {
+        return None
+    }
+    let end = match s[2..].find('$') {
+        Some(end) => end + 2,
+        None => return None,
+    };
+    u32::from_str_radix(&s[2..end], 16).ok()
+                                       .and_then(char::from_u32)
+                                       .map(|c| (c, end + 1))
+}
+
+// The hash rustc appends to symbols: an "h" followed by 16 hex digits, and
+// for some symbols up to 3 further characters to tell apart items with the
+// same path.
+fn is_hash(s: &str) -> bool {
+    s.len() >= 17 && s.len() <= 20 && s.starts_with("h") &&
+        s.bytes().skip(1).take(16).all(|b| (b as char).is_digit(16))
+}
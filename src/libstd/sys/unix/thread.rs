@@ -318,7 +318,7 @@ pub fn sleep(dur: Duration) {
 // case.  We previously used weak linkage (under the same assumption),
 // but that caused Debian to detect an unnecessarily strict versioned
 // dependency on libc6 (#23628).
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(target_env = "musl")))]
 fn min_stack_size(attr: *const libc::pthread_attr_t) -> libc::size_t {
     use dynamic_lib::DynamicLibrary;
     use sync::{Once, ONCE_INIT};
@@ -343,8 +343,8 @@ fn min_stack_size(attr: *const libc::pthread_attr_t) -> libc::size_t {
 }
 
 // No point in looking up __pthread_get_minstack() on non-glibc
-// platforms.
-#[cfg(not(target_os = "linux"))]
+// platforms, and statically linked musl programs can't look up symbols.
+#[cfg(any(not(target_os = "linux"), target_env = "musl"))]
 fn min_stack_size(_: *const libc::pthread_attr_t) -> libc::size_t {
     PTHREAD_STACK_MIN
 }
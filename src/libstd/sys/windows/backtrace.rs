@@ -98,12 +98,14 @@ enum ADDRESS_MODE {
     AddrModeFlat,
 }
 
+#[repr(C)]
 struct ADDRESS64 {
     Offset: u64,
     Segment: u16,
     Mode: ADDRESS_MODE,
 }
 
+#[repr(C)]
 pub struct STACKFRAME64 {
     AddrPC: ADDRESS64,
     AddrReturn: ADDRESS64,
@@ -118,6 +120,7 @@ pub struct STACKFRAME64 {
     KdHelp: KDHELP64,
 }
 
+#[repr(C)]
 struct KDHELP64 {
     Thread: u64,
     ThCallbackStack: libc::DWORD,
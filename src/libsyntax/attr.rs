@@ -514,6 +514,84 @@ pub fn find_stability(diagnostic: &SpanHandler, attrs: &[Attribute],
     return s;
 }
 
+/// Represents the `#[deprecated]` attribute in crates outside of the
+/// standard library, where it is not paired with a stability level.
+#[derive(RustcEncodable,RustcDecodable,Clone,Debug,PartialEq)]
+pub struct Deprecation {
+    /// The version in which the item was deprecated.
+    pub since: InternedString,
+    /// Free-form explanation of why the item is deprecated.
+    pub note: Option<InternedString>,
+    /// What to use instead, e.g. the path of a replacement item.
+    pub suggestion: Option<InternedString>,
+}
+
+/// Find the `#[deprecated(since = "...", note = "...", suggestion = "...")]`
+/// attribute. `None` if none exists.
+pub fn find_deprecation(diagnostic: &SpanHandler, attrs: &[Attribute],
+                        item_sp: Span) -> Option<Deprecation> {
+    let mut depr: Option<Deprecation> = None;
+
+    'outer: for attr in attrs {
+        if attr.name() != "deprecated" {
+            continue
+        }
+
+        mark_used(attr);
+
+        if depr.is_some() {
+            diagnostic.span_err(item_sp, "multiple deprecated attributes");
+            break
+        }
+
+        let metas = match attr.meta_item_list() {
+            Some(metas) => metas,
+            None => {
+                diagnostic.span_err(attr.span(), "incorrect deprecated attribute type, \
+                                                  expected #[deprecated(since = \"...\")]");
+                continue
+            }
+        };
+
+        let mut since = None;
+        let mut note = None;
+        let mut suggestion = None;
+        for meta in metas {
+            let slot = match &meta.name()[..] {
+                "since" => &mut since,
+                "note" => &mut note,
+                "suggestion" => &mut suggestion,
+                name => {
+                    diagnostic.span_err(meta.span,
+                                        &format!("unknown meta item `{}` in deprecated \
+                                                  attribute", name));
+                    continue 'outer;
+                }
+            };
+            match meta.value_str() {
+                Some(v) => *slot = Some(v),
+                None => {
+                    diagnostic.span_err(meta.span, "incorrect meta item");
+                    continue 'outer;
+                }
+            }
+        }
+
+        match since {
+            Some(since) => {
+                depr = Some(Deprecation {
+                    since: since,
+                    note: note,
+                    suggestion: suggestion,
+                });
+            }
+            None => diagnostic.span_err(attr.span(), "missing 'since'"),
+        }
+    }
+
+    depr
+}
+
 pub fn require_unique_names(diagnostic: &SpanHandler, metas: &[P<MetaItem>]) {
     let mut set = HashSet::new();
     for meta in metas {
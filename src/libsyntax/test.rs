@@ -443,6 +443,8 @@ fn mk_main(cx: &mut TestCtxt) -> P<ast::Item> {
     //            test::test_main_static(::std::os::args().as_slice(), TESTS);
     //        }
 
+    // The harness has no real span, so it gets no debuginfo and, under
+    // `-C coverage`, no counters: coverage reports only count the tests.
     let sp = ignored_span(cx, DUMMY_SP);
     let ecx = &cx.ext_cx;
 
@@ -12,10 +12,12 @@
 
 #include "rustllvm.h"
 
+#include "llvm/ProfileData/InstrProfReader.h"
 #include "llvm/Support/CBindingWrapping.h"
 #include "llvm/Support/FileSystem.h"
 #include "llvm/Support/Host.h"
 #include "llvm/Target/TargetLibraryInfo.h"
+#include "llvm/Transforms/Instrumentation.h"
 #include "llvm/Transforms/IPO/PassManagerBuilder.h"
 
 #include "llvm-c/Transforms/PassManagerBuilder.h"
@@ -296,3 +298,46 @@ LLVMRustMarkAllFunctionsNounwind(LLVMModuleRef M) {
         }
     }
 }
+
+// Lowers the `llvm.instrprof.increment` calls emitted under
+// `-C profile-generate` to counters that the profiling runtime writes to
+// `Output` when the program exits.
+extern "C" void
+LLVMRustAddInstrProfilingPass(LLVMPassManagerRef PM, const char *Output) {
+    InstrProfOptions Options;
+    Options.InstrProfileOutput = Output;
+    unwrap(PM)->add(createInstrProfilingPass(Options));
+}
+
+// Opens a profile merged by `llvm-profdata` for `-C profile-use`.
+extern "C" IndexedInstrProfReader*
+LLVMRustOpenProfile(const char *Path) {
+    std::unique_ptr<IndexedInstrProfReader> Reader;
+    if (std::error_code EC = IndexedInstrProfReader::create(Path, Reader)) {
+        LLVMRustSetLastError(EC.message().c_str());
+        return NULL;
+    }
+    return Reader.release();
+}
+
+// Copies up to `Len` of the counters recorded for a function into `Counts`.
+// Returns how many counters the profile has for it, or -1 if it has none
+// for this name and hash.
+extern "C" int64_t
+LLVMRustGetProfileCounts(IndexedInstrProfReader *Reader,
+                         const char *Name,
+                         uint64_t Hash,
+                         uint64_t *Counts,
+                         size_t Len) {
+    std::vector<uint64_t> Result;
+    if (Reader->getFunctionCounts(Name, Hash, Result))
+        return -1;
+    for (size_t i = 0; i < Len && i < Result.size(); i++)
+        Counts[i] = Result[i];
+    return Result.size();
+}
+
+extern "C" void
+LLVMRustFreeProfile(IndexedInstrProfReader *Reader) {
+    delete Reader;
+}
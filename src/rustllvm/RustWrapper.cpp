@@ -15,6 +15,7 @@
 #include "llvm/IR/DiagnosticPrinter.h"
 
 #include "llvm/IR/CallSite.h"
+#include "llvm/IR/DataLayout.h"
 
 //===----------------------------------------------------------------------===
 //
@@ -737,6 +738,316 @@ LLVMRustLinkInExternalBitcode(LLVMModuleRef dst, char *bc, size_t len) {
     return true;
 }
 
+// Whether `V` is, or is a constant expression referring to, a global which
+// can't be referenced from outside of its module.
+static bool
+refersToLocalGlobal(const Value *V) {
+    if (const GlobalValue *GV = dyn_cast<GlobalValue>(V))
+        return GV->hasLocalLinkage() || isa<GlobalAlias>(GV);
+    if (const ConstantExpr *CE = dyn_cast<ConstantExpr>(V)) {
+        for (User::const_op_iterator I = CE->op_begin(), E = CE->op_end(); I != E; ++I) {
+            if (refersToLocalGlobal(*I))
+                return true;
+        }
+    }
+    return false;
+}
+
+// Reports a function that summary LTO may import, with its size in
+// instructions.
+typedef void (*RustImportCandidateFn)(void *data, const char *name, size_t len,
+                                      unsigned size);
+
+// Loads the bitcode of one object file of an upstream crate and reduces it
+// to the functions that summary LTO may import into codegen units: those
+// with at most `limit` instructions, which aren't marked noinline or cold
+// and which don't refer to globals private to `bc`, as those globals would
+// have to be duplicated. Each of them is reported through `candidate`, and
+// the reduced module, in which every other function and global is a
+// declaration, is written to `out` as bitcode. This is done once per
+// upstream crate, so that codegen units only ever load the candidates.
+extern "C" bool
+LLVMRustBuildImportSummary(char *bc, size_t len, unsigned limit,
+                           RustImportCandidateFn candidate, void *data,
+                           RustStringRef out) {
+    LLVMContext Context;
+#if LLVM_VERSION_MINOR == 5
+    MemoryBuffer* buf = MemoryBuffer::getMemBufferCopy(StringRef(bc, len));
+    ErrorOr<Module *> Src = llvm::getLazyBitcodeModule(buf, Context);
+#else
+    std::unique_ptr<MemoryBuffer> buf = MemoryBuffer::getMemBufferCopy(StringRef(bc, len));
+    ErrorOr<Module *> Src = llvm::getLazyBitcodeModule(std::move(buf), Context);
+#endif
+    if (!Src) {
+        LLVMRustSetLastError(Src.getError().message().c_str());
+#if LLVM_VERSION_MINOR == 5
+        delete buf;
+#endif
+        return false;
+    }
+    std::unique_ptr<Module> M(*Src);
+    if (std::error_code EC = M->materializeAllPermanently()) {
+        LLVMRustSetLastError(EC.message().c_str());
+        return false;
+    }
+
+    // The imported functions are only there to be inlined, so their debug info
+    // would only describe functions that never end up in the output.
+    StripDebugInfo(*M);
+
+    DenseSet<Function*> Candidates;
+    for (Module::iterator F = M->begin(), FE = M->end(); F != FE; ++F) {
+        if (F->isDeclaration() || F->hasLocalLinkage() ||
+            F->hasFnAttribute(Attribute::NoInline) ||
+            F->hasFnAttribute(Attribute::Cold))
+            continue;
+
+        unsigned Count = 0;
+        bool Local = false;
+        for (Function::iterator B = F->begin(), BE = F->end(); B != BE && !Local; ++B) {
+            for (BasicBlock::iterator I = B->begin(), IE = B->end(); I != IE; ++I) {
+                Count++;
+                for (User::op_iterator O = I->op_begin(), OE = I->op_end(); O != OE; ++O) {
+                    if (refersToLocalGlobal(*O))
+                        Local = true;
+                }
+            }
+        }
+        if (!Local && Count <= limit) {
+            Candidates.insert(&*F);
+            StringRef Name = F->getName();
+            candidate(data, Name.data(), Name.size(), Count);
+        }
+    }
+
+    // Reduce everything but the candidates to declarations. The globals which
+    // only the dropped code referred to go away entirely.
+    std::vector<GlobalValue*> Dead;
+    for (Module::global_iterator G = M->global_begin(), GE = M->global_end(); G != GE; ++G) {
+        if (G->isDeclaration())
+            continue;
+        G->setInitializer(NULL);
+        if (G->hasLocalLinkage() || G->getName().startswith("llvm."))
+            Dead.push_back(&*G);
+        else
+            G->setLinkage(GlobalValue::ExternalLinkage);
+    }
+    for (Module::iterator F = M->begin(), FE = M->end(); F != FE; ++F) {
+        if (!Candidates.count(&*F) && !F->isDeclaration()) {
+            if (F->hasLocalLinkage())
+                Dead.push_back(&*F);
+            F->deleteBody();
+        }
+    }
+    for (Module::alias_iterator A = M->alias_begin(), AE = M->alias_end(); A != AE; ++A)
+        Dead.push_back(&*A);
+    for (std::vector<GlobalValue*>::iterator G = Dead.begin(), GE = Dead.end(); G != GE; ++G) {
+        (*G)->removeDeadConstantUsers();
+        (*G)->replaceAllUsesWith(UndefValue::get((*G)->getType()));
+    }
+    for (std::vector<GlobalValue*>::iterator G = Dead.begin(), GE = Dead.end(); G != GE; ++G)
+        (*G)->eraseFromParent();
+
+    raw_rust_string_ostream OS(out);
+    WriteBitcodeToFile(M.get(), OS);
+    return true;
+}
+
+// Reports how often a module calls a function it declares.
+typedef void (*RustExternalCallsFn)(void *data, const char *name, size_t len,
+                                    unsigned calls);
+
+// Counts the call sites of each function that `m` declares but doesn't
+// define, through `f`. Calls from functions marked cold, which includes those
+// that a profile given to `-C profile-use` says never ran, aren't counted, and
+// neither are calls to cold functions.
+extern "C" void
+LLVMRustCountExternalCalls(LLVMModuleRef m, RustExternalCallsFn f, void *data) {
+    Module *M = unwrap(m);
+    DenseMap<Function*, unsigned> Calls;
+    for (Module::iterator F = M->begin(), FE = M->end(); F != FE; ++F) {
+        if (F->isDeclaration() || F->hasFnAttribute(Attribute::Cold))
+            continue;
+        for (Function::iterator B = F->begin(), BE = F->end(); B != BE; ++B) {
+            for (BasicBlock::iterator I = B->begin(), IE = B->end(); I != IE; ++I) {
+                CallSite CS(&*I);
+                if (!CS)
+                    continue;
+                Function *Callee = CS.getCalledFunction();
+                if (Callee && Callee->isDeclaration() && !Callee->isIntrinsic() &&
+                    !Callee->hasFnAttribute(Attribute::Cold))
+                    Calls[Callee]++;
+            }
+        }
+    }
+    for (DenseMap<Function*, unsigned>::iterator C = Calls.begin(), CE = Calls.end();
+         C != CE; ++C) {
+        StringRef Name = C->first->getName();
+        f(data, Name.data(), Name.size(), C->second);
+    }
+}
+
+// Links the functions named in `names` out of `bc`, a module reduced by
+// LLVMRustBuildImportSummary, into `dst` as `available_externally`
+// definitions: they can be inlined into `dst` but are never emitted by it.
+// The other candidates in `bc` are dropped before linking.
+extern "C" bool
+LLVMRustImportFunctions(LLVMModuleRef dst, char *bc, size_t len,
+                        const char **names, size_t num_names) {
+    Module *Dst = unwrap(dst);
+#if LLVM_VERSION_MINOR == 5
+    MemoryBuffer* buf = MemoryBuffer::getMemBufferCopy(StringRef(bc, len));
+    ErrorOr<Module *> Src = llvm::getLazyBitcodeModule(buf, Dst->getContext());
+#else
+    std::unique_ptr<MemoryBuffer> buf = MemoryBuffer::getMemBufferCopy(StringRef(bc, len));
+    ErrorOr<Module *> Src = llvm::getLazyBitcodeModule(std::move(buf), Dst->getContext());
+#endif
+    if (!Src) {
+        LLVMRustSetLastError(Src.getError().message().c_str());
+#if LLVM_VERSION_MINOR == 5
+        delete buf;
+#endif
+        return false;
+    }
+    // The reduced module only has the bodies of the candidates, so loading
+    // it completely is cheap.
+    if (std::error_code EC = (*Src)->materializeAllPermanently()) {
+        LLVMRustSetLastError(EC.message().c_str());
+        delete *Src;
+        return false;
+    }
+    Module *M = *Src;
+
+    DenseSet<Function*> Import;
+    for (size_t i = 0; i < num_names; i++) {
+        Function *F = M->getFunction(names[i]);
+        Function *Decl = Dst->getFunction(names[i]);
+        if (F != NULL && !F->isDeclaration() && Decl != NULL && Decl->isDeclaration())
+            Import.insert(F);
+    }
+    for (Module::iterator F = M->begin(), FE = M->end(); F != FE; ++F) {
+        if (Import.count(&*F))
+            F->setLinkage(GlobalValue::AvailableExternallyLinkage);
+        else if (!F->isDeclaration())
+            F->deleteBody();
+    }
+
+    std::string Err;
+
+#if LLVM_VERSION_MINOR >= 6
+    raw_string_ostream Stream(Err);
+    DiagnosticPrinterRawOStream DP(Stream);
+    if (Linker::LinkModules(Dst, M, [&](const DiagnosticInfo &DI) { DI.print(DP); })) {
+#else
+    if (Linker::LinkModules(Dst, M, Linker::DestroySource, &Err)) {
+#endif
+        LLVMRustSetLastError(Err.c_str());
+        delete M;
+        return false;
+    }
+    delete M;
+    return true;
+}
+
+extern "C" bool
+LLVMRustHasFunctionAttrString(LLVMValueRef fn, const char *Name) {
+    Function *F = unwrap<Function>(fn);
+    return F->getAttributes().hasAttribute(AttributeSet::FunctionIndex, Name);
+}
+
+// The stack space a value of type `Ty` takes if it is spilled or passed on
+// the stack.
+static uint64_t
+StackSlotSize(const DataLayout &DL, Type *Ty) {
+    if (!Ty->isSized())
+        return 0;
+    return std::max<uint64_t>(DL.getTypeAllocSize(Ty), DL.getPointerSize());
+}
+
+// The space a value takes in the outgoing arguments of a call: all of the
+// pointee of a byval or inalloca argument, which is copied there, and a stack
+// slot for any other.
+static uint64_t
+CallArgSize(const DataLayout &DL, CallSite CS, unsigned ArgNo) {
+    Value *Arg = CS.getArgument(ArgNo);
+    if (!CS.isByValOrInAllocaArgument(ArgNo))
+        return StackSlotSize(DL, Arg->getType());
+    Type *Ty = cast<PointerType>(Arg->getType())->getElementType();
+    return DL.getTypeAllocSize(Ty) + DL.getPrefTypeAlignment(Ty);
+}
+
+// An estimate of the size of the stack frame code generation gives `fn`,
+// made to err on the large side, as LLVM can't tell the size before the frame
+// is laid out: its allocas, each padded by its alignment, plus the outgoing
+// arguments of every call it makes, as if none shared the stack, and two
+// stack slots for each of its arguments and each value it computes, as if
+// every one of them were spilled, and spilled again after its live range was
+// split. The return address, callee-saved registers and the alignment of the
+// frame itself aren't counted. Allocas of a size only known at runtime are
+// counted as one element; rustc doesn't emit any.
+extern "C" uint64_t
+LLVMRustFrameSizeEstimate(LLVMValueRef fn) {
+    Function *F = unwrap<Function>(fn);
+    DataLayout DL(F->getParent());
+    uint64_t Size = 0;
+    for (Function::arg_iterator A = F->arg_begin(), AE = F->arg_end(); A != AE; ++A)
+        Size += 2 * StackSlotSize(DL, A->getType());
+    for (Function::iterator B = F->begin(), BE = F->end(); B != BE; ++B) {
+        for (BasicBlock::iterator I = B->begin(), IE = B->end(); I != IE; ++I) {
+            if (AllocaInst *AI = dyn_cast<AllocaInst>(&*I)) {
+                uint64_t Count = 1;
+                if (ConstantInt *C = dyn_cast<ConstantInt>(AI->getArraySize()))
+                    Count = C->getZExtValue();
+                Size += Count * DL.getTypeAllocSize(AI->getAllocatedType()) +
+                        AI->getAlignment();
+            } else {
+                Size += 2 * StackSlotSize(DL, I->getType());
+            }
+            CallSite CS(&*I);
+            if (!CS)
+                continue;
+            for (unsigned ArgNo = 0, NumArgs = CS.arg_size(); ArgNo != NumArgs; ++ArgNo)
+                Size += CallArgSize(DL, CS, ArgNo);
+        }
+    }
+    return Size;
+}
+
+// Moves the body of `fn` into a new internal function named `name`, and
+// leaves `fn` calling it with its own arguments. Returns that call, so that
+// code inserted before it runs before the body's stack frame is set up.
+extern "C" LLVMValueRef
+LLVMRustOutlineFunctionBody(LLVMValueRef fn, const char *name) {
+    Function *F = unwrap<Function>(fn);
+    Function *Body = Function::Create(F->getFunctionType(),
+                                      GlobalValue::InternalLinkage,
+                                      name, F->getParent());
+    Body->copyAttributesFrom(F);
+    Body->setVisibility(GlobalValue::DefaultVisibility);
+    Body->setDLLStorageClass(GlobalValue::DefaultStorageClass);
+    Body->removeFnAttr(Attribute::AlwaysInline);
+    Body->addFnAttr(Attribute::NoInline);
+    Body->getBasicBlockList().splice(Body->begin(), F->getBasicBlockList());
+
+    std::vector<Value*> Args;
+    Function::arg_iterator NA = Body->arg_begin();
+    for (Function::arg_iterator A = F->arg_begin(), AE = F->arg_end(); A != AE; ++A, ++NA) {
+        A->replaceAllUsesWith(&*NA);
+        NA->takeName(&*A);
+        Args.push_back(&*A);
+    }
+
+    BasicBlock *BB = BasicBlock::Create(F->getContext(), "start", F);
+    CallInst *Call = CallInst::Create(Body, Args, "", BB);
+    Call->setCallingConv(Body->getCallingConv());
+    Call->setAttributes(Body->getAttributes());
+    if (F->getReturnType()->isVoidTy())
+        ReturnInst::Create(F->getContext(), BB);
+    else
+        ReturnInst::Create(F->getContext(), Call, BB);
+    return wrap(Call);
+}
+
 extern "C" void*
 LLVMRustOpenArchive(char *path) {
     ErrorOr<std::unique_ptr<MemoryBuffer>> buf_or = MemoryBuffer::getFile(path,
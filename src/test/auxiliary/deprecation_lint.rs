@@ -0,0 +1,34 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![crate_type = "lib"]
+
+#[deprecated(since = "1.2.0", note = "too slow", suggestion = "new_frob")]
+pub fn old_frob() {}
+
+pub fn new_frob() {}
+
+#[deprecated(since = "0.9.0")]
+pub struct OldConfig;
+
+#[deprecated(since = "1.0.0", note = "the whole module is going away")]
+pub mod legacy {
+    pub fn helper() {}
+}
+
+pub struct Widget;
+
+impl Widget {
+    #[deprecated(since = "1.1.0", suggestion = "Widget::render")]
+    pub fn draw(&self) {}
+
+    pub fn render(&self) {}
+}
@@ -0,0 +1,20 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[link(name = "foo", whole_archive)] //~ ERROR: `whole_archive` can only be given for
+#[link(name = "foo", kind = "static", whole_archive)]
+#[link(name = "foo", cfg())] //~ ERROR: `cfg()` must have exactly one argument
+#[link(name = "foo", cfg(unix, windows))] //~ ERROR: `cfg()` must have exactly one argument
+#[link(arg = "--foo", name = "foo")] //~ ERROR: can't give both `arg` and `name`
+#[link(arg)] //~ ERROR: `arg` must be given as `arg = "..."`
+#[link(arg = "--foo", cfg(unix))]
+extern {}
+
+fn main() {}
@@ -0,0 +1,40 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// aux-build:deprecation_lint.rs
+
+// Check that `#[deprecated]` works outside of the standard library, both
+// within a crate and across crates.
+
+#![deny(deprecated)]
+#![allow(dead_code)]
+
+extern crate deprecation_lint;
+
+use deprecation_lint::*;
+
+#[deprecated(since = "2.0.0", note = "local deprecation")]
+fn local_old() {}
+
+fn main() {
+    old_frob(); //~ ERROR too slow (deprecated since 1.2.0); use `new_frob` instead
+    new_frob();
+
+    let _ = OldConfig; //~ ERROR use of deprecated item (deprecated since 0.9.0)
+
+    legacy::helper(); //~ ERROR use of deprecated item: the whole module is going away
+
+    let w = Widget;
+    w.draw(); //~ ERROR use `Widget::render` instead
+    w.render();
+
+    local_old(); //~ ERROR use of deprecated item: local deprecation
+}
@@ -0,0 +1,25 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[deprecated] //~ ERROR incorrect deprecated attribute type
+fn f1() { }
+
+#[deprecated(note = "no version")] //~ ERROR missing 'since'
+fn f2() { }
+
+#[deprecated(since = "1.0.0", reason = "wrong key")] //~ ERROR unknown meta item `reason`
+fn f3() { }
+
+#[deprecated(since = "1.0.0")]
+#[deprecated(since = "1.0.1")]
+fn f4() { } //~ ERROR multiple deprecated attributes
+
+fn main() { }
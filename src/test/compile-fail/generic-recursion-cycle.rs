@@ -0,0 +1,38 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Check that polymorphic recursion through several functions and methods is
+// reported before translation, along with the cycle of calls.
+
+struct Wrapper<T>(T);
+
+impl<T> Wrapper<T> {
+    fn wrap<U>(&self, u: U) {
+        ping(Some(u)); //~ ERROR recurses infinitely
+    }
+}
+
+fn ping<A>(a: A) {
+    pong(a); //~ NOTE `ping` calls `pong` here
+}
+
+fn pong<B>(b: B) {
+    Wrapper(()).wrap(vec![b]); //~ NOTE `pong` calls
+}
+
+// Recursion that does not grow the type arguments is fine.
+fn countdown<T: Clone>(t: T, n: u32) {
+    if n > 0 { countdown(t.clone(), n - 1) }
+}
+
+fn main() {
+    ping(0u8);
+    countdown("hello", 3);
+}
@@ -8,13 +8,11 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-// error-pattern:reached the recursion limit during monomorphization
-
 // Verify the compiler fails with an error on infinite function
 // recursions.
 
 fn generic<T>() {
-    generic::<Option<T>>();
+    generic::<Option<T>>(); //~ ERROR recurses infinitely
 }
 
 
@@ -14,6 +14,7 @@
 
 trait Bar {
     fn bar<T>(&self, t: T);
+    //~^ NOTE method `bar` has generic type parameters
 }
 
 trait Quux {
@@ -24,13 +25,11 @@ trait Quux {
 fn make_bar<T:Bar>(t: &T) -> &Bar {
     t
         //~^ ERROR `Bar` is not object-safe
-        //~| NOTE method `bar` has generic type parameters
 }
 
 fn make_bar_explicit<T:Bar>(t: &T) -> &Bar {
     t as &Bar
         //~^ ERROR `Bar` is not object-safe
-        //~| NOTE method `bar` has generic type parameters
 }
 
 fn make_quux<T:Quux>(t: &T) -> &Quux {
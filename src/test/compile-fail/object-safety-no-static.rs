@@ -13,18 +13,17 @@
 
 trait Foo : ::std::marker::MarkerTrait {
     fn foo();
+    //~^ NOTE method `foo` has no receiver
 }
 
 fn foo_implicit<T:Foo+'static>(b: Box<T>) -> Box<Foo+'static> {
     b
         //~^ ERROR cannot convert to a trait object
-        //~| NOTE method `foo` has no receiver
 }
 
 fn foo_explicit<T:Foo+'static>(b: Box<T>) -> Box<Foo+'static> {
     b as Box<Foo>
         //~^ ERROR cannot convert to a trait object
-        //~| NOTE method `foo` has no receiver
 }
 
 fn main() {
@@ -11,20 +11,18 @@
 // Check that we correctly prevent users from making trait objects
 // from traits where `Self : Sized`.
 
-trait Bar : Sized {
+trait Bar : Sized { //~ NOTE the trait cannot require that `Self : Sized`
     fn bar<T>(&self, t: T);
 }
 
 fn make_bar<T:Bar>(t: &T) -> &Bar {
     t
         //~^ ERROR `Bar` is not object-safe
-        //~| NOTE the trait cannot require that `Self : Sized`
 }
 
 fn make_bar_explicit<T:Bar>(t: &T) -> &Bar {
     t as &Bar
         //~^ ERROR `Bar` is not object-safe
-        //~| NOTE the trait cannot require that `Self : Sized`
 }
 
 fn main() {
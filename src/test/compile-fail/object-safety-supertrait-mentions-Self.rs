@@ -16,6 +16,7 @@ trait Bar<T> {
 }
 
 trait Baz : Bar<Self> {
+//~^ NOTE the trait cannot use `Self` as a type parameter in the supertrait listing
 }
 
 fn make_bar<T:Bar<u32>>(t: &T) -> &Bar<u32> {
@@ -25,7 +26,6 @@ fn make_bar<T:Bar<u32>>(t: &T) -> &Bar<u32> {
 fn make_baz<T:Baz>(t: &T) -> &Baz {
     t
         //~^ ERROR `Baz` is not object-safe
-        //~| NOTE the trait cannot use `Self` as a type parameter in the supertrait listing
 }
 
 fn main() {
@@ -0,0 +1,21 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// aux-build:panic_abort_lib.rs
+
+// A crate that unwinds cannot link against one compiled without landing
+// pads.
+
+extern crate panic_abort_lib;
+//~^ ERROR the crate `panic_abort_lib` is compiled with the panic strategy `abort`
+
+fn main() {
+    panic_abort_lib::frob();
+}
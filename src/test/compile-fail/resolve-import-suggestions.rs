@@ -0,0 +1,49 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Check that unresolved imports suggest items of the same or a
+// similar name found elsewhere in the module graph, and point out items
+// that exist but are private.
+
+mod outer {
+    pub mod inner {
+        pub struct Widget;
+        pub fn frobnicate() {}
+    }
+
+    mod hidden {
+        pub struct Gadget;
+    }
+
+    fn secret() {}
+}
+
+use outer::Widget;
+//~^ ERROR unresolved import `outer::Widget`
+//~| HELP did you mean `outer::inner::Widget`?
+
+use outer::inner::frobnicat;
+//~^ ERROR unresolved import `outer::inner::frobnicat`
+//~| HELP did you mean `outer::inner::frobnicate`?
+
+use outer::Gadget;
+//~^ ERROR unresolved import `outer::Gadget`
+//~| NOTE `outer::hidden::Gadget` exists but is not accessible, because `outer::hidden` is private
+
+use outer::inner::secret;
+//~^ ERROR unresolved import `outer::inner::secret`
+//~| NOTE `outer::secret` exists but is private
+
+// A glob import names no item, so nothing is suggested for it.
+use outer::inne::*;
+//~^ ERROR unresolved import `outer::inne::*`
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Check that unresolved names and types suggest the paths of items with the
+// same name elsewhere in the module graph.
+
+mod shapes {
+    pub mod round {
+        pub struct Circle;
+        pub fn area() -> f64 { 0.0 }
+    }
+}
+
+fn main() {
+    let _ = area();
+    //~^ ERROR unresolved name `area`
+    //~| HELP did you mean `shapes::round::area`?
+    let _: Circle = shapes::round::Circle;
+    //~^ ERROR use of undeclared type name `Circle`
+    //~| HELP did you mean `shapes::round::Circle`?
+}
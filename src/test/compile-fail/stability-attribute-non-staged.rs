@@ -14,5 +14,4 @@
 
 #[unstable] //~ WARNING: stability attributes are deprecated
 #[stable] //~ WARNING: stability attributes are deprecated
-#[deprecated] //~ WARNING: stability attributes are deprecated
 fn main() { }
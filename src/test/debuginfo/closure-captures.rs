@@ -0,0 +1,58 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-lldb
+
+// compile-flags:-g
+
+// Closures are described as structs with one member per captured variable,
+// named after that variable.
+
+// gdb-command:run
+
+// gdb-command:print by_value
+// gdb-check:$1 = {variable = 1, a_struct = {a = -3, b = 4.5, c = 5}}
+// gdb-command:print *by_ref.variable
+// gdb-check:$2 = 1
+// gdb-command:print *by_ref.a_struct
+// gdb-check:$3 = {a = -3, b = 4.5, c = 5}
+// gdb-command:print no_captures
+// gdb-check:$4 = {<No data fields>}
+
+#![allow(unused_variables)]
+#![omit_gdb_pretty_printer_section]
+
+#[derive(Clone, Copy)]
+struct Struct {
+    a: isize,
+    b: f64,
+    c: usize
+}
+
+fn main() {
+    let variable = 1;
+    let a_struct = Struct {
+        a: -3,
+        b: 4.5,
+        c: 5
+    };
+
+    let by_value = move || variable + a_struct.a;
+    let by_ref = || variable + a_struct.a;
+    let no_captures = || 0;
+
+    zzz(); // #break
+
+    by_value();
+    by_ref();
+    no_captures();
+}
+
+fn zzz() {()}
@@ -0,0 +1,64 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-lldb
+
+// compile-flags:-g
+
+// Enums with a discriminant have a `discriminant` member whose type
+// enumerates the variant names, which gdb can print without the pretty
+// printers.
+
+// gdb-command:run
+
+// gdb-command:print first.discriminant
+// gdb-check:$1 = First
+
+// gdb-command:print second.discriminant
+// gdb-check:$2 = Second
+
+// gdb-command:print third.discriminant
+// gdb-check:$3 = Third
+
+// gdb-command:print/d third.discriminant
+// gdb-check:$4 = 2
+
+// gdb-command:print boxed.discriminant
+// gdb-check:$5 = Second
+
+// gdb-command:print nested.inner.discriminant
+// gdb-check:$6 = First
+
+#![allow(unused_variables)]
+#![omit_gdb_pretty_printer_section]
+
+use self::Shape::{First, Second, Third};
+
+enum Shape {
+    First(u32),
+    Second { a: u64, b: u16 },
+    Third(u8, u8),
+}
+
+struct Nested {
+    inner: Shape,
+}
+
+fn main() {
+    let first = First(1);
+    let second = Second { a: 2, b: 3 };
+    let third = Third(4, 5);
+    let boxed = Box::new(Second { a: 6, b: 7 });
+    let nested = Nested { inner: First(8) };
+
+    zzz(); // #break
+}
+
+fn zzz() {()}
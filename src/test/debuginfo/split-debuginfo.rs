@@ -0,0 +1,47 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-lldb
+// ignore-windows
+// ignore-macos
+
+// compile-flags:-g -C split-debuginfo
+
+// The executable only has a debuglink to the split-out debug info, which gdb
+// has to follow to find the variables below.
+
+// gdb-command:run
+
+// gdb-command:print a_struct
+// gdb-check:$1 = {a = -3, b = 4.5, c = 5}
+// gdb-command:print variable
+// gdb-check:$2 = 1
+
+#![allow(unused_variables)]
+#![omit_gdb_pretty_printer_section]
+
+struct Struct {
+    a: isize,
+    b: f64,
+    c: usize
+}
+
+fn main() {
+    let variable = 1;
+    let a_struct = Struct {
+        a: -3,
+        b: 4.5,
+        c: 5
+    };
+
+    zzz(); // #break
+}
+
+fn zzz() {()}
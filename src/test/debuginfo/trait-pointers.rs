@@ -11,7 +11,22 @@
 // min-lldb-version: 310
 
 // compile-flags:-g
+
 // gdb-command:run
+
+// gdb-command:print reference
+// gdb-check:$1 = {pointer = [...], vtable = [...]}
+// gdb-command:print (void *)reference.pointer == (void *)&stack_struct
+// gdb-check:$2 = 1
+// gdb-command:print reference.vtable == unique.vtable
+// gdb-check:$3 = 1
+// gdb-command:print reference.vtable == other_reference.vtable
+// gdb-check:$4 = 0
+// gdb-command:print *(struct Struct *)reference.pointer
+// gdb-check:$5 = {a = 0, b = 1}
+// gdb-command:print *(struct Struct *)unique.pointer
+// gdb-check:$6 = {a = 2, b = 3}
+
 // lldb-command:run
 
 #![allow(unused_variables)]
@@ -28,10 +43,14 @@ struct Struct {
 }
 
 impl Trait for Struct {}
+impl Trait for isize {}
 
-// There is no real test here yet. Just make sure that it compiles without crashing.
 fn main() {
     let stack_struct = Struct { a:0, b: 1.0 };
     let reference: &Trait = &stack_struct as &Trait;
     let unique: Box<Trait> = box Struct { a:2, b: 3.0 } as Box<Trait>;
+    let other_reference: &Trait = &0_isize as &Trait;
+    zzz(); // #break
 }
+
+fn zzz() {()}
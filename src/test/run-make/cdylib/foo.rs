@@ -0,0 +1,29 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![crate_type = "cdylib"]
+
+extern crate bar;
+
+#[no_mangle]
+pub static FOO_VERSION: u32 = 1;
+
+#[export_name = "foo_patch_level"]
+pub static FOO_PATCH_LEVEL: u32 = 0;
+
+#[no_mangle]
+pub extern "C" fn foo(x: i32) -> i32 {
+    not_exported(bar::double(x))
+}
+
+pub fn not_exported(x: i32) -> i32 {
+    x + 1
+}
@@ -0,0 +1,12 @@
+// ignore-license
+#include <stdint.h>
+
+extern uint32_t FOO_VERSION;
+int32_t foo(int32_t);
+
+int main() {
+    if (FOO_VERSION != 1) {
+        return 1;
+    }
+    return foo(20) == 41 ? 0 : 1;
+}
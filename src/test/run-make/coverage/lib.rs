@@ -0,0 +1,31 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[cfg(lto)]
+extern crate dep;
+
+pub fn covered(x: u32) -> u32 {
+    x + 1 // covered
+}
+
+pub fn uncovered(x: u32) -> u32 {
+    x + 2 // uncovered
+}
+
+#[test]
+fn test_covered() {
+    assert_eq!(covered(0), 1);
+}
+
+#[cfg(lto)]
+#[test]
+fn test_dep() {
+    assert_eq!(dep::double(covered(0)), 2);
+}
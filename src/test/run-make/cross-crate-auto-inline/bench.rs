@@ -0,0 +1,32 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Calls the small leaf functions of `leaf` in loops, which are only inlined
+// when their bodies were exported. The test only checks the IR; to compare
+// timings, run it with `--bench` against `leaf` built with and without
+// `-C cross-crate-inline-threshold=0`.
+
+#![feature(test)]
+
+extern crate leaf;
+extern crate test;
+
+use test::{Bencher, black_box};
+
+#[bench]
+fn point_x(b: &mut Bencher) {
+    let points = (0..1000).map(leaf::Point::new).collect::<Vec<_>>();
+    b.iter(|| black_box(&points).iter().fold(0, |sum, p| sum + p.x()));
+}
+
+#[bench]
+fn answer(b: &mut Bencher) {
+    b.iter(|| (0..black_box(1000)).fold(0, |sum, _| sum + leaf::answer()));
+}
@@ -0,0 +1,35 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+static SECRET: u32 = 7;
+
+pub struct Point {
+    x: i32,
+}
+
+impl Point {
+    pub fn new(x: i32) -> Point { Point { x: x } }
+
+    pub fn x(&self) -> i32 { self.x }
+}
+
+// Small leaf functions have their bodies exported, even when they use
+// private items.
+pub fn answer() -> u32 { 42 }
+
+pub fn secret() -> u32 { SECRET }
+
+// Functions that call other functions aren't exported.
+pub fn twice_answer() -> u32 { answer() + answer() }
+
+#[inline(never)]
+pub fn never() -> u32 { 1 }
@@ -0,0 +1,19 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate leaf;
+
+fn main() {
+    assert_eq!(leaf::answer(), 42);
+    assert_eq!(leaf::secret(), 7);
+    assert_eq!(leaf::twice_answer(), 84);
+    assert_eq!(leaf::never(), 1);
+    assert_eq!(leaf::Point::new(3).x(), 3);
+}
@@ -0,0 +1,8 @@
+// ignore-license
+int get_answer(void) {
+    return 1;
+}
+
+int __wrap_get_answer(void) {
+    return 42;
+}
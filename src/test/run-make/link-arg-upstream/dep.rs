@@ -0,0 +1,23 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![crate_type = "rlib"]
+
+#[link(name = "answer", kind = "static")]
+#[link(arg = "--wrap=get_answer")]
+#[link(arg = "--no-such-option", cfg(not(unix)))]
+extern {
+    fn get_answer() -> i32;
+}
+
+pub fn answer() -> i32 {
+    unsafe { get_answer() }
+}
@@ -0,0 +1,4 @@
+// ignore-license
+int my_function() {
+    return 2;
+}
@@ -0,0 +1,7 @@
+// ignore-license
+extern int REGISTERED;
+
+__attribute__((constructor))
+static void register_ctor(void) {
+    REGISTERED = 1;
+}
@@ -0,0 +1,8 @@
+// ignore-license
+#include <stdint.h>
+
+int32_t answer(void);
+
+int main() {
+    return answer() == 42 ? 0 : 1;
+}
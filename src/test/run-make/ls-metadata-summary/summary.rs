@@ -0,0 +1,30 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+#[macro_export]
+macro_rules! answer { () => (42) }
+
+pub struct Point { pub x: i32, pub y: i32 }
+
+pub mod shapes {
+    #[inline]
+    pub fn origin() -> ::Point { ::Point { x: 0, y: 0 } }
+
+    pub fn area() -> i32 { 0 }
+
+    fn hidden() {}
+}
+
+#[link(name = "m")]
+extern {
+    pub fn cos(x: f64) -> f64;
+}
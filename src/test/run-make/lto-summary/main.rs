@@ -0,0 +1,28 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+extern crate lib;
+
+use std::env;
+
+fn main() {
+    let x = env::args().count() as u32;
+    assert_eq!(lib::add_one(x), x + 1);
+    if x > 100 {
+        unlikely(x);
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn unlikely(x: u32) {
+    assert_eq!(lib::add_two(x), x + 2);
+}
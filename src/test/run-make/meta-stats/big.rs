@@ -0,0 +1,21 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+macro_rules! unused_fns {
+    ($($name:ident)*) => ($(pub fn $name() -> u32 { 0 })*)
+}
+
+pub mod unused {
+    unused_fns!(a b c d e f g h i j k l m n o p q r s t u v w x y z);
+}
+
+pub fn answer() -> u32 { 42 }
@@ -0,0 +1,22 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::thread;
+
+fn main() {
+    // Threads and unwinding both come from the statically linked runtime.
+    let result = thread::spawn(|| {
+        panic!("unwinding out of a thread");
+    }).join();
+    assert!(result.is_err());
+
+    let s = thread::spawn(|| (1..101u64).fold(0, |a, b| a + b)).join().unwrap();
+    assert_eq!(s, 5050);
+}
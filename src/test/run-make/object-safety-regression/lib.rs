@@ -0,0 +1,29 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+pub trait Foo {
+    fn foo(&self);
+}
+
+pub trait Bar {
+    #[cfg(before)]
+    fn bar(&self);
+    #[cfg(after)]
+    fn bar<T>(&self, t: T);
+}
+
+trait Private {
+    #[cfg(before)]
+    fn private(&self);
+    #[cfg(after)]
+    fn private(&self) -> Self;
+}
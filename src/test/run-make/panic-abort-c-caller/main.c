@@ -0,0 +1,7 @@
+// ignore-license
+void foo();
+
+int main() {
+    foo();
+    return 0;
+}
@@ -0,0 +1,37 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[inline(never)]
+fn collatz(mut n: u64) -> u32 {
+    let mut steps = 0;
+    while n != 1 {
+        if n % 2 == 0 {
+            n /= 2;
+        } else {
+            n = 3 * n + 1;
+        }
+        steps += 1;
+    }
+    steps
+}
+
+#[inline(never)]
+fn never_called() -> u32 {
+    if collatz(27) > 100 { 1 } else { 2 }
+}
+
+fn main() {
+    let total: u32 = (1..1000).map(collatz).fold(0, |a, b| a + b);
+    assert_eq!(total, 59431);
+    if std::env::args().count() > 5 {
+        println!("{}", never_called());
+    }
+}
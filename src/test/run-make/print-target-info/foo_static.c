@@ -0,0 +1,2 @@
+// ignore-license
+void foo_static() {}
@@ -0,0 +1,26 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+pub struct Padded {
+    pub a: u8,
+    pub b: u32,
+    pub c: u16,
+}
+
+pub enum Shape {
+    Dot,
+    Line(u8, u32),
+}
+
+pub fn make() -> (Padded, Shape) {
+    (Padded { a: 1, b: 2, c: 3 }, Shape::Line(4, 5))
+}
@@ -0,0 +1,40 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+pub struct Covariant<'a, T: 'a> {
+    field: &'a T,
+}
+
+pub struct Contravariant<T> {
+    field: fn(T),
+}
+
+pub struct Invariant<'a, T> {
+    field: Cell<&'a T>,
+}
+
+pub enum Shared<T> {
+    One(Rc<T>),
+    None,
+}
+
+// Not generic, so not listed.
+pub struct Plain;
+
+// Not exported, so not listed.
+struct Private<T> {
+    field: T,
+}
@@ -0,0 +1,22 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![crate_type = "rlib"]
+
+#[inline(never)]
+pub fn generic<T>(t: T) -> T {
+    t
+}
+
+pub fn instantiate() {
+    generic(1i32);
+    generic(vec![1u8]);
+    generic(Some(Some(Some(Some(Some(Some(Some(Some(1u8)))))))));
+}
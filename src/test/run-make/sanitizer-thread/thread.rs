@@ -0,0 +1,23 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Two threads write to the same static without synchronization.
+
+use std::thread;
+
+static mut COUNTER: usize = 0;
+
+fn main() {
+    let child = thread::spawn(|| {
+        unsafe { COUNTER += 1; }
+    });
+    unsafe { COUNTER += 1; }
+    child.join().unwrap();
+}
@@ -24,6 +24,11 @@ fn foo() {
     }
 }
 
+#[inline(never)]
+fn generic<T>(_t: T) {
+    foo();
+}
+
 #[inline(never)]
 fn double() {
     struct Double;
@@ -51,7 +56,7 @@ fn runtest(me: &str) {
     let out = p.wait_with_output().unwrap();
     assert!(!out.status.success());
     let s = str::from_utf8(&out.stderr).unwrap();
-    assert!(s.contains("stack backtrace") && s.contains("foo::h"),
+    assert!(s.contains("stack backtrace") && s.contains("backtrace::foo"),
             "bad output: {}", s);
 
     // Make sure the stack trace is *not* printed
@@ -61,17 +66,25 @@ fn runtest(me: &str) {
     let out = p.wait_with_output().unwrap();
     assert!(!out.status.success());
     let s = str::from_utf8(&out.stderr).unwrap();
-    assert!(!s.contains("stack backtrace") && !s.contains("foo::h"),
+    assert!(!s.contains("stack backtrace") && !s.contains("backtrace::foo"),
             "bad output2: {}", s);
 
+    // Make sure instantiations of generic functions show their type parameters
+    let p = template(me).arg("generic-fail").env("RUST_BACKTRACE", "1").spawn().unwrap();
+    let out = p.wait_with_output().unwrap();
+    assert!(!out.status.success());
+    let s = str::from_utf8(&out.stderr).unwrap();
+    assert!(s.contains("backtrace::generic::<u8>"),
+            "bad output5: {}", s);
+
     // Make sure a stack trace is printed
     let p = template(me).arg("double-fail").spawn().unwrap();
     let out = p.wait_with_output().unwrap();
     assert!(!out.status.success());
     let s = str::from_utf8(&out.stderr).unwrap();
-    // loosened the following from double::h to double:: due to
+    // loosened the following from double::h to double due to
     // spurious failures on mac, 32bit, optimized
-    assert!(s.contains("stack backtrace") && s.contains("double::"),
+    assert!(s.contains("stack backtrace") && s.contains("backtrace::double"),
             "bad output3: {}", s);
 
     // Make sure a stack trace isn't printed too many times
@@ -92,6 +105,8 @@ fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() >= 2 && args[1] == "fail" {
         foo();
+    } else if args.len() >= 2 && args[1] == "generic-fail" {
+        generic(1u8);
     } else if args.len() >= 2 && args[1] == "double-fail" {
         double();
     } else {
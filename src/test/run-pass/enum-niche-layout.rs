@@ -0,0 +1,75 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Enums with an empty case use invalid values of any field, not just null
+// pointers, to tell their cases apart.
+
+use std::mem::size_of;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Colour { Red, Green, Blue }
+
+#[derive(PartialEq, Debug)]
+enum Shape { Dot(u8), Line(u8, u8) }
+
+#[derive(PartialEq, Debug)]
+struct Flagged { flag: bool, value: u16 }
+
+static NONE_BOOL: Option<bool> = None;
+static SOME_CHAR: Option<char> = Some('x');
+static NONE_NESTED: Option<Option<bool>> = Some(None);
+
+fn unwrap_or<T>(opt: Option<T>, default: T) -> T {
+    match opt {
+        Some(x) => x,
+        None => default,
+    }
+}
+
+pub fn main() {
+    assert_eq!(size_of::<Option<bool>>(), 1);
+    assert_eq!(size_of::<Option<char>>(), 4);
+    assert_eq!(size_of::<Option<Option<bool>>>(), 1);
+    assert_eq!(size_of::<Option<Option<Option<char>>>>(), 4);
+    assert_eq!(size_of::<Option<Colour>>(), size_of::<Colour>());
+    assert_eq!(size_of::<Option<Shape>>(), size_of::<Shape>());
+    assert_eq!(size_of::<Option<Flagged>>(), size_of::<Flagged>());
+    assert_eq!(size_of::<Option<(u32, bool)>>(), size_of::<(u32, bool)>());
+    assert_eq!(size_of::<Option<[bool; 3]>>(), 3);
+
+    assert_eq!(unwrap_or(Some(false), true), false);
+    assert_eq!(unwrap_or(None, true), true);
+    assert_eq!(unwrap_or(Some('\u{10FFFF}'), 'a'), '\u{10FFFF}');
+    assert_eq!(unwrap_or(None, 'a'), 'a');
+    assert_eq!(unwrap_or(Some(Colour::Blue), Colour::Red), Colour::Blue);
+    assert_eq!(unwrap_or(None, Colour::Green), Colour::Green);
+    assert_eq!(unwrap_or(Some(Shape::Line(1, 2)), Shape::Dot(0)), Shape::Line(1, 2));
+    assert_eq!(unwrap_or(None, Shape::Dot(3)), Shape::Dot(3));
+
+    let flagged = Some(Flagged { flag: true, value: 7 });
+    assert_eq!(flagged, Some(Flagged { flag: true, value: 7 }));
+    assert!(flagged != None);
+
+    let nested: Vec<Option<Option<bool>>> = vec![None, Some(None), Some(Some(false)),
+                                                 Some(Some(true))];
+    let names: Vec<_> = nested.iter().map(|x| {
+        match *x {
+            None => "none",
+            Some(None) => "some none",
+            Some(Some(false)) => "false",
+            Some(Some(true)) => "true",
+        }
+    }).collect();
+    assert_eq!(names, ["none", "some none", "false", "true"]);
+
+    assert_eq!(NONE_BOOL, None);
+    assert_eq!(SOME_CHAR, Some('x'));
+    assert_eq!(NONE_NESTED, Some(None));
+}
@@ -0,0 +1,48 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// Recursion whose type arguments go through an associated type is not
+// reported as infinite: the projection normalizes to a smaller type, so
+// the chain of instantiations ends.
+
+trait Peel {
+    type Inner: Peel;
+    fn depth() -> u32;
+}
+
+impl Peel for () {
+    type Inner = ();
+    fn depth() -> u32 { 0 }
+}
+
+impl<T: Peel> Peel for Box<T> {
+    type Inner = T;
+    fn depth() -> u32 { 1 + T::depth() }
+}
+
+fn count<T: Peel>(n: u32) -> u32 {
+    if n == 0 { T::depth() } else { count::<T::Inner>(n - 1) }
+}
+
+fn next<I: Iterator>(mut it: I) -> Option<I::Item> {
+    it.next()
+}
+
+fn first_of_first<I>(it: I) -> Option<u8>
+    where I: Iterator, I::Item: Iterator<Item=u8>
+{
+    next(it).and_then(next::<I::Item>)
+}
+
+fn main() {
+    assert_eq!(count::<Box<Box<()>>>(1), 1);
+    assert_eq!(first_of_first(vec![vec![7u8]].into_iter()), Some(7));
+}
@@ -0,0 +1,32 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Polymorphic recursion in generic functions that are never instantiated
+// is never monomorphized, so it is not reported.
+
+#![allow(dead_code)]
+
+fn generic<T>() {
+    generic::<Option<T>>();
+}
+
+struct Nested<T>(T);
+
+impl<T> Nested<T> {
+    fn deeper(self) {
+        Nested(self).deeper();
+    }
+}
+
+pub fn exported<T>(t: T) {
+    exported(vec![t]);
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -C panic=abort
+
+// Check that with `-C panic=abort` a panic prints its message and then
+// aborts the process, without running destructors.
+
+use std::env;
+use std::process::Command;
+
+struct Noisy;
+
+impl Drop for Noisy {
+    fn drop(&mut self) { println!("dropped"); }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() > 1 && args[1] == "test" {
+        let _noisy = Noisy;
+        panic!("going down");
+    }
+
+    let output = Command::new(&args[0]).arg("test").output().unwrap();
+    assert!(!output.status.success());
+    // A panic that unwinds out of `main` exits with status 101.
+    assert!(output.status.code() != Some(101));
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stdout.contains("dropped"));
+    assert!(stderr.contains("going down"));
+}
@@ -0,0 +1,123 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// ignore-android
+// ignore-bitrig
+// ignore-dragonfly
+// ignore-freebsd
+// ignore-ios
+// ignore-macos
+// ignore-openbsd
+// ignore-windows
+// ignore-aarch64
+// ignore-arm
+// ignore-mips
+// ignore-powerpc
+// ignore-x86
+
+// Stack frames larger than the guard page are probed, so an overflow in a
+// function with one is still reported instead of skipping the guard page.
+// The frames here are made up of one big alloca, of spill slots, and of the
+// locals of inlined calls.
+
+#![feature(asm)]
+
+use std::env;
+use std::process::Command;
+use std::thread;
+
+#[inline(always)]
+pub fn black_box<T>(dummy: T) { unsafe { asm!("" : : "r"(&dummy)) } }
+
+// Returns `n` without LLVM knowing it.
+#[inline(never)]
+fn opaque(mut n: u64) -> u64 {
+    unsafe { asm!("" : "+r"(n) : : : "volatile") }
+    n
+}
+
+// Initializing `local` writes its 64K from the bottom up, which jumps right
+// over the guard page unless the frame was probed first.
+fn recurse(array: &[u64; 8192]) {
+    let local = [array[0] + 1; 8192];
+    black_box(&local);
+    recurse(&local);
+    black_box(()); // don't optimize this into a tail call
+}
+
+// Computes 16 values for each `x`, which are all live across `$body`. The
+// body makes a call, and there are far fewer registers that survive a call
+// than values, so most of them get a stack slot of their own.
+macro_rules! live_across {
+    ($n:expr; ; $body:expr) => ($body);
+    ($n:expr; x $($xs:ident)*; $body:expr) => {{
+        let (a, b, c, d) = (opaque($n), opaque($n + 1), opaque($n + 2), opaque($n + 3));
+        let (e, f, g, h) = (opaque($n), opaque($n + 1), opaque($n + 2), opaque($n + 3));
+        let (i, j, k, l) = (opaque($n), opaque($n + 1), opaque($n + 2), opaque($n + 3));
+        let (m, o, p, q) = (opaque($n), opaque($n + 1), opaque($n + 2), opaque($n + 3));
+        let r = live_across!($n; $($xs)*; $body);
+        r + a + b + c + d + e + f + g + h + i + j + k + l + m + o + p + q
+    }}
+}
+
+// A frame of about 5K of spill slots, without any large alloca.
+fn spills(n: u64) -> u64 {
+    live_across!(n; x x x x x x x x x x x x x x x x x x x x
+                    x x x x x x x x x x x x x x x x x x x x;
+                 spills(n + 1))
+}
+
+#[inline(always)]
+fn chunk(n: u64) -> [u64; 384] {
+    [opaque(n); 384]
+}
+
+// Each `chunk` is inlined, leaving four arrays of less than a page each in
+// the frame, all live across the recursive call.
+fn inlined(n: u64) {
+    let (a, b, c, d) = (chunk(n), chunk(n + 1), chunk(n + 2), chunk(n + 3));
+    black_box(&a);
+    black_box(&b);
+    black_box(&c);
+    black_box(&d);
+    inlined(n + 1);
+    black_box(&a);
+    black_box(&b);
+    black_box(&c);
+    black_box(&d);
+}
+
+fn overflow(frame: &str) {
+    match frame {
+        "alloca" => recurse(&[0; 8192]),
+        "spills" => { spills(0); }
+        "inlined" => inlined(0),
+        _ => panic!("unknown frame {}", frame),
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() > 2 && args[1] == "main-thread" {
+        overflow(&args[2]);
+    } else if args.len() > 2 && args[1] == "child-thread" {
+        let frame = args[2].clone();
+        thread::spawn(move || overflow(&frame)).join().unwrap();
+    } else {
+        for frame in &["alloca", "spills", "inlined"] {
+            for arg in &["main-thread", "child-thread"] {
+                let out = Command::new(&args[0]).arg(arg).arg(frame).output().unwrap();
+                assert!(!out.status.success());
+                let error = String::from_utf8_lossy(&out.stderr);
+                assert!(error.contains("has overflowed its stack"), "{}: {}", frame, error);
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Fields of structs without a `#[repr]` are reordered to minimize padding,
+// while `#[repr(C)]` structs keep declaration order.
+
+use std::mem::{self, size_of};
+
+struct Padded {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+
+#[repr(C)]
+struct CPadded {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+
+struct Generic<T: ?Sized> {
+    a: u8,
+    b: u32,
+    last: T,
+}
+
+// Not generic, but its unsized last field must stay last all the same.
+struct Tail {
+    a: u8,
+    b: u32,
+    last: [u16],
+}
+
+struct WithDrop {
+    a: u8,
+    b: u64,
+    c: u8,
+}
+
+impl Drop for WithDrop {
+    fn drop(&mut self) {
+        assert_eq!((self.a, self.b, self.c), (1, 2, 3));
+    }
+}
+
+static PADDED: Padded = Padded { a: 1, b: 2, c: 3 };
+
+pub fn main() {
+    assert_eq!(size_of::<Padded>(), 8);
+    assert_eq!(size_of::<CPadded>(), 12);
+    assert_eq!(size_of::<Generic<u8>>(), 8);
+
+    let p = Padded { a: 1, b: 2, c: 3 };
+    assert_eq!((p.a, p.b, p.c), (1, 2, 3));
+    let Padded { a, b, c } = PADDED;
+    assert_eq!((a, b, c), (1, 2, 3));
+
+    let mut g = Generic { a: 4, b: 5, last: [6u8, 7] };
+    g.last[1] = 8;
+    assert_eq!((g.a, g.b, g.last), (4, 5, [6, 8]));
+
+    let unsized_last: &Generic<[u8]> = &g;
+    assert_eq!((unsized_last.a, unsized_last.b), (4, 5));
+    assert_eq!(&unsized_last.last, &[6, 8][..]);
+
+    let h = Generic { a: 9, b: 10, last: [11u16, 12, 13] };
+    let fat: &Generic<[u16]> = &h;
+    let tail: &Tail = unsafe { mem::transmute(fat) };
+    assert_eq!((tail.a, tail.b), (9, 10));
+    assert_eq!(&tail.last, &[11, 12, 13][..]);
+
+    let _d = WithDrop { a: 1, b: 2, c: 3 };
+}
@@ -0,0 +1,21 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+// @has deprecated/index.html '//tr[@class="deprecated module-item"]' 'old_frob'
+// @has deprecated/fn.old_frob.html '//*[@class="stability"]' \
+//      'Deprecated since 1.2.0: too slow Use new_frob instead.'
+#[deprecated(since = "1.2.0", note = "too slow", suggestion = "new_frob")]
+pub fn old_frob() {}
+
+// Items without `#[deprecated]` carry no stability marker at all outside
+// staged_api crates.
+// @!has deprecated/fn.new_frob.html '//*[@class="stability"]' ''
+pub fn new_frob() {}
@@ -0,0 +1,35 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+// @has variance/struct.Covariant.html '//*[@class="variance"]' 'Parameters'
+// @has - '//table//td' 'covariant'
+// @has - '//*[@class="auto-traits"]' 'this type is Send and is Sync'
+pub struct Covariant<'a, T: 'a> {
+    pub field: &'a T,
+}
+
+// @has variance/struct.Invariant.html '//table//td' 'invariant'
+// @has - '//*[@class="auto-traits"]' 'this type is Send and is not Sync'
+pub struct Invariant<T> {
+    pub field: Cell<T>,
+}
+
+// @has variance/enum.Shared.html '//*[@class="auto-traits"]' \
+//      'this type is not Send and is not Sync'
+pub enum Shared<T> {
+    One(Rc<T>),
+    None,
+}
+
+// @!has variance/struct.Plain.html '//*[@class="variance"]' 'Parameters'
+pub struct Plain;